@@ -0,0 +1,68 @@
+//! Compares matching candidate objects against a mapping's accessors (decoding
+//! its values for every candidate) with matching them against the `Filter` of
+//! `as_filter` (decoding its values once); run with `cargo bench --bench filter`.
+
+use pk11_uri_parser::{parse, CandidateAttributes, ObjectType};
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 1_000;
+const CANDIDATES: u8 = 200;
+
+fn bench(name: &str, mut match_candidates: impl FnMut() -> usize) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(match_candidates());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:>10}: {:?} per {CANDIDATES} candidates ({elapsed:?} total)",
+        elapsed / ITERATIONS
+    );
+}
+
+fn main() {
+    let pk11_uri = "pkcs11:object=Private%20key%20for%20Card%20Authentication;type=private;id=%69%95%3E%5C%F4%BD%EC%91";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+
+    let labels: Vec<String> = (0..CANDIDATES)
+        .map(|n| format!("Private key for Card Authentication {n}"))
+        .collect();
+    let ids: Vec<[u8; 8]> = (0..CANDIDATES)
+        .map(|n| [0x69, 0x95, 0x3E, 0x5C, 0xF4, 0xBD, 0xEC, n])
+        .collect();
+    let candidates: Vec<CandidateAttributes> = (0..usize::from(CANDIDATES))
+        .map(|n| CandidateAttributes {
+            class: 3, // CKO_PRIVATE_KEY
+            id: &ids[n],
+            label: labels[n].as_bytes(),
+        })
+        .collect();
+
+    bench("accessors", || {
+        candidates
+            .iter()
+            .filter(|candidate| {
+                let mapping = black_box(&mapping);
+                mapping.r#type().is_none_or(|r#type| {
+                    ObjectType::from_object_class(candidate.class)
+                        .is_some_and(|object_type| object_type.as_str() == r#type)
+                }) && mapping
+                    .id_bytes()
+                    .expect("id should decode")
+                    .is_none_or(|id| id == candidate.id)
+                    && mapping
+                        .object_lossy()
+                        .is_none_or(|label| label.as_bytes() == candidate.label)
+            })
+            .count()
+    });
+
+    bench("as_filter", || {
+        let filter = black_box(&mapping).as_filter();
+        candidates
+            .iter()
+            .filter(|candidate| filter.matches(candidate))
+            .count()
+    });
+}
@@ -0,0 +1,48 @@
+//! Compares repeated `normalize` calls with reusing a buffer via
+//! `normalize_into`; run with `cargo bench --bench normalize`.
+
+use pk11_uri_parser::{parse, PK11URIMapping};
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 100_000;
+
+const CORPUS: [&str; 4] = [
+    "pkcs11:token=The%20Software%20PKCS%2311%20Softtoken;manufacturer=Snake%20Oil,%20Inc.;model=1.0;object=my-certificate;type=cert;id=%69%95%3e%5c%f4%bd%ec%91;serial=?pin-source=file:/etc/token_pin",
+    "pkcs11:object=my-key;type=private?module-name=mypkcs11",
+    "pkcs11:v-attr=a?pin-source=|/usr/lib/pinomatic&v-attr=b&v-attr=c",
+    "pkcs11:",
+];
+
+fn bench(name: &str, mut normalize_corpus: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        normalize_corpus();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:>14}: {:?} per corpus ({elapsed:?} total)",
+        elapsed / ITERATIONS
+    );
+}
+
+fn main() {
+    let mappings: Vec<PK11URIMapping> = CORPUS
+        .iter()
+        .map(|pk11_uri| parse(pk11_uri).expect("mapping should be valid"))
+        .collect();
+
+    bench("normalize", || {
+        for mapping in &mappings {
+            black_box(black_box(mapping).normalize());
+        }
+    });
+
+    let mut buf = String::new();
+    bench("normalize_into", || {
+        for mapping in &mappings {
+            black_box(mapping).normalize_into(&mut buf);
+            black_box(&buf);
+        }
+    });
+}
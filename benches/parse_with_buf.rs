@@ -0,0 +1,49 @@
+//! Compares `parse` with `parse_with_buf` over a corpus of valid and invalid
+//! PKCS#11 URIs; run with `cargo bench --bench parse_with_buf`.
+
+use pk11_uri_parser::{parse, parse_with_buf};
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 100_000;
+
+const CORPUS: [&str; 8] = [
+    "pkcs11:token=The%20Software%20PKCS%2311%20Softtoken;manufacturer=Snake%20Oil,%20Inc.;model=1.0;object=my-certificate;type=cert;id=%69%95%3E%5C%F4%BD%EC%91;serial=?pin-source=file:/etc/token_pin",
+    "pkcs11:object=Private key for Card Authentication;pin-value=123456",
+    "pkcs11:token=my-token;
+            object=my-key;
+            type=privat",
+    "pkcs11:object=my-key?pin-source=file:/etc/token_pin&module-name=mypkcs11",
+    "pkcs11:object=my-key;token=a;token=b",
+    "pkcs11:slot-id=one",
+    "pkcs11:?pin-source=file:/etc/token_pin;module-name=mypkcs11",
+    "pkcs11:object=my-key;",
+];
+
+fn bench(name: &str, mut parse_corpus: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        parse_corpus();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:>14}: {:?} per corpus ({:?} total)",
+        elapsed / ITERATIONS,
+        elapsed
+    );
+}
+
+fn main() {
+    bench("parse", || {
+        for pk11_uri in CORPUS {
+            let _ = black_box(parse(black_box(pk11_uri)));
+        }
+    });
+
+    let mut buf = String::new();
+    bench("parse_with_buf", || {
+        for pk11_uri in CORPUS {
+            let _ = black_box(parse_with_buf(black_box(pk11_uri), &mut buf));
+        }
+    });
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Whatever the input, `parse` (and displaying its error) must never panic.
+fuzz_target!(|pk11_uri: &str| {
+    if let Err(err) = pk11_uri_parser::parse(pk11_uri) {
+        let _ = err.to_string();
+    }
+});
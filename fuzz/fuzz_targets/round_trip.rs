@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pk11_uri_parser::{parse, PK11URIMapping};
+
+// An arbitrary (valid) mapping must serialize to a URI which parses
+// back to a mapping serializing identically.
+fuzz_target!(|mapping: PK11URIMapping| {
+    let pk11_uri = mapping.to_string();
+    let reparsed = parse(&pk11_uri).expect("serialized mapping should parse");
+    assert_eq!(reparsed.to_string(), pk11_uri);
+});
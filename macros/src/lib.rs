@@ -0,0 +1,147 @@
+//! Compile-time validation of literal PKCS#11 URIs in accordance to [RFC7512][rfc7512]
+//! specifications, courtesy of the [pk11-uri-parser][pk11_uri_parser] library.
+//!
+//! [rfc7512]: <https://datatracker.ietf.org/doc/html/rfc7512>
+//!
+//! ## Examples
+//!
+//! ```
+//! use pk11_uri_parser_macros::pkcs11_uri;
+//!
+//! // validated when compiling, so parsing at runtime can't fail:
+//! const PRIVATE_KEY: &str = pkcs11_uri!("pkcs11:object=my-key;type=private");
+//!
+//! let mapping = pk11_uri_parser::parse(PRIVATE_KEY).expect("validated at compile time");
+//! assert_eq!(mapping.object(), Some("my-key"));
+//! ```
+//!
+//! An RFC7512 violation fails the build with the same [PK11URIError][pk11_uri_parser::PK11URIError]
+//! [parse][pk11_uri_parser::parse] would result in at runtime:
+//! ```compile_fail
+//! use pk11_uri_parser_macros::pkcs11_uri;
+//!
+//! const PRIVATE_KEY: &str = pkcs11_uri!("pkcs11:object=my-key;type=private-key");
+//! ```
+//! ```terminal
+//! error: pkcs11:object=my-key;type=private-key
+//!                             ^^^^^^^^^^^^^^^^ Invalid `pk11-pattr`: `pk11-type` = `"type" "=" ( "public" / "private" / "cert" / "secret-key" / "data" )`.
+//!
+//!        help: Replace `private-key` value with one of `public`, `private`, `cert`, `secret-key`, or `data`.
+//! ```
+//!
+//! Validation is always performed (irrespective of the features pk11-uri-parser is otherwise
+//! used with) and the expansion is simply the `&'static str` literal itself: the macro adds no
+//! runtime cost.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Validates the PKCS#11 URI string literal at compile time, expanding to the very same
+/// `&'static str` literal. A URI which [parse][pk11_uri_parser::parse] would reject fails
+/// the build with the resulting [PK11URIError][pk11_uri_parser::PK11URIError].
+///
+/// Only a single (possibly raw) string literal is accepted:
+/// ```compile_fail
+/// use pk11_uri_parser_macros::pkcs11_uri;
+///
+/// const OBJECT: &str = "my-key";
+/// let pk11_uri = pkcs11_uri!(OBJECT);
+/// ```
+#[proc_macro]
+pub fn pkcs11_uri(input: TokenStream) -> TokenStream {
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal,
+        // `macro_rules!` forwarding wraps the literal in an invisible group:
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::None => {
+            return pkcs11_uri(group.stream());
+        }
+        (first, _) => {
+            let span = first.map_or_else(Span::call_site, |token| token.span());
+            return compile_error(
+                "`pkcs11_uri!` expects a single string literal, ie, `pkcs11_uri!(\"pkcs11:object=my-key\")`.",
+                span,
+            );
+        }
+    };
+
+    let Some(pk11_uri) = string_value(&literal) else {
+        return compile_error(
+            "`pkcs11_uri!` expects a string literal, ie, `pkcs11_uri!(\"pkcs11:object=my-key\")`.",
+            literal.span(),
+        );
+    };
+    match pk11_uri_parser::parse(&pk11_uri) {
+        Ok(_) => TokenStream::from(TokenTree::Literal(literal)),
+        Err(err) => compile_error(&err.to_string(), literal.span()),
+    }
+}
+
+/// The value of a (possibly raw) string literal, or `None` for any other literal.
+fn string_value(literal: &Literal) -> Option<String> {
+    let literal = literal.to_string();
+
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw
+            .get(hashes + 1..raw.len() - hashes - 1)
+            .map(String::from);
+    }
+
+    let quoted = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            '\\' => value.push('\\'),
+            '"' => value.push('"'),
+            '\'' => value.push('\''),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                value.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                let hex: String = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|&c| c != '}')
+                    .filter(|&c| c != '_')
+                    .collect();
+                value.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            // A line continuation skips the newline and any leading whitespace:
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+/// Expands to `compile_error!` with the given `message`, reported at `span`.
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    [
+        TokenTree::from(Punct::new(':', Spacing::Joint)),
+        Punct::new(':', Spacing::Alone).into(),
+        Ident::new("core", span).into(),
+        Punct::new(':', Spacing::Joint).into(),
+        Punct::new(':', Spacing::Alone).into(),
+        Ident::new("compile_error", span).into(),
+        Punct::new('!', Spacing::Alone).into(),
+        Group::new(Delimiter::Parenthesis, TokenTree::from(message).into()).into(),
+    ]
+    .into_iter()
+    .map(|mut token| {
+        token.set_span(span);
+        token
+    })
+    .collect()
+}
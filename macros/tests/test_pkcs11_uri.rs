@@ -0,0 +1,42 @@
+use pk11_uri_parser_macros::pkcs11_uri;
+
+const PRIVATE_KEY: &str =
+    pkcs11_uri!("pkcs11:object=my-key;type=private?pin-source=file:/etc/token_pin");
+
+/// The macro expands to the very same (validated) literal.
+#[test]
+fn valid_literals_expand_to_themselves() {
+    assert_eq!(
+        PRIVATE_KEY,
+        "pkcs11:object=my-key;type=private?pin-source=file:/etc/token_pin"
+    );
+    assert_eq!(pkcs11_uri!("pkcs11:"), "pkcs11:");
+    assert_eq!(
+        pkcs11_uri!(r#"pkcs11:object=my-key;v-attr="quoted""#),
+        r#"pkcs11:object=my-key;v-attr="quoted""#
+    );
+}
+
+/// Escapes (including line continuations) are interpreted before validation.
+#[test]
+fn escaped_literals_are_validated_by_value() {
+    let pk11_uri = pkcs11_uri!(
+        "pkcs11:token=my-token;\
+                object=my-key\x3Btype=cert\u{3f}module-name=p11-kit"
+    );
+    let mapping = pk11_uri_parser::parse(pk11_uri).expect("validated at compile time");
+    assert_eq!(mapping.object(), Some("my-key"));
+    assert_eq!(mapping.r#type(), Some("cert"));
+    assert_eq!(mapping.module_name(), Some("p11-kit"));
+}
+
+/// Forwarding through `macro_rules!` still results in a validated literal.
+#[test]
+fn forwarded_literals_are_validated() {
+    macro_rules! object_uri {
+        ($pk11_uri:literal) => {
+            pkcs11_uri!($pk11_uri)
+        };
+    }
+    assert_eq!(object_uri!("pkcs11:object=my-key"), "pkcs11:object=my-key");
+}
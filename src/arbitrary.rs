@@ -0,0 +1,92 @@
+use super::common::{PK11_PATTR_NAMES, PK11_QATTR_NAMES, PK11_QUERY_RES_AVAIL, PK11_RES_AVAIL};
+use super::PK11URIMapping;
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+use alloc::vec::Vec;
+
+/// Generates mappings that satisfy RFC7512 (and therefore this library's
+/// validation): every value is a slice of the fuzzer's input, truncated to
+/// its longest prefix of characters allowed unencoded in the value's component.
+/// Serializing a generated mapping with its `Display` implementation and
+/// re-parsing the result yields an equivalent mapping.
+impl<'a> Arbitrary<'a> for PK11URIMapping<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // (the path's additional '&' is left out: `a&type=cert` isn't a valid value)
+        let path_value = |u: &mut Unstructured<'a>| maybe(u, |u| value(u, &[]));
+        let query_value = |u: &mut Unstructured<'a>| maybe(u, |u| value(u, &PK11_QUERY_RES_AVAIL));
+
+        let mut mapping = PK11URIMapping {
+            token: path_value(u)?,
+            manufacturer: path_value(u)?,
+            serial: path_value(u)?,
+            model: path_value(u)?,
+            library_manufacturer: path_value(u)?,
+            library_version: maybe(u, |u| u.choose(&["0", "1", "1.0", "2.40", "3.01"]).copied())?,
+            library_description: path_value(u)?,
+            object: path_value(u)?,
+            r#type: maybe(u, |u| {
+                u.choose(&["public", "private", "cert", "secret-key", "data"])
+                    .copied()
+            })?,
+            id: path_value(u)?,
+            slot_description: path_value(u)?,
+            slot_manufacturer: path_value(u)?,
+            slot_id: digits(u)?,
+            pin_source: query_value(u)?,
+            pin_value: query_value(u)?,
+            module_name: query_value(u)?,
+            module_path: query_value(u)?,
+            ..Default::default()
+        };
+        // (a PKCS#11 URI containing both is refused as invalid)
+        if mapping.pin_source.is_some() {
+            mapping.pin_value = None;
+        }
+
+        for _ in 0..u.int_in_range(0..=3)? {
+            let name = vendor_name(u)?;
+            // values shared by both components keep vendor placement unambiguous:
+            let values = (0..u.int_in_range(1..=3)?)
+                .map(|_| value(u, &[]))
+                .collect::<Result<Vec<_>>>()?;
+            if let Some(name) = name {
+                mapping.vendor.insert(name, values);
+            }
+        }
+
+        Ok(mapping)
+    }
+}
+
+fn maybe<'a, T>(
+    u: &mut Unstructured<'a>,
+    f: impl FnOnce(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Option<T>> {
+    Ok(if u.arbitrary()? { Some(f(u)?) } else { None })
+}
+
+/// Longest prefix of an arbitrary `&str` consisting of characters allowed
+/// unencoded in every component value plus the given `addl_res_avail`.
+fn value<'a>(u: &mut Unstructured<'a>, addl_res_avail: &[char]) -> Result<&'a str> {
+    Ok(prefix(u.arbitrary()?, |c| {
+        c.is_ascii_alphanumeric() || PK11_RES_AVAIL.contains(&c) || addl_res_avail.contains(&c)
+    }))
+}
+
+/// A `1*DIGIT` value, if the input happens to start with one.
+fn digits<'a>(u: &mut Unstructured<'a>) -> Result<Option<&'a str>> {
+    Ok(Some(prefix(u.arbitrary()?, |c| c.is_ascii_digit())).filter(|digits| !digits.is_empty()))
+}
+
+/// A `1*pk11-v-attr-nm-char` name not colliding with any standard attribute name.
+fn vendor_name<'a>(u: &mut Unstructured<'a>) -> Result<Option<&'a str>> {
+    let name = prefix(u.arbitrary()?, |c| {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+    });
+    Ok(Some(name).filter(|name| {
+        !name.is_empty() && !PK11_PATTR_NAMES.contains(name) && !PK11_QATTR_NAMES.contains(name)
+    }))
+}
+
+fn prefix(value: &str, allowed: impl Fn(char) -> bool) -> &str {
+    &value[..value.find(|c| !allowed(c)).unwrap_or(value.len())]
+}
@@ -0,0 +1,109 @@
+use super::{pin_source, PK11URIMapping, PinSourceKind};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+/// What redacted PIN material is replaced with.
+const REDACTED: &str = "***";
+/// What a redacted `pin-source` command is replaced with.
+const REDACTED_COMMAND: &str = "|***";
+
+impl<'a> PK11URIMapping<'a> {
+    /// A single-line summary of the mapping suitable for security audit logs, eg.
+    /// `object=my-key type=private pin-source=file module-name=mypkcs11 vendor[v-attr=1]`.
+    ///
+    /// Attributes are separated by a space and always appear in the same order: standard
+    /// path attributes, then standard query attributes (both in RFC7512 order), then each
+    /// vendor-specific value as `vendor[name=value]`, sorted by name. Values appear exactly
+    /// as parsed (ie, still percent-encoded) with the following redactions:
+    /// * `pin-value` is never included, not even its presence.
+    /// * `pin-source` is reduced to the kind of source it refers to: `file` for a `file:`
+    ///   URI, `command` for a `|command` form, the scheme name of any other URI (such as
+    ///   `https`), or `other` if none of those apply.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=my-key;type=private?pin-source=file:/etc/token_pin";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(mapping.audit_summary(), "object=my-key type=private pin-source=file");
+    ///
+    /// let mapping = pk11_uri_parser::parse("pkcs11:object=my-key?pin-value=123456").expect("mapping should be valid");
+    /// assert_eq!(mapping.audit_summary(), "object=my-key");
+    /// ```
+    pub fn audit_summary(&self) -> String {
+        self.path_attributes()
+            .into_iter()
+            .chain(self.query_attributes())
+            .filter_map(|(attr, value)| match (attr, value?) {
+                ("pin-value", _) => None,
+                ("pin-source", pin_source) => {
+                    Some(format!("pin-source={}", pin_source_audit_kind(pin_source)))
+                }
+                (attr, value) => Some(format!("{attr}={value}")),
+            })
+            .chain(self.sorted_vendor().into_iter().flat_map(|(attr, values)| {
+                values
+                    .iter()
+                    .map(move |value| format!("vendor[{attr}={value}]"))
+            }))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether the mapping embeds PIN material, such that it shouldn't be logged as-is:
+    /// a `pin-value`, or a `pin-source` command (whose arguments may well hold a secret).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mapping = pk11_uri_parser::parse("pkcs11:object=my-key?pin-value=123456").expect("mapping should be valid");
+    /// assert!(mapping.contains_sensitive_material());
+    ///
+    /// let pk11_uri = "pkcs11:object=my-key?pin-source=file:/etc/token_pin";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert!(!mapping.contains_sensitive_material());
+    /// ```
+    pub fn contains_sensitive_material(&self) -> bool {
+        self.pin_value.is_some()
+            || matches!(self.pin_source_kind(), Some(PinSourceKind::Command(_)))
+    }
+
+    /// Displays the mapping just like its `Display` implementation does, save for its
+    /// [sensitive material][PK11URIMapping::contains_sensitive_material()] being masked:
+    /// the `pin-value` is replaced with `***`, as is the command of a `pin-source` command
+    /// (retaining its `|`).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=my-key;type=private?pin-value=123456&module-name=mypkcs11";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(
+    ///     mapping.redacted().to_string(),
+    ///     "pkcs11:object=my-key;type=private?pin-value=***&module-name=mypkcs11"
+    /// );
+    ///
+    /// let pk11_uri = "pkcs11:object=my-key?pin-source=|/usr/lib/pinomatic%20--pin%3D1234";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(mapping.redacted().to_string(), "pkcs11:object=my-key?pin-source=|***");
+    /// ```
+    pub fn redacted(&self) -> impl fmt::Display + 'a {
+        let mut redacted = self.clone();
+        if redacted.pin_value.is_some() {
+            redacted.pin_value = Some(REDACTED);
+        }
+        if let Some(PinSourceKind::Command(_)) = redacted.pin_source_kind() {
+            redacted.pin_source = Some(REDACTED_COMMAND);
+        }
+        redacted
+    }
+}
+
+/// The kind of source a `pin-source` refers to, without any of its details.
+fn pin_source_audit_kind(pin_source: &str) -> &str {
+    match PinSourceKind::new(pin_source) {
+        PinSourceKind::File(_) => "file",
+        PinSourceKind::Command(_) => "command",
+        PinSourceKind::Uri(_) => pin_source::scheme(pin_source).unwrap_or("other"),
+    }
+}
@@ -0,0 +1,189 @@
+use super::{parse_with_options, PK11URIError, PK11URIMapping, ParseOptions};
+#[cfg(feature = "warnings")]
+use super::{parse_with_warnings, WarningKind};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// The outcome of [validating a batch][validate_batch] of PKCS#11 URIs.
+///
+/// Failures are kept in the order they were encountered, each paired with the
+/// caller-supplied source id (a filename, `file:line`, etc.) of the offending URI.
+#[derive(Debug, Default)]
+pub struct BatchReport<'a> {
+    successes: usize,
+    failures: Vec<(&'a str, PK11URIError)>,
+    #[cfg(feature = "warnings")]
+    warnings: Vec<(WarningKind, usize)>,
+}
+
+impl<'a> BatchReport<'a> {
+    /// The number of URIs which parsed successfully.
+    pub fn successes(&self) -> usize {
+        self.successes
+    }
+
+    /// The source id and error of every URI which failed to parse.
+    pub fn failures(&self) -> &[(&'a str, PK11URIError)] {
+        &self.failures
+    }
+
+    /// The number of URIs validated.
+    pub fn total(&self) -> usize {
+        self.successes + self.failures.len()
+    }
+
+    /// Whether every URI in the batch parsed successfully.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// The number of warnings of each [kind][WarningKind] issued for the URIs which parsed
+    /// successfully, in the order each kind was first encountered.
+    #[cfg(feature = "warnings")]
+    pub fn warnings(&self) -> &[(WarningKind, usize)] {
+        &self.warnings
+    }
+
+    /// Counts a warning of the given `kind`.
+    #[cfg(feature = "warnings")]
+    fn count_warning(&mut self, kind: WarningKind) {
+        match self
+            .warnings
+            .iter_mut()
+            .find(|(counted, _)| *counted == kind)
+        {
+            Some((_, count)) => *count += 1,
+            None => self.warnings.push((kind, 1)),
+        }
+    }
+}
+
+/// Lists each failure (prefixed by its source id) followed by a summary line.
+impl fmt::Display for BatchReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (source_id, err) in &self.failures {
+            write!(f, "{source_id}:\n{err}\n\n")?;
+        }
+        write!(
+            f,
+            "{successes} of {total} PKCS#11 URIs are valid.",
+            successes = self.successes,
+            total = self.total()
+        )
+    }
+}
+
+/// [Parses][super::parse] every `(source_id, pk11_uri)` pair, summarizing the outcome in
+/// a [BatchReport]. Unlike `parse`, a violation doesn't stop the remainder of the
+/// batch from being validated. The `source_id` is opaque to the library and serves
+/// only to correlate failures back to wherever the URI came from. Given the `warnings`
+/// feature, the kinds of warnings the valid URIs merit are tallied as well.
+///
+/// ## Examples
+///
+/// ```
+/// let uris = [
+///     ("tokens.conf:1", "pkcs11:object=my-key;type=private"),
+///     ("tokens.conf:2", "pkcs11:object=my key;type=private"),
+/// ];
+/// let report = pk11_uri_parser::validate_batch(uris);
+/// assert_eq!(report.total(), 2);
+/// # #[cfg(feature = "validation")]
+/// assert_eq!(report.failures()[0].0, "tokens.conf:2");
+/// ```
+pub fn validate_batch<'a>(uris: impl IntoIterator<Item = (&'a str, &'a str)>) -> BatchReport<'a> {
+    uris.into_iter().fold(
+        BatchReport::default(),
+        |mut report, (source_id, pk11_uri)| {
+            #[cfg(feature = "warnings")]
+            let parsed = parse_with_warnings(pk11_uri).map(|(_mapping, warnings)| {
+                for warning in warnings {
+                    report.count_warning(warning.kind());
+                }
+            });
+            #[cfg(not(feature = "warnings"))]
+            let parsed = super::parse(pk11_uri).map(|_mapping| ());
+            match parsed {
+                Ok(()) => report.successes += 1,
+                Err(err) => report.failures.push((source_id, err)),
+            }
+            report
+        },
+    )
+}
+
+/// [Parses][super::parse] each line of the newline-delimited `input` (such as a configuration file
+/// holding one PKCS#11 URI per line), resulting in the outcome of every line in order.
+/// Unlike `parse`, a violation doesn't stop the remaining lines from being parsed. Each
+/// line (without its `\n` or `\r\n` terminator) is parsed as a PKCS#11 URI of its own, so a
+/// PKCS#11 URI can't be spread over multiple lines here as it could be for `parse`.
+///
+/// ## Examples
+///
+/// ```
+/// let input = "pkcs11:object=my-key;type=private\npkcs11:object=my-cert;type=cert\n";
+/// let mappings = pk11_uri_parser::parse_many(input);
+/// assert_eq!(mappings.len(), 2);
+/// assert_eq!(mappings[1].as_ref().ok().and_then(|mapping| mapping.object()), Some("my-cert"));
+/// ```
+pub fn parse_many(input: &str) -> Vec<Result<PK11URIMapping<'_>, PK11URIError>> {
+    parse_many_with_options(input, &ParseManyOptions::default())
+}
+
+/// Adjusts how [parse_many_with_options] treats a newline-delimited list of PKCS#11 URIs.
+/// The `Default` options result in exactly the same treatment as [parse_many].
+#[derive(Debug, Clone, Default)]
+pub struct ParseManyOptions<'o> {
+    /// The [ParseOptions] each line is parsed with.
+    pub parse_options: ParseOptions<'o>,
+
+    /// Whether blank lines and `#` comment lines (such as those of a configuration file)
+    /// are skipped rather than parsed, and so failed, as PKCS#11 URIs.
+    pub skip_blank_and_comment_lines: bool,
+}
+
+impl<'o> ParseManyOptions<'o> {
+    /// Set the [parse_options][ParseManyOptions::parse_options] option.
+    pub fn parse_options(mut self, parse_options: ParseOptions<'o>) -> Self {
+        self.parse_options = parse_options;
+        self
+    }
+
+    /// Set the [skip_blank_and_comment_lines][ParseManyOptions::skip_blank_and_comment_lines] option.
+    pub fn skip_blank_and_comment_lines(mut self, skip_blank_and_comment_lines: bool) -> Self {
+        self.skip_blank_and_comment_lines = skip_blank_and_comment_lines;
+        self
+    }
+}
+
+/// Like [parse_many], but adjusted according to the given [ParseManyOptions], of which
+/// [skip_blank_and_comment_lines][ParseManyOptions::skip_blank_and_comment_lines] skips
+/// blank lines and `#` comment lines altogether (so they have no outcome in the results).
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::{parse_many_with_options, ParseManyOptions};
+///
+/// let input = "# signing keys:
+/// pkcs11:object=my-key;type=private
+///
+/// pkcs11:object=my-other-key;type=private";
+/// let options = ParseManyOptions::default().skip_blank_and_comment_lines(true);
+/// let mappings = parse_many_with_options(input, &options);
+/// assert_eq!(mappings.len(), 2);
+/// assert!(mappings.iter().all(Result::is_ok));
+/// ```
+pub fn parse_many_with_options<'a>(
+    input: &'a str,
+    options: &ParseManyOptions,
+) -> Vec<Result<PK11URIMapping<'a>, PK11URIError>> {
+    input
+        .lines()
+        .filter(|line| {
+            !(options.skip_blank_and_comment_lines
+                && (line.trim().is_empty() || line.trim_start().starts_with('#')))
+        })
+        .map(|line| parse_with_options(line, &options.parse_options))
+        .collect()
+}
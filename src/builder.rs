@@ -0,0 +1,248 @@
+use super::percent::{percent_encode_bytes, percent_encode_value};
+use super::{
+    parse, standard_component, Component, ObjectType, PK11URIError, PK11URIMapping, SecondarySpan,
+    VendorMap, ViolationKind,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Generates the builder's (percent-encoding) setter for a textual attribute.
+macro_rules! builder_setter {
+    ($fn_name:ident, $doc:meta, $attr_name:literal, $component:expr) => {
+        #[doc = "Set the (unencoded) value of the"]
+        #[doc = stringify!($attr_name)]
+        #[$doc]
+        #[doc = "attribute."]
+        pub fn $fn_name(mut self, $fn_name: &str) -> Self {
+            self.$fn_name = Some(percent_encode_value($fn_name, $component));
+            self
+        }
+    };
+    ($pattr_fn:ident for pk11-pattr $pattr_name:literal) => {
+        builder_setter!($pattr_fn, doc = "path", $pattr_name, Component::Path);
+    };
+    ($qattr_fn:ident for pk11-qattr $qattr_name:literal) => {
+        builder_setter!($qattr_fn, doc = "query", $qattr_name, Component::Query);
+    };
+}
+
+/// Constructs a PKCS#11 URI from attribute values, placing each attribute in its proper
+/// component. Values are given *unencoded*: any character requiring it is percent-encoded
+/// when the URI is [built][PK11URIBuilder::build()] (path values additionally have '&'
+/// encoded, query values may keep '/', '?' and '|').
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::{ObjectType, PK11URIBuilder};
+///
+/// let pk11_uri = PK11URIBuilder::new()
+///     .token("My Token")
+///     .object("my-key")
+///     .type_(ObjectType::Private)
+///     .pin_source("file:/etc/token_pin")
+///     .build()
+///     .expect("valid PKCS#11 URI");
+/// assert_eq!(
+///     pk11_uri,
+///     "pkcs11:token=My%20Token;object=my-key;type=private?pin-source=file:/etc/token_pin"
+/// );
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct PK11URIBuilder {
+    // pk11-pattr:
+    token: Option<String>,
+    manufacturer: Option<String>,
+    serial: Option<String>,
+    model: Option<String>,
+    library_manufacturer: Option<String>,
+    library_version: Option<String>,
+    library_description: Option<String>,
+    object: Option<String>,
+    r#type: Option<ObjectType>,
+    id: Option<String>,
+    slot_description: Option<String>,
+    slot_manufacturer: Option<String>,
+    slot_id: Option<String>,
+    // pk11-qattr:
+    pin_source: Option<String>,
+    pin_value: Option<String>,
+    module_name: Option<String>,
+    module_path: Option<String>,
+    // vendor-specific (in the order they were added):
+    vendor: Vec<(String, String)>,
+}
+
+impl PK11URIBuilder {
+    /// A builder without any attributes (which builds the lone `pkcs11:` scheme).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // pk11-pattr:
+    builder_setter!(token for pk11-pattr "token");
+    builder_setter!(manufacturer for pk11-pattr "manufacturer");
+    builder_setter!(serial for pk11-pattr "serial");
+    builder_setter!(model for pk11-pattr "model");
+    builder_setter!(library_manufacturer for pk11-pattr "library-manufacturer");
+    builder_setter!(library_version for pk11-pattr "library-version");
+    builder_setter!(library_description for pk11-pattr "library-description");
+    builder_setter!(object for pk11-pattr "object");
+    builder_setter!(slot_description for pk11-pattr "slot-description");
+    builder_setter!(slot_manufacturer for pk11-pattr "slot-manufacturer");
+
+    /// Set the `type` path attribute.
+    pub fn type_(mut self, object_type: ObjectType) -> Self {
+        self.r#type = Some(object_type);
+        self
+    }
+
+    /// Set the `id` path attribute from the (binary) object id, which is percent-encoded in its entirety.
+    pub fn id(mut self, id: &[u8]) -> Self {
+        self.id = Some(percent_encode_bytes(id));
+        self
+    }
+
+    /// Set the `slot-id` path attribute.
+    pub fn slot_id(mut self, slot_id: u64) -> Self {
+        self.slot_id = Some(slot_id.to_string());
+        self
+    }
+
+    // pk11-qattr:
+    builder_setter!(pin_source for pk11-qattr "pin-source");
+    builder_setter!(pin_value for pk11-qattr "pin-value");
+    builder_setter!(module_name for pk11-qattr "module-name");
+    builder_setter!(module_path for pk11-qattr "module-path");
+
+    // vendor-specific:
+    /// Add an (unencoded) value of the *vendor-specific* attribute `name`. Adding multiple
+    /// values of the same attribute places them in the query component.
+    ///
+    /// A `name` colliding with a standard attribute name results in a [PK11URIError]
+    /// when building; use the standard attribute's own setter instead.
+    pub fn vendor(mut self, name: &str, value: &str) -> Self {
+        self.vendor.push((
+            name.to_string(),
+            percent_encode_value(value, Component::Path),
+        ));
+        self
+    }
+
+    /// Builds the canonical PKCS#11 URI of the attributes (as per [PK11URIMapping]'s
+    /// `Display` implementation), verifying the result in the same manner as [parse].
+    /// Assuming the `validation` feature is enabled, values which RFC7512 doesn't allow
+    /// (such as a `library-version` of `one`) result in the same [PK11URIError] that
+    /// parsing the built URI would.
+    ///
+    /// Combinations RFC7512 says a PKCS#11 URI should be refused for (setting both
+    /// `pin_source` and `pin_value`) result in a [PK11URIError] regardless.
+    pub fn build(&self) -> Result<String, PK11URIError> {
+        let mut vendor: VendorMap<&str, Vec<&str>> = VendorMap::new();
+        for (name, value) in &self.vendor {
+            vendor.entry(name).or_default().push(value);
+        }
+        let mapping = PK11URIMapping {
+            token: self.token.as_deref(),
+            manufacturer: self.manufacturer.as_deref(),
+            serial: self.serial.as_deref(),
+            model: self.model.as_deref(),
+            library_manufacturer: self.library_manufacturer.as_deref(),
+            library_version: self.library_version.as_deref(),
+            library_description: self.library_description.as_deref(),
+            object: self.object.as_deref(),
+            r#type: self.r#type.map(|object_type| object_type.as_str()),
+            id: self.id.as_deref(),
+            slot_description: self.slot_description.as_deref(),
+            slot_manufacturer: self.slot_manufacturer.as_deref(),
+            slot_id: self.slot_id.as_deref(),
+            pin_source: self.pin_source.as_deref(),
+            pin_value: self.pin_value.as_deref(),
+            module_name: self.module_name.as_deref(),
+            module_path: self.module_path.as_deref(),
+            vendor,
+            attribute_order: Vec::new(),
+            source_order: Vec::new(),
+        };
+        let pk11_uri = mapping.to_string();
+
+        // A vendor-specific attribute named after a standard one would otherwise
+        // (depending upon its placement) be parsed *as* the standard attribute:
+        if let Some((name, value, component)) = self.vendor.iter().find_map(|(name, value)| {
+            standard_component(name).map(|component| (name, value, component))
+        }) {
+            return Err(naming_collision(pk11_uri, name, value, component));
+        }
+
+        // "If a URI contains both "pin-source" and "pin-value" query attributes, the URI SHOULD be refused as invalid."
+        if let (Some(pin_source), Some(pin_value)) = (&self.pin_source, &self.pin_value) {
+            return Err(pin_conflict(pk11_uri, pin_source, pin_value));
+        }
+
+        parse(&pk11_uri)?;
+        Ok(pk11_uri)
+    }
+}
+
+/// The error of setting both the `pin-source` and `pin-value` query attributes, spanning
+/// the (latter) `pin-value` and relating the `pin-source`.
+fn pin_conflict(pk11_uri: String, pin_source: &str, pin_value: &str) -> PK11URIError {
+    // (the canonical query always has `pin-source` preceding `pin-value`)
+    let span = |attribute: String| {
+        let start = pk11_uri
+            .find(&attribute)
+            .expect("canonical uri contains the attribute")
+            + 1;
+        (start, start + attribute.len() - 1)
+    };
+    let related_span = span(format!("?pin-source={pin_source}"));
+    let error_span = span(format!("&pin-value={pin_value}"));
+    PK11URIError {
+        pk11_uri,
+        error_span,
+        kind: ViolationKind::ConflictingAttributes,
+        violation: String::from(
+            r#"Conflicting `pk11-qattr`: a PKCS#11 URI containing both "pin-source" and "pin-value" query attributes SHOULD be refused as invalid."#,
+        ),
+        help: String::from("Set only one of `pin_source` or `pin_value`."),
+        secondary_span: Some(SecondarySpan::Related(related_span)),
+    }
+}
+
+/// The error of a vendor-specific attribute colliding with a standard
+/// attribute's `name`, spanning the (last) `name=value` occurrence.
+fn naming_collision(
+    pk11_uri: String,
+    name: &str,
+    value: &str,
+    component: Component,
+) -> PK11URIError {
+    let attribute = format!("{name}={value}");
+    let start = pk11_uri
+        .match_indices(&attribute)
+        .map(|(start, _)| start)
+        .filter(|start| pk11_uri[..*start].ends_with([':', ';', '?', '&']))
+        .last()
+        .unwrap_or(0);
+    let violation = match component {
+        Component::Path => "Naming collision with standard path component.",
+        Component::Query => "Naming collision with standard query component.",
+    };
+    PK11URIError {
+        error_span: (start, start + attribute.len()),
+        pk11_uri,
+        kind: ViolationKind::PathQueryNamingCollision,
+        violation: String::from(violation),
+        help: format!(
+            "`{name}` is a standard attribute: set it using the builder's `{setter}` method rather than `vendor`.",
+            setter = match name {
+                "type" => String::from("type_"),
+                name => name.replace('-', "_"),
+            }
+        ),
+        secondary_span: None,
+    }
+}
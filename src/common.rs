@@ -1,9 +1,53 @@
+#[cfg(feature = "validation")]
+use super::percent::{percent_decode_str, percent_encoding_fix};
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use super::warning::{WarningKind, Warnings};
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use super::ParseOptions;
+use super::ViolationKind;
+use alloc::{format, string::String};
+
 /// A `parse` evaluation's most granular error, used
 /// as the basis for building up error information
 /// in order to feed details to larger contexts.
 pub(crate) struct ValidationErr {
+    pub(crate) kind: ViolationKind,
     pub(crate) violation: String,
     pub(crate) help: String,
+    /// Start and end offsets of the offending characters when narrower than
+    /// the whole component: relative to the value while validating, relative
+    /// to the (untidied) component once the attribute has been parsed.
+    pub(crate) span: Option<(usize, usize)>,
+    /// Offset (relative as per `span`) of the first offending character, pinpointing
+    /// it within a broader highlight (such as the first of a value's spaces).
+    pub(crate) focus: Option<usize>,
+    /// The address and length of the first occurrence's value when the
+    /// offending attribute duplicates one already assigned.
+    pub(crate) duplicate_of: Option<(usize, usize)>,
+}
+
+#[cfg(feature = "validation")]
+impl ValidationErr {
+    /// Rebases a value-relative `span` onto the `component` that `value` is a slice of.
+    pub(crate) fn within(mut self, value: &str, component: &str) -> Self {
+        let offset = value.as_ptr() as usize - component.as_ptr() as usize;
+        self.span = self.span.map(|(start, end)| (start + offset, end + offset));
+        self.focus = self.focus.map(|focus| focus + offset);
+        self
+    }
+}
+
+/// The address and length of an already assigned `value`, identifying the first
+/// occurrence of an attribute that's since been duplicated.
+#[cfg(feature = "validation")]
+pub(crate) fn first_occurrence(value: &str) -> (usize, usize) {
+    (value.as_ptr() as usize, value.len())
 }
 
 #[cfg(feature = "validation")]
@@ -11,9 +55,51 @@ pub(crate) trait Validation<'a> {
     fn validate(&self, value: &'a str) -> Result<(), ValidationErr>;
 }
 
-#[cfg(all(debug_assertions, feature = "debug_warnings"))]
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
 pub(crate) trait Warning<'a> {
-    fn maybe_warn(&self, value: &'a str);
+    fn maybe_warn(&self, value: &'a str, options: &ParseOptions, warnings: &mut Warnings<'a>);
+}
+
+/// The thirteen standard `pk11-pattr` attribute names, in RFC7512 order.
+pub(crate) const PK11_PATTR_NAMES: [&str; 13] = [
+    "token",
+    "manufacturer",
+    "serial",
+    "model",
+    "library-manufacturer",
+    "library-version",
+    "library-description",
+    "object",
+    "type",
+    "id",
+    "slot-description",
+    "slot-manufacturer",
+    "slot-id",
+];
+
+/// The four standard `pk11-qattr` attribute names, in RFC7512 order.
+pub(crate) const PK11_QATTR_NAMES: [&str; 4] =
+    ["pin-source", "pin-value", "module-name", "module-path"];
+
+/// Characters that may appear unencoded in both path and query values
+/// (refer to the RFC7512 specification for more details).
+pub(crate) const PK11_RES_AVAIL: [char; 17] = [
+    '-', '.', '_', '~', ':', '[', ']', '@', '!', '$', '\'', '(', ')', '*', '+', ',', '=',
+];
+
+/// Characters additionally allowed unencoded in path values (`pk11-pchar`).
+pub(crate) const PK11_PATH_RES_AVAIL: [char; 1] = ['&'];
+
+/// Characters additionally allowed unencoded in query values (`pk11-qchar`).
+pub(crate) const PK11_QUERY_RES_AVAIL: [char; 3] = ['/', '?', '|'];
+
+/// Whether the (unencoded) `c` SHOULD be percent-encoded in a value which
+/// may additionally carry the `addl_res_avail` characters unencoded.
+pub(crate) fn should_percent_encode(c: char, addl_res_avail: &[char]) -> bool {
+    !(c.is_alphanumeric() || PK11_RES_AVAIL.contains(&c) || addl_res_avail.contains(&c))
 }
 
 /// A "newtype" that encapsulates `1*pk11-v-attr-nm-char` vendor-specific
@@ -32,39 +118,35 @@ impl<'a> TryFrom<&'a str> for VendorAttribute<'a> {
         // Non-standard attribute name that happens to be empty?
         if vendor_attr.is_empty() {
             return Err(ValidationErr {
+                kind: ViolationKind::MalformedComponent,
                 violation: String::from("Invalid component: Missing attribute name."),
                 help: String::from("The attribute name may not be blank. Refer to the RFC7512 specification for valid attributes."),
+                span: None,
+                focus: None,
+                duplicate_of: None,
             });
         }
 
         // Misplaced path-component attribute?
-        if [
-            "token",
-            "manufacturer",
-            "serial",
-            "model",
-            "library-manufacturer",
-            "library-version",
-            "library-description",
-            "object",
-            "type",
-            "id",
-            "slot-description",
-            "slot-manufacturer",
-            "slot-id",
-        ]
-        .contains(&vendor_attr)
-        {
+        if PK11_PATTR_NAMES.contains(&vendor_attr) {
             return Err(ValidationErr {
+                kind: ViolationKind::PathQueryNamingCollision,
                 violation: String::from("Naming collision with standard path component."),
                 help: String::from("Move this attribute and its value to the PKCS#11 URI path."),
+                span: None,
+                focus: None,
+                duplicate_of: None,
             });
         }
         // Misplaced query-component attribute?
-        if ["pin-source", "pin-value", "module-name", "module-path"].contains(&vendor_attr) {
+        if PK11_QATTR_NAMES.contains(&vendor_attr) {
             return Err(ValidationErr {
+                kind: ViolationKind::PathQueryNamingCollision,
                 violation: String::from("Naming collision with standard query component."),
                 help: format!("Move `{vendor_attr}` and its value to the PKCS#11 URI query."),
+                span: None,
+                focus: None,
+                duplicate_of: None,
             });
         }
         // Validation rules for `1*pk11-v-attr-nm-char`:
@@ -72,35 +154,188 @@ impl<'a> TryFrom<&'a str> for VendorAttribute<'a> {
             v_attr_nm_char.is_alphanumeric() || v_attr_nm_char == '-' || v_attr_nm_char == '_'
         }) {
             return Err(ValidationErr{
+                kind: ViolationKind::InvalidVendorName,
                 violation: String::from("Invalid vendor-specific component name: expected `1*pk11-v-attr-nm-char`."),
-                help: format!("`{vendor_attr}` violated vendor-specific attribute name characters consisting solely of alphanumeric, '-', or '_'.")
+                help: format!("`{vendor_attr}` violated vendor-specific attribute name characters consisting solely of alphanumeric, '-', or '_'."),
+                span: None,
+                focus: None,
+                duplicate_of: None,
             });
         }
 
-        #[cfg(debug_assertions)]
-        if vendor_attr.starts_with("x-") {
-            println!(
-                r#"pkcs11 warning: per RFC7512, the previously used convention of starting vendor attributes with an "x-" prefix is now deprecated.  Identified: `{vendor_attr}`."#
-            );
-        }
-
         Ok(VendorAttribute(vendor_attr))
     }
 }
 
 #[cfg(not(feature = "validation"))]
 impl<'a> From<&'a str> for VendorAttribute<'a> {
-
     fn from(vendor_attr: &'a str) -> Self {
-        #[cfg(all(debug_assertions, feature = "debug_warnings"))]
-        if vendor_attr.starts_with("x-") {
-            println!(
-                r#"pkcs11 warning: per RFC7512, the previously used convention of starting vendor attributes with an "x-" prefix is now deprecated.  Identified: `{vendor_attr}`."#
+        VendorAttribute(vendor_attr)
+    }
+}
+
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+impl<'a> VendorAttribute<'a> {
+    /// Vendor-specific names were once conventionally prefixed with "x-".
+    pub(crate) fn maybe_warn(&self, options: &ParseOptions, warnings: &mut Warnings<'a>) {
+        if is_deprecated_vendor_name(self.0) && !options.allow_x_vendor_prefix {
+            warnings.warn(
+                WarningKind::DeprecatedVendorPrefix,
+                self.0,
+                self.0,
+                format!(r#"per RFC7512, the previously used convention of starting vendor attributes with an "x-" prefix is now deprecated.  Identified: `{}`."#, self.0),
             );
         }
+    }
+}
 
-        VendorAttribute(vendor_attr)
+/// Splits a `pk11-pattr` or `pk11-qattr` into its (trimmed) attribute name and value.
+pub(crate) fn split_attribute(pk11_attr: &str) -> Result<(&str, &str), ValidationErr> {
+    // Intentionally *not* putting the empty check here
+    // (and incurring its associated O(n) runtime cost);
+    // the empty check gets handled further downstream
+    // if the below `ok_or_else` arm is invoked.
+    pk11_attr
+        .split_once('=')
+        .map(|(attribute, value)| (attribute.trim(), value.trim()))
+        .ok_or_else(|| {
+            let attribute = pk11_attr.trim();
+            let offset = attribute.as_ptr() as usize - pk11_attr.as_ptr() as usize;
+            ValidationErr {
+                kind: ViolationKind::MalformedComponent,
+                violation: format!(
+                    "Malformed component: `{attribute}` is missing its '=' and value."
+                ),
+                help: format!("Attributes take the form `name=value`, ie, `{attribute}=value`."),
+                span: Some((offset, offset + attribute.len())),
+                focus: None,
+                duplicate_of: None,
+            }
+        })
+}
+
+/// A `pk11_attr` beginning with its '=' (as in `=value`) lacks an attribute name, the error
+/// identifying the '=' itself.
+#[cfg(feature = "validation")]
+pub(crate) fn missing_attribute_name(pk11_attr: &str) -> Result<(), ValidationErr> {
+    match pk11_attr.split_once('=') {
+        Some((attribute, value)) if attribute.trim().is_empty() => {
+            let start = attribute.len();
+            Err(ValidationErr {
+                kind: ViolationKind::MalformedComponent,
+                violation: String::from("Invalid component: Missing attribute name."),
+                help: format!(
+                    "The attribute name is missing before `=`, ie, `name={}`.",
+                    value.trim()
+                ),
+                span: Some((start, start + 1)),
+                focus: None,
+                duplicate_of: None,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whitespace adjacent to the '=' of `pk11_attr` is a violation (rather than trimmed away)
+/// when attribute names are strict.
+#[cfg(feature = "validation")]
+pub(crate) fn strict_attribute_name(pk11_attr: &str) -> Result<(), ValidationErr> {
+    let Some((attribute, value)) = pk11_attr.split_once('=') else {
+        return Ok(());
+    };
+    let name = attribute.trim_end();
+    let start = name.len();
+    // (whitespace following an empty value is merely formatting)
+    let value_whitespace = match value.trim_start() {
+        "" => 0,
+        trimmed => value.len() - trimmed.len(),
+    };
+    let end = attribute.len() + 1 + value_whitespace;
+    if end - start == 1 {
+        return Ok(());
+    }
+    let name = name.trim_start();
+    Err(ValidationErr {
+        kind: ViolationKind::MalformedComponent,
+        violation: format!("Malformed component: `{name}` has whitespace surrounding its '='."),
+        help: format!(
+            "Remove the whitespace, ie, `{name}={value}`.",
+            value = value.trim()
+        ),
+        span: Some((start, end)),
+        focus: None,
+        duplicate_of: None,
+    })
+}
+
+/// A value of nothing but whitespace is a violation, as trimming would silently leave it
+/// empty (whereas a value *meant* to hold spaces has them percent-encoded, as in `%20`).
+/// Newlines and tabs are merely formatting, so an empty value followed by a newline (and
+/// any indentation) is fine.
+#[cfg(feature = "validation")]
+pub(crate) fn whitespace_only_value(pk11_attr: &str) -> Result<(), ValidationErr> {
+    let Some((attribute, value)) = pk11_attr.split_once('=') else {
+        return Ok(());
+    };
+    if !value.trim().is_empty()
+        || value.contains('\n')
+        || !value.contains(|c: char| c.is_whitespace() && c != '\t')
+    {
+        return Ok(());
     }
+    let name = attribute.trim();
+    let start = attribute.len() + 1;
+    Err(ValidationErr {
+        kind: ViolationKind::WhitespaceOnlyValue,
+        violation: format!(
+            "Invalid component value: the value of `{name}` is whitespace only, which is trimmed away."
+        ),
+        help: format!(
+            "Remove the whitespace for an empty value, ie, `{name}=`, or percent-encode it for a value of spaces, ie, `{name}=%20`."
+        ),
+        span: Some((start, start + value.len())),
+        focus: None,
+        duplicate_of: None,
+    })
+}
+
+/// The percent-decoded value of the `pk11_attr` must be valid UTF-8, the error
+/// identifying the first octet (possibly a `%XX` triplet) at which it isn't.
+#[cfg(feature = "validation")]
+pub(crate) fn valid_utf8_value(pk11_attr: &str) -> Result<(), ValidationErr> {
+    let (attribute, value) = split_attribute(pk11_attr)?;
+    let Err(decode_err) = percent_decode_str(value) else {
+        return Ok(());
+    };
+    let start = decode_err.offset();
+    let end = match value.as_bytes()[start] {
+        b'%' => start + 3,
+        _ => start + 1,
+    };
+    let err = ValidationErr {
+        kind: ViolationKind::InvalidUtf8,
+        violation: format!(
+            "Invalid component value: the percent-decoded value of `{attribute}` isn't valid UTF-8 text."
+        ),
+        help: format!(
+            "Correct the octet `{}` (at offset {start} of the value), percent-encoding text as its UTF-8 octets, ie, `%C3%A1` for `á`.",
+            &value[start..end]
+        ),
+        span: Some((start, end)),
+        focus: None,
+        duplicate_of: None,
+    };
+    Err(err.within(value, pk11_attr))
+}
+
+/// Per RFC7512, the previously used convention of starting
+/// vendor-specific attribute names with "x-" is deprecated.
+pub(crate) fn is_deprecated_vendor_name(vendor_attr: &str) -> bool {
+    vendor_attr.starts_with("x-")
 }
 
 /// Values for *both* path and query components must not contain empty spaces or the '#' character.
@@ -108,13 +343,18 @@ impl<'a> From<&'a str> for VendorAttribute<'a> {
 pub(crate) fn common_validation(value: &str) -> Option<ValidationErr> {
     if value.contains(' ') {
         return Some(ValidationErr {
+            kind: ViolationKind::EmptySpaceInValue,
             violation: String::from("Invalid component value: Appendix A of [RFC3986] specifies component values may not contain empty spaces."),
-            help: format!("Replace `{value}` with `{fixed}`.", fixed=value.replace(' ', "%20"))
+            help: format!("Replace `{value}` with `{fixed}`.", fixed=value.replace(' ', "%20")),
+            span: None,
+            focus: value.find(' '),
+            duplicate_of: None,
         });
     }
 
     if value.contains('#') {
         return Some(ValidationErr {
+            kind: ViolationKind::HashInValue,
             violation: String::from(
                 "Invalid component value: The '#' delimiter must always be percent-encoded.",
             ),
@@ -122,27 +362,86 @@ pub(crate) fn common_validation(value: &str) -> Option<ValidationErr> {
                 "Replace `{value}` with `{fixed}`.",
                 fixed = value.replace('#', "%23")
             ),
+            span: None,
+            focus: None,
+            duplicate_of: None,
+        });
+    }
+
+    if let Some(offset) = find_malformed_percent(value) {
+        let hex_len = value[offset + 1..]
+            .bytes()
+            .take(2)
+            .take_while(u8::is_ascii_hexdigit)
+            .count();
+        return Some(ValidationErr {
+            kind: ViolationKind::InvalidPercentEncoding,
+            violation: String::from(
+                r#"Invalid component value: a '%' must begin a percent-encoded octet, `pct-encoded = "%" HEXDIG HEXDIG`."#,
+            ),
+            help: format!(
+                "Replace `{value}` with `{fixed}`.",
+                fixed = percent_encoding_fix(value, |_| true).unwrap_or_default()
+            ),
+            span: Some((offset, offset + 1 + hex_len)),
+            focus: None,
+            duplicate_of: None,
         });
     }
 
     None
 }
 
-/// If running in a non-optimized build, this function will be utilized
-/// to identify potential issues of unsupported characters.  The intent
-/// of this function is to properly test attribute values in debug builds
+/// The offset of the first '%' within `value` that isn't followed by two hexadecimal digits.
+#[cfg(feature = "validation")]
+fn find_malformed_percent(value: &str) -> Option<usize> {
+    value
+        .match_indices('%')
+        .map(|(offset, _)| offset)
+        .find(|offset| {
+            !value
+                .as_bytes()
+                .get(offset + 1..offset + 3)
+                .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+        })
+}
+
+/// Identifies a misused component delimiter: a `delimiter` within `value` that's
+/// followed by what appears to be another attribute, as in `object=my-key&type=cert`
+/// (where `is_attribute` decides whether the text preceding the next '=' names one).
+/// Returns the offset of the delimiter within `value`.
+#[cfg(feature = "validation")]
+pub(crate) fn find_misused_delimiter(
+    value: &str,
+    delimiter: char,
+    is_attribute: impl Fn(&str) -> bool,
+) -> Option<usize> {
+    value
+        .match_indices(delimiter)
+        .map(|(offset, _)| offset)
+        .find(|offset| {
+            value[offset + 1..]
+                .split_once('=')
+                .is_some_and(|(attribute, _)| is_attribute(attribute.trim()))
+        })
+}
+
+/// If running in a non-optimized build (or with the `warnings` feature), this
+/// function will be utilized to identify potential issues of unsupported characters.
+/// The intent of this function is to properly test attribute values in debug builds
 /// and make appropriate changes for usage prior to release builds.
-#[cfg(all(debug_assertions, feature = "debug_warnings"))]
-pub(crate) fn maybe_suggest_percent_encoding<const T: usize>(
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+pub(crate) fn maybe_suggest_percent_encoding<'a, const T: usize>(
     attribute: &str,
-    value: &str,
+    value: &'a str,
     addl_res_avail: [char; T], // additional reserved available
+    allowed: &[char],          // additionally allowed by the caller (see `ParseOptions`)
+    warnings: &mut Warnings<'a>,
 ) {
-    // refer to the RFC7512 specification for more details:
-    const PK11_RES_AVAIL: [char; 17] = [
-        '-', '.', '_', '~', ':', '[', ']', '@', '!', '$', '\'', '(', ')', '*', '+', ',', '=',
-    ];
-    let mut iter = value.chars().enumerate().peekable();
+    let mut iter = value.char_indices().peekable();
     while let Some((offset, value_char)) = iter.next() {
         match value_char {
             '%' => {
@@ -150,16 +449,28 @@ pub(crate) fn maybe_suggest_percent_encoding<const T: usize>(
                 if iter.next_if(|(_offset, c)| c.is_ascii_hexdigit()).is_none()
                     || iter.next_if(|(_offset, c)| c.is_ascii_hexdigit()).is_none()
                 {
-                    println!("pkcs11 warning: identified malformed percent-encoding at offset {offset} in \
-                    `{value}` of component `{attribute}={value}`");
+                    warnings.warn(
+                        WarningKind::MalformedPercentEncoding,
+                        attribute,
+                        &value[offset..offset + 1],
+                        format!(
+                            "identified malformed percent-encoding at offset {offset} in \
+                    `{value}` of component `{attribute}={value}`"
+                        ),
+                    );
                 }
             }
-            c if c.is_alphanumeric()
-                || PK11_RES_AVAIL.contains(&c)
-                || addl_res_avail.contains(&c) => {}
+            c if !should_percent_encode(c, &addl_res_avail) || allowed.contains(&c) => {}
             _ => {
-                println!("pkcs11 warning: the `{value_char}` identified at offset {offset} in `{value}` of \
-                component `{attribute}={value}` SHOULD be percent-encoded.");
+                warnings.warn(
+                    WarningKind::UnencodedCharacter,
+                    attribute,
+                    &value[offset..offset + value_char.len_utf8()],
+                    format!(
+                        "the `{value_char}` identified at offset {offset} in `{value}` of \
+                component `{attribute}={value}` SHOULD be percent-encoded."
+                    ),
+                );
             }
         }
     }
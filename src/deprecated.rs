@@ -0,0 +1,41 @@
+use super::common::is_deprecated_vendor_name;
+use super::PK11URIMapping;
+use alloc::vec::Vec;
+
+impl<'a> PK11URIMapping<'a> {
+    /// Whether the mapping uses any construct deprecated by RFC7512; see [deprecated_items][Self::deprecated_items()].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:x-muppet=cookie-monster";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert!(mapping.uses_deprecated());
+    /// ```
+    pub fn uses_deprecated(&self) -> bool {
+        self.vendor
+            .keys()
+            .any(|vendor_attr| is_deprecated_vendor_name(vendor_attr))
+    }
+
+    /// The names of the attributes using constructs deprecated by RFC7512, sorted by name.
+    /// Unlike the `pkcs11 warning:` messages concerning them, this is available for any build.
+    ///
+    /// Currently the only such construct is the previously used convention of
+    /// starting vendor-specific attribute names with an "x-" prefix.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:x-muppet=cookie-monster;v-attr=val?x-puppet=kermit";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(mapping.deprecated_items(), ["x-muppet", "x-puppet"]);
+    /// ```
+    pub fn deprecated_items(&self) -> Vec<&'a str> {
+        self.sorted_vendor()
+            .into_iter()
+            .map(|(vendor_attr, _)| vendor_attr)
+            .filter(|vendor_attr| is_deprecated_vendor_name(vendor_attr))
+            .collect()
+    }
+}
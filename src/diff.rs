@@ -0,0 +1,104 @@
+use super::PK11URIMapping;
+use alloc::{vec, vec::Vec};
+
+/// A difference between two mappings' attribute, as reported by
+/// [diff][PK11URIMapping::diff()]. A standard attribute has a single value, while a
+/// vendor-specific attribute has each of its values (in order). Values are compared
+/// exactly as they were parsed (ie, still percent-encoded).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrDiff<'a> {
+    /// The attribute only the other mapping has.
+    Added {
+        /// The attribute's name.
+        name: &'a str,
+        /// The other mapping's values.
+        values: Vec<&'a str>,
+    },
+    /// The attribute only the mapping itself has.
+    Removed {
+        /// The attribute's name.
+        name: &'a str,
+        /// The mapping's values.
+        values: Vec<&'a str>,
+    },
+    /// The attribute both mappings have, with differing values.
+    Changed {
+        /// The attribute's name.
+        name: &'a str,
+        /// The mapping's values.
+        old: Vec<&'a str>,
+        /// The other mapping's values.
+        new: Vec<&'a str>,
+    },
+}
+
+impl<'a> PK11URIMapping<'a> {
+    /// The differences between this mapping and the `other` one, such as for debugging why
+    /// two PKCS#11 URIs identify different objects: the standard attributes in RFC7512
+    /// order (path before query), followed by the vendor-specific attributes sorted by
+    /// name. Attributes both mappings have the same values of aren't reported, so mappings
+    /// which are equal (as per `PartialEq`) have no differences.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use pk11_uri_parser::AttrDiff;
+    ///
+    /// let mapping = pk11_uri_parser::parse("pkcs11:token=my-token;type=cert;v-attr=a")
+    ///     .expect("mapping should be valid");
+    /// let other = pk11_uri_parser::parse("pkcs11:type=private;object=my-key;v-attr=a")
+    ///     .expect("mapping should be valid");
+    /// assert_eq!(
+    ///     mapping.diff(&other),
+    ///     [
+    ///         AttrDiff::Removed { name: "token", values: vec!["my-token"] },
+    ///         AttrDiff::Added { name: "object", values: vec!["my-key"] },
+    ///         AttrDiff::Changed { name: "type", old: vec!["cert"], new: vec!["private"] },
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff(&self, other: &PK11URIMapping<'a>) -> Vec<AttrDiff<'a>> {
+        let standard = self
+            .path_attributes()
+            .into_iter()
+            .zip(other.path_attributes())
+            .chain(
+                self.query_attributes()
+                    .into_iter()
+                    .zip(other.query_attributes()),
+            )
+            .map(|((name, old), (_, new))| {
+                (
+                    name as &'a str,
+                    old.map(|old| vec![old]),
+                    new.map(|new| vec![new]),
+                )
+            });
+
+        let mut vendor_names: Vec<_> = self
+            .vendor
+            .keys()
+            .chain(other.vendor.keys())
+            .copied()
+            .collect();
+        vendor_names.sort_unstable();
+        vendor_names.dedup();
+        let vendor = vendor_names.into_iter().map(|name| {
+            (
+                name,
+                self.vendor.get(name).cloned(),
+                other.vendor.get(name).cloned(),
+            )
+        });
+
+        standard
+            .chain(vendor)
+            .filter_map(|(name, old, new)| match (old, new) {
+                (None, Some(values)) => Some(AttrDiff::Added { name, values }),
+                (Some(values), None) => Some(AttrDiff::Removed { name, values }),
+                (Some(old), Some(new)) if old != new => Some(AttrDiff::Changed { name, old, new }),
+                _ => None,
+            })
+            .collect()
+    }
+}
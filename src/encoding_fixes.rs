@@ -0,0 +1,74 @@
+use super::common::{should_percent_encode, PK11_PATH_RES_AVAIL, PK11_QUERY_RES_AVAIL};
+use super::percent::percent_encoding_fix;
+use super::serialize::place_vendor_values;
+use super::PK11URIMapping;
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+impl PK11URIMapping<'_> {
+    /// The percent-encoding fixes of the mapping's values, each as an `(attribute, original,
+    /// fixed)` triple: wherever a value holds characters which SHOULD be percent-encoded
+    /// (or a '%' not beginning a well-formed percent-encoding), `fixed` is its replacement
+    /// with just those characters encoded. The `id` attribute SHOULD be percent-encoded in
+    /// its entirety, so its replacement encodes every octet.
+    ///
+    /// These are the same fixes the `pkcs11 warning:` messages suggest, but are available
+    /// for any build. Fixes are ordered as the values are serialized: standard path attributes,
+    /// standard query attributes, then vendor-specific attributes sorted by name (with one
+    /// fix per offending value).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=my<key>;id=ab?module-name=p11-kit";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// let fixes: Vec<_> = mapping
+    ///     .encoding_fixes()
+    ///     .into_iter()
+    ///     .map(|(attribute, _original, fixed)| format!("{attribute}={fixed}"))
+    ///     .collect();
+    /// assert_eq!(fixes, ["object=my%3Ckey%3E", "id=%61%62"]);
+    /// ```
+    pub fn encoding_fixes(&self) -> Vec<(String, String, String)> {
+        let path_fix = |value: &str| {
+            percent_encoding_fix(value, |c| !should_percent_encode(c, &PK11_PATH_RES_AVAIL))
+        };
+        let query_fix = |value: &str| {
+            percent_encoding_fix(value, |c| !should_percent_encode(c, &PK11_QUERY_RES_AVAIL))
+        };
+
+        let mut fixes = vec![];
+        let mut push = |attribute: &str, value: &str, fixed: Option<String>| {
+            if let Some(fixed) = fixed {
+                fixes.push((attribute.to_string(), value.to_string(), fixed));
+            }
+        };
+        for (attribute, value) in self.path_attributes() {
+            let Some(value) = value else { continue };
+            match attribute {
+                "id" => push(attribute, value, percent_encoding_fix(value, |_| false)),
+                // validated against their own (unencoded) grammar:
+                "library-version" | "type" | "slot-id" => {}
+                _ => push(attribute, value, path_fix(value)),
+            }
+        }
+        for (attribute, value) in self.query_attributes() {
+            if let Some(value) = value {
+                push(attribute, value, query_fix(value));
+            }
+        }
+        for (attribute, values) in self.sorted_vendor() {
+            let (path_value, query_values) = place_vendor_values(values);
+            if let Some(value) = path_value {
+                push(attribute, value, path_fix(value));
+            }
+            for value in query_values {
+                push(attribute, value, query_fix(value));
+            }
+        }
+        fixes
+    }
+}
@@ -0,0 +1,169 @@
+//! A C-compatible interface, for parsing PKCS#11 URIs from non-Rust (HSM) tooling.
+//!
+//! [pk11_uri_parse] parses a NUL-terminated PKCS#11 URI into an opaque handle, whose
+//! values are retrieved as NUL-terminated strings by way of the accessor functions (such
+//! as [pk11_uri_object]) or, for any attribute including vendor-specific ones, by name
+//! using [pk11_uri_attribute]. Every string is owned by the handle, remaining valid until
+//! the handle is released using [pk11_uri_free]. A PKCS#11 URI which isn't valid results
+//! in a handle whose [pk11_uri_last_error] describes the violation.
+//!
+//! Build the library as a C library with the `ffi` feature, such as by way of
+//! `cargo rustc --release --features ffi --crate-type cdylib`, and declare:
+//!
+//! ```c
+//! typedef struct PK11URIHandle PK11URIHandle;
+//!
+//! PK11URIHandle *pk11_uri_parse(const char *pk11_uri);
+//! const char *pk11_uri_last_error(const PK11URIHandle *handle);
+//! const char *pk11_uri_attribute(const PK11URIHandle *handle, const char *attr);
+//! const char *pk11_uri_object(const PK11URIHandle *handle); /* etc. */
+//! void pk11_uri_free(PK11URIHandle *handle);
+//! ```
+use super::parse_bytes;
+use alloc::{
+    boxed::Box,
+    ffi::CString,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::ffi::{c_char, CStr};
+use core::ptr;
+
+/// The opaque result of [pk11_uri_parse]: either the parsed values or the error.
+pub struct PK11URIHandle {
+    /// Attribute names paired with their values (a vendor-specific attribute's first value).
+    values: Vec<(String, CString)>,
+    /// The displayed `PK11URIError` of a PKCS#11 URI which isn't valid.
+    error: Option<CString>,
+}
+
+impl PK11URIHandle {
+    fn new(pk11_uri: &[u8]) -> Self {
+        match parse_bytes(pk11_uri) {
+            Ok(mapping) => {
+                let standard = mapping
+                    .iter()
+                    .map(|(attr, value)| (attr.to_string(), c_string(value)));
+                let vendor = mapping.vendor_iter().filter_map(|(name, values)| {
+                    Some((name.to_string(), c_string(values.first()?)))
+                });
+                PK11URIHandle {
+                    values: standard.chain(vendor).collect(),
+                    error: None,
+                }
+            }
+            Err(err) => PK11URIHandle {
+                values: Vec::new(),
+                error: Some(c_string(&err.to_string())),
+            },
+        }
+    }
+
+    fn value(&self, attr: &[u8]) -> *const c_char {
+        self.values
+            .iter()
+            .find(|(name, _)| name.as_bytes() == attr)
+            .map_or(ptr::null(), |(_, value)| value.as_ptr())
+    }
+}
+
+/// Copies `value` (a slice of the C string given to `pk11_uri_parse`) as a C string.
+fn c_string(value: &str) -> CString {
+    CString::new(value).expect("a C string holds no interior NUL")
+}
+
+/// Parses the NUL-terminated `pk11_uri` as per [parse_bytes], resulting
+/// in a handle to be released using [pk11_uri_free]. Null only when `pk11_uri` is null.
+///
+/// # Safety
+///
+/// `pk11_uri` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pk11_uri_parse(pk11_uri: *const c_char) -> *mut PK11URIHandle {
+    if pk11_uri.is_null() {
+        return ptr::null_mut();
+    }
+    let pk11_uri = CStr::from_ptr(pk11_uri);
+    Box::into_raw(Box::new(PK11URIHandle::new(pk11_uri.to_bytes())))
+}
+
+/// The description of the violation when the PKCS#11 URI isn't valid, or null when it
+/// was parsed successfully (or `handle` is null).
+///
+/// # Safety
+///
+/// `handle` must be null or a handle returned by [pk11_uri_parse] which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn pk11_uri_last_error(handle: *const PK11URIHandle) -> *const c_char {
+    handle
+        .as_ref()
+        .and_then(|handle| handle.error.as_ref())
+        .map_or(ptr::null(), |error| error.as_ptr())
+}
+
+/// The value of the (standard or vendor-specific) attribute named by the NUL-terminated
+/// `attr`, the first value of a vendor-specific attribute, or null if there is none.
+///
+/// # Safety
+///
+/// `handle` must be null or a handle returned by [pk11_uri_parse] which hasn't been freed,
+/// and `attr` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn pk11_uri_attribute(
+    handle: *const PK11URIHandle,
+    attr: *const c_char,
+) -> *const c_char {
+    match (handle.as_ref(), attr.is_null()) {
+        (Some(handle), false) => handle.value(CStr::from_ptr(attr).to_bytes()),
+        _ => ptr::null(),
+    }
+}
+
+/// Releases the `handle`, along with every string retrieved from it.
+///
+/// # Safety
+///
+/// `handle` must be null or a handle returned by [pk11_uri_parse] which hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn pk11_uri_free(handle: *mut PK11URIHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Generates the C accessor of a standard attribute.
+macro_rules! ffi_access {
+    ($fn_name:ident for $attr_name:literal) => {
+        #[doc = concat!("The value of the `", $attr_name, "` attribute, or null if there is none.")]
+        ///
+        /// # Safety
+        ///
+        /// `handle` must be null or a handle returned by [pk11_uri_parse] which hasn't been freed.
+        #[no_mangle]
+        pub unsafe extern "C" fn $fn_name(handle: *const PK11URIHandle) -> *const c_char {
+            handle
+                .as_ref()
+                .map_or(ptr::null(), |handle| handle.value($attr_name.as_bytes()))
+        }
+    };
+}
+
+// pk11-pattr:
+ffi_access!(pk11_uri_token for "token");
+ffi_access!(pk11_uri_manufacturer for "manufacturer");
+ffi_access!(pk11_uri_serial for "serial");
+ffi_access!(pk11_uri_model for "model");
+ffi_access!(pk11_uri_library_manufacturer for "library-manufacturer");
+ffi_access!(pk11_uri_library_version for "library-version");
+ffi_access!(pk11_uri_library_description for "library-description");
+ffi_access!(pk11_uri_object for "object");
+ffi_access!(pk11_uri_type for "type");
+ffi_access!(pk11_uri_id for "id");
+ffi_access!(pk11_uri_slot_description for "slot-description");
+ffi_access!(pk11_uri_slot_manufacturer for "slot-manufacturer");
+ffi_access!(pk11_uri_slot_id for "slot-id");
+// pk11-qattr:
+ffi_access!(pk11_uri_pin_source for "pin-source");
+ffi_access!(pk11_uri_pin_value for "pin-value");
+ffi_access!(pk11_uri_module_name for "module-name");
+ffi_access!(pk11_uri_module_path for "module-path");
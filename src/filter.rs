@@ -0,0 +1,74 @@
+use super::percent::percent_decode_lossy;
+use super::{ObjectType, PK11URIMapping};
+use alloc::borrow::Cow;
+
+/// The search criteria of a mapping's object-identifying attributes (`type`, `id`, and
+/// `object`), decoded once by [as_filter][PK11URIMapping::as_filter()] so as to cheaply
+/// [match][Filter::matches()] any number of candidate objects, such as those a
+/// `C_FindObjects` loop encounters.
+#[derive(Debug, Clone)]
+pub struct Filter<'a> {
+    /// The `CKA_CLASS` of the `type` (`Some(None)` for a `type` RFC7512 doesn't
+    /// define, which no object matches).
+    class: Option<Option<u64>>,
+    /// The decoded `id`, ie, `CKA_ID`.
+    id: Option<Cow<'a, [u8]>>,
+    /// The decoded `object`, ie, `CKA_LABEL`.
+    label: Option<Cow<'a, [u8]>>,
+}
+
+/// The attributes of a candidate PKCS#11 object to be [matched][Filter::matches()].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CandidateAttributes<'c> {
+    /// The object's `CKA_CLASS`.
+    pub class: u64,
+    /// The object's `CKA_ID`.
+    pub id: &'c [u8],
+    /// The object's `CKA_LABEL`.
+    pub label: &'c [u8],
+}
+
+impl Filter<'_> {
+    /// Whether the candidate object has each of the filter's attributes; attributes the
+    /// mapping didn't specify match any object.
+    pub fn matches(&self, candidate: &CandidateAttributes) -> bool {
+        self.class
+            .is_none_or(|class| class == Some(candidate.class))
+            && self.id.as_deref().is_none_or(|id| id == candidate.id)
+            && self
+                .label
+                .as_deref()
+                .is_none_or(|label| label == candidate.label)
+    }
+}
+
+impl<'a> PK11URIMapping<'a> {
+    /// The [Filter] of the mapping's `type`, `id`, and `object` attributes. Values are
+    /// percent-decoded leniently: a `%` not followed by two hexadecimal digits is taken
+    /// as-is.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use pk11_uri_parser::CandidateAttributes;
+    ///
+    /// let pk11_uri = "pkcs11:object=my%20key;type=private;id=%01%02";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// let filter = mapping.as_filter();
+    ///
+    /// // CKO_PRIVATE_KEY:
+    /// let candidate = CandidateAttributes { class: 3, id: &[1, 2], label: b"my key" };
+    /// assert!(filter.matches(&candidate));
+    /// // CKO_PUBLIC_KEY:
+    /// assert!(!filter.matches(&CandidateAttributes { class: 2, ..candidate }));
+    /// ```
+    pub fn as_filter(&self) -> Filter<'a> {
+        Filter {
+            class: self.r#type.map(|r#type| {
+                ObjectType::from_type_value(r#type).map(|object_type| object_type.to_object_class())
+            }),
+            id: self.id.map(percent_decode_lossy),
+            label: self.object.map(percent_decode_lossy),
+        }
+    }
+}
@@ -69,7 +69,7 @@
 //! ```
 //! Attempting to parse that uri will result in a [PK11URIError].
 //! ```terminal
-//! PK11URIError { pk11_uri: "pkcs11:object=Private key for Card Authentication;pin-value=123456", error_span: (7, 49), violation: "Invalid component value: Appendix A of [RFC3986] specifies component values may not contain empty spaces.", help: "Replace `Private key for Card Authentication` with `Private%20key%20for%20Card%20Authentication`." }
+//! PK11URIError { pk11_uri: "pkcs11:object=Private key for Card Authentication;pin-value=123456", error_span: (7, 49), kind: EmptySpaceInValue, violation: "Invalid component value: Appendix A of [RFC3986] specifies component values may not contain empty spaces.", help: "Replace `Private key for Card Authentication` with `Private%20key%20for%20Card%20Authentication`.", secondary_span: Some(Focus((21, 22))) }
 //! ```
 //! Or if you'd prefer a fancier output, simply display the PK11URIError (*not* using `:?` debug):
 //! ```
@@ -81,6 +81,7 @@
 //! ```terminal
 //! pkcs11:object=Private key for Card Authentication;pin-value=123456
 //!        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ Invalid component value: Appendix A of [RFC3986] specifies component values may not contain empty spaces.
+//!                      ^ starting here
 //!
 //! help: Replace `Private key for Card Authentication` with `Private%20key%20for%20Card%20Authentication`.
 //! ```
@@ -130,7 +131,9 @@
 //! pkcs11 warning: the `>` identified at offset 9 in `cookie<^^>monster!` of component `x-muppet=cookie<^^>monster!` SHOULD be percent-encoded.
 //! x-muppet: ["cookie<^^>monster!"]
 //! ```
-//! Any warning related code is explicitly **not** included in `--release` builds.
+//! Any warning related code is explicitly **not** included in `--release` builds, unless the `warnings` feature
+//! is enabled: `parse_with_warnings` then returns each `PK11URIWarning` (its message, attribute, and offset
+//! within the uri) alongside the mapping so that they may be logged or otherwise acted upon.
 //!
 //!  ## Crate feature flags
 //!
@@ -147,19 +150,106 @@
 //! [dependencies]
 //! pk11-uri-parser = {version = "0.1.4", default-features = false}
 //! ```
-//! It's important to note, however, that doing so will introduce `expect("my expectation")` calls to perform
-//! unwrap functionality required in the parsing.
+//! It's important to note, however, that doing so means a malformed PKCS#11 URI may be parsed into an unexpected
+//! mapping; only components that can't be parsed at all (such as an attribute lacking its `=`) result in a
+//! [PK11URIError].
+//!
+//! Doing so also drops the default `std` feature, leaving a `no_std` library that needs nothing more than `core`
+//! and `alloc` (such as for embedded use); add `features = ["std"]` to keep building against the standard library.
+//! The `debug_warnings` feature requires `std`, as there's otherwise nowhere to print its messages.
+//!
+//! Conversely, the non-default `warnings` feature makes warnings available to *any* build (including `--release`)
+//! by way of `parse_with_warnings` (or the `warning_sink` of `ParseOptions`, which hands each warning to a
+//! callback). Its `strict_advisories` option goes further still, refusing any PKCS#11 URI which merits a warning.
+//!
+//! The non-default `ffi` feature exposes a C-compatible interface (see the `ffi` module), for parsing
+//! PKCS#11 URIs from non-Rust tooling.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::cell::RefCell;
 use core::error;
-use std::collections::HashMap;
-use std::fmt;
+use core::fmt;
+use core::hash;
+use core::ops::Range;
 
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod audit;
+mod batch;
+mod builder;
 mod common;
+mod deprecated;
+mod diff;
+mod encoding_fixes;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod filter;
+mod merge;
+mod normalize;
+mod object_type;
+mod options;
+mod owned;
+mod parts;
+mod percent;
+mod pin_source;
 mod pk11_pattr;
 mod pk11_qattr;
+mod require;
+#[cfg(feature = "serde")]
+mod serde;
+mod serialize;
+mod shell;
+mod violation;
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+mod warning;
+mod with;
+
+pub use batch::{
+    parse_many, parse_many_with_options, validate_batch, BatchReport, ParseManyOptions,
+};
+pub use builder::PK11URIBuilder;
+pub use diff::AttrDiff;
+pub use filter::{CandidateAttributes, Filter};
+pub use merge::VendorMergePolicy;
+pub use normalize::fingerprint;
+pub use object_type::ObjectType;
+pub use options::ParseOptions;
+pub use owned::{parse_owned, OwnedPK11URIMapping};
+pub use percent::{percent_encode_value, DecodeError};
+pub use pin_source::{PinSource, PinSourceKind, PinSourceUrl};
+pub use require::MissingAttrError;
+pub use shell::shell_quote;
+pub use violation::ViolationKind;
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use warning::Warnings;
+#[cfg(feature = "warnings")]
+pub use warning::{PK11URIWarning, WarningKind};
+
+/// The map of vendor-specific attribute names to their values: a `HashMap` when
+/// building against the standard library, otherwise an `alloc` `BTreeMap`.
+#[cfg(feature = "std")]
+type VendorMap<K, V> = std::collections::HashMap<K, V>;
+#[cfg(not(feature = "std"))]
+type VendorMap<K, V> = alloc::collections::BTreeMap<K, V>;
 
 const PKCS11_SCHEME: &str = "pkcs11:";
 const PKCS11_SCHEME_LEN: usize = PKCS11_SCHEME.len();
@@ -180,33 +270,107 @@ pub struct PK11URIError {
     pk11_uri: String,
     /// The start end end offsets of the error.
     error_span: (usize, usize),
+    /// The category of the violation.
+    kind: ViolationKind,
     /// The ABNF or RFC7512 text exhibiting the issue.
     violation: String,
     /// Human-friendly suggestion of how to resolve the issue.
     help: String,
+    /// The start and end offsets of a span highlighted alongside the `error_span`.
+    secondary_span: Option<SecondarySpan>,
+}
+
+/// A span highlighted alongside a [PK11URIError]'s `error_span` (a violation having at most one).
+#[derive(Debug, Clone, Copy)]
+enum SecondarySpan {
+    /// The start and end offsets of the first occurrence of a duplicated attribute.
+    Related((usize, usize)),
+    /// The start and end offsets of the first offending character within the `error_span`.
+    Focus((usize, usize)),
+}
+
+impl PK11URIError {
+    /// The start and end offsets of the violation within the tidied uri.
+    pub fn error_span(&self) -> (usize, usize) {
+        self.error_span
+    }
+
+    /// The [category][ViolationKind] of the violation, for handling it programmatically.
+    pub fn kind(&self) -> ViolationKind {
+        self.kind
+    }
+
+    /// The start and end offsets, within the tidied uri, of the first occurrence of an
+    /// attribute the violation duplicates (the `error_span` identifying the duplicate
+    /// itself), allowing both to be highlighted. `None` for any other violation.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:token=a;object=my-key;token=b";
+    /// # #[cfg(feature = "validation")]
+    /// # {
+    /// let err = pk11_uri_parser::parse(pk11_uri).expect_err("duplicate token attribute");
+    /// assert_eq!(err.related_span(), Some((7, 14)));
+    /// assert_eq!(err.error_span(), (29, 36));
+    /// # }
+    /// ```
+    pub fn related_span(&self) -> Option<(usize, usize)> {
+        match self.secondary_span {
+            Some(SecondarySpan::Related(related_span)) => Some(related_span),
+            _ => None,
+        }
+    }
+
+    /// The start and end offsets, within the tidied uri, of the first offending character
+    /// of a violation whose `error_span` highlights more than just it (the first space of a
+    /// value containing spaces), allowing a cursor to be placed right at it in a long value.
+    /// `None` for any other violation.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:token=Card Authentication";
+    /// # #[cfg(feature = "validation")]
+    /// # {
+    /// let err = pk11_uri_parser::parse(pk11_uri).expect_err("space in token value");
+    /// assert_eq!(err.error_span(), (7, 32));
+    /// assert_eq!(err.focus_span(), Some((17, 18)));
+    /// # }
+    /// ```
+    pub fn focus_span(&self) -> Option<(usize, usize)> {
+        match self.secondary_span {
+            Some(SecondarySpan::Focus(focus_span)) => Some(focus_span),
+            _ => None,
+        }
+    }
 }
 
 impl error::Error for PK11URIError {}
 
-/// Highlights the issue using the `error_span`.
+/// Highlights the issue using the `error_span`, pointing out any `focus_span` beneath it.
 impl fmt::Display for PK11URIError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let padding = self.error_span.0;
         let highlight = self.error_span.1 - padding;
         write!(
             f,
-            "{}\n{:padding$}{:^^highlight$} {violation}\n\nhelp: {help}",
+            "{}\n{:padding$}{:^^highlight$} {violation}",
             self.pk11_uri,
             "",
             "^",
             violation = self.violation,
-            help = self.help
-        )
+        )?;
+        if let Some((start, end)) = self.focus_span() {
+            let highlight = end - start;
+            write!(f, "\n{:start$}{:^^highlight$} starting here", "", "^")?;
+        }
+        write!(f, "\n\nhelp: {help}", help = self.help)
     }
 }
 
 /// Encapsulates the result of successfully [parsing][parse] a PKCS#11 URI.
-#[derive(Debug, Default, Clone)]
+#[derive(Default, Clone)]
 pub struct PK11URIMapping<'a> {
     // pk11-pattr:
     token: Option<&'a str>,
@@ -228,7 +392,11 @@ pub struct PK11URIMapping<'a> {
     module_name: Option<&'a str>,
     module_path: Option<&'a str>,
     // vendor-specific:
-    vendor: HashMap<&'a str, Vec<&'a str>>,
+    vendor: VendorMap<&'a str, Vec<&'a str>>,
+    // standard attribute names in the order they were parsed:
+    attribute_order: Vec<&'static str>,
+    // every attribute name and its component in the order they were parsed (if preserved):
+    source_order: Vec<(&'a str, Component)>,
 }
 
 impl<'a> PK11URIMapping<'a> {
@@ -275,6 +443,658 @@ impl<'a> PK11URIMapping<'a> {
     pub fn vendor(&self, vendor_attr: &str) -> Option<&Vec<&'a str>> {
         self.vendor.get(vendor_attr)
     }
+
+    /// Retrieve the first value of the *vendor-specific* `vendor_attr` if parsed, which
+    /// suits the (common) case of a single-valued vendor-specific attribute.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:v-attr=val1?v-attr=val2";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// assert_eq!(mapping.vendor_first("v-attr"), Some("val1"));
+    /// assert_eq!(mapping.vendor_first("v-other"), None);
+    /// ```
+    pub fn vendor_first(&self, vendor_attr: &str) -> Option<&'a str> {
+        self.vendor
+            .get(vendor_attr)
+            .and_then(|values| values.first().copied())
+    }
+
+    /// The names of every parsed *vendor-specific* attribute, sorted by name, allowing the
+    /// discovery of attributes to retrieve using [vendor][PK11URIMapping::vendor()].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:v-path=a;object=my-key?v-query=b&v-query=c";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// let vendor_names: Vec<_> = mapping.vendor_names().collect();
+    /// assert_eq!(vendor_names, ["v-path", "v-query"]);
+    /// ```
+    pub fn vendor_names(&self) -> impl Iterator<Item = &'a str> {
+        let mut vendor_names: Vec<_> = self.vendor.keys().copied().collect();
+        vendor_names.sort_unstable();
+        vendor_names.into_iter()
+    }
+
+    /// The names of the standard (path and query) attributes in the order they appeared
+    /// in the parsed PKCS#11 URI, as opposed to the canonical order of the `Display`
+    /// implementation. Vendor-specific attributes aren't included (see [parse_preserving_order]
+    /// for an order which includes them).
+    ///
+    /// The order is recorded whenever a PKCS#11 URI is parsed, costing a `Vec` of at most
+    /// seventeen `&'static str` names per mapping. A mapping which wasn't produced by
+    /// parsing (such as the `Default` one) has no order.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:type=cert;v-attr=val;object=my-certificate?pin-source=file:/etc/token_pin";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// assert_eq!(mapping.attribute_order(), ["type", "object", "pin-source"]);
+    /// ```
+    pub fn attribute_order(&self) -> &[&'static str] {
+        &self.attribute_order
+    }
+
+    /// The number of parsed attributes: every standard attribute plus every value of
+    /// the *vendor-specific* attributes (a vendor-specific attribute given twice counts
+    /// twice).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=my-key;type=private;v-attr=a?v-attr=b";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// assert_eq!(mapping.len(), 4);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.iter().count() + self.vendor.values().map(Vec::len).sum::<usize>()
+    }
+
+    /// Whether the mapping has no attributes at all, as is the case of a bare `pkcs11:`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mapping = pk11_uri_parser::parse("pkcs11:").expect("valid mapping");
+    /// assert!(mapping.is_empty());
+    ///
+    /// let mapping = pk11_uri_parser::parse("pkcs11:?v-attr=a").expect("valid mapping");
+    /// assert!(!mapping.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none() && self.vendor.is_empty()
+    }
+
+    // iteration:
+    /// Iterates over the name and value of every parsed standard attribute: the path
+    /// attributes followed by the query attributes, each in RFC7512 order.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:type=cert;object=my-certificate;v-attr=val?pin-source=file:/etc/token_pin";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// let attributes: Vec<_> = mapping.iter().collect();
+    /// assert_eq!(
+    ///     attributes,
+    ///     [("object", "my-certificate"), ("type", "cert"), ("pin-source", "file:/etc/token_pin")]
+    /// );
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &'a str)> {
+        self.path_attributes()
+            .into_iter()
+            .chain(self.query_attributes())
+            .filter_map(|(attr, value)| Some((attr, value?)))
+    }
+
+    /// Iterates over the name and values of every *vendor-specific* attribute, sorted by name.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:v-path=a?v-query=b&v-query=c";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// let vendor: Vec<_> = mapping.vendor_iter().collect();
+    /// assert_eq!(vendor, [("v-path", &["a"][..]), ("v-query", &["b", "c"][..])]);
+    /// ```
+    pub fn vendor_iter(&self) -> impl Iterator<Item = (&'a str, &[&'a str])> {
+        self.sorted_vendor()
+            .into_iter()
+            .map(|(name, values)| (name, values.as_slice()))
+    }
+
+    /// The span of every parsed (standard and vendor-specific) attribute's `name=value`
+    /// within the *tidied* `pk11_uri` (as per a [PK11URIError]'s `error_span`), in the order
+    /// they appear, such as for highlighting the attributes in an editor. The `pk11_uri`
+    /// must be the one the mapping was parsed from: values which aren't slices of it (such
+    /// as those of a deserialized mapping) have no span.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:type=cert;\n\tobject=my-certificate?v-attr=a";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// assert_eq!(
+    ///     mapping.attribute_spans(pk11_uri),
+    ///     [("type", 7..16), ("object", 17..38), ("v-attr", 39..47)]
+    /// );
+    /// ```
+    pub fn attribute_spans(&self, pk11_uri: &str) -> Vec<(&'a str, Range<usize>)> {
+        let uri_range = pk11_uri.as_bytes().as_ptr_range();
+        let mut spans: Vec<_> = self
+            .iter()
+            .map(|(name, value)| (name as &'a str, value))
+            .chain(
+                self.vendor
+                    .iter()
+                    .flat_map(|(name, values)| values.iter().map(move |value| (*name, *value))),
+            )
+            .filter(|(_, value)| {
+                let value_range = value.as_bytes().as_ptr_range();
+                uri_range.start <= value_range.start && value_range.end <= uri_range.end
+            })
+            .map(|(name, value)| {
+                let (start, end) = related_span(pk11_uri, (value.as_ptr() as usize, value.len()));
+                (name, start..end)
+            })
+            .collect();
+        spans.sort_unstable_by_key(|(_, span)| span.start);
+        spans
+    }
+    // percent-decoded:
+    attr_decoded!(token_decoded from token for pk11-pattr "token");
+    attr_decoded!(manufacturer_decoded from manufacturer for pk11-pattr "manufacturer");
+    attr_decoded!(serial_decoded from serial for pk11-pattr "serial");
+    attr_decoded!(model_decoded from model for pk11-pattr "model");
+    attr_decoded!(library_manufacturer_decoded from library_manufacturer for pk11-pattr "library-manufacturer");
+    attr_decoded!(library_version_decoded from library_version for pk11-pattr "library-version");
+    attr_decoded!(library_description_decoded from library_description for pk11-pattr "library-description");
+    attr_decoded!(object_decoded from object for pk11-pattr "object");
+    attr_decoded!(type_decoded from r#type for pk11-pattr "type");
+    attr_decoded!(slot_description_decoded from slot_description for pk11-pattr "slot-description");
+    attr_decoded!(slot_manufacturer_decoded from slot_manufacturer for pk11-pattr "slot-manufacturer");
+    attr_decoded!(slot_id_decoded from slot_id for pk11-pattr "slot-id");
+    attr_decoded!(pin_source_decoded from pin_source for pk11-qattr "pin-source");
+    attr_decoded!(pin_value_decoded from pin_value for pk11-qattr "pin-value");
+    attr_decoded!(module_name_decoded from module_name for pk11-qattr "module-name");
+    attr_decoded!(module_path_decoded from module_path for pk11-qattr "module-path");
+    /// Retrieve the raw bytes of the `id` path attribute if one was parsed.
+    ///
+    /// The `id` attribute is binary and its value SHOULD be entirely percent-encoded,
+    /// but tools frequently emit partially encoded values. Only `%XX` triplets are
+    /// decoded; every other character is taken as its literal (ASCII) byte.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:id=ab%43cd";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// assert_eq!(mapping.id_bytes(), Ok(Some(vec![0x61, 0x62, 0x43, 0x63, 0x64])));
+    /// ```
+    ///
+    /// A `%` not followed by two hexadecimal digits results in a [DecodeError].
+    pub fn id_bytes(&self) -> Result<Option<Vec<u8>>, DecodeError> {
+        self.id
+            .map(|id| percent::percent_decode(id).map(Cow::into_owned))
+            .transpose()
+    }
+
+    /// Retrieve the `object` path attribute's label, percent-decoded, if one was parsed.
+    ///
+    /// Intended for display purposes (such as a token picker) where *something* should be
+    /// shown regardless of how well-formed the label is, the decoding never fails: a `%` not
+    /// followed by two hexadecimal digits is kept as-is, and decoded bytes which aren't valid
+    /// UTF-8 are replaced by `U+FFFD REPLACEMENT CHARACTER` (as per `String::from_utf8_lossy`).
+    /// The value is borrowed whenever there's nothing to decode.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=caf%C3%A9%20%FF";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("valid mapping");
+    /// assert_eq!(mapping.object_lossy().as_deref(), Some("café \u{FFFD}"));
+    /// ```
+    pub fn object_lossy(&self) -> Option<Cow<'a, str>> {
+        self.object
+            .map(|object| match percent::percent_decode_lossy(object) {
+                Cow::Borrowed(bytes) => String::from_utf8_lossy(bytes),
+                Cow::Owned(bytes) => Cow::Owned(String::from_utf8_lossy(&bytes).into_owned()),
+            })
+    }
+    // numeric:
+    /// Retrieve the `library-version` path attribute's major and (optional) minor version
+    /// numbers if one was parsed. The digits are read as decimal numbers, so leading zeros
+    /// are insignificant: `1.01` results in `(1, Some(1))`.
+    ///
+    /// A version that isn't of the form `M[.N]` (which only a mapping parsed without the
+    /// `validation` feature may hold), or whose numbers exceed `u32::MAX`, results in `None`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mapping = pk11_uri_parser::parse("pkcs11:library-version=2.40").expect("valid mapping");
+    /// assert_eq!(mapping.library_version_parts(), Some((2, Some(40))));
+    ///
+    /// let mapping = pk11_uri_parser::parse("pkcs11:library-version=10").expect("valid mapping");
+    /// assert_eq!(mapping.library_version_parts(), Some((10, None)));
+    /// ```
+    pub fn library_version_parts(&self) -> Option<(u32, Option<u32>)> {
+        let version = self.library_version?;
+        // (`u32::from_str` would also accept a leading '+')
+        let number = |digits: &str| {
+            digits
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| digits.parse().ok())
+                .flatten()
+        };
+        match version.split_once('.') {
+            Some((major, minor)) => Some((number(major)?, Some(number(minor)?))),
+            None => Some((number(version)?, None)),
+        }
+    }
+
+    /// Retrieve the `slot-id` path attribute's numeric value if one was parsed.
+    ///
+    /// RFC7512 puts no upper bound on the number of `slot-id` digits, but a `CK_SLOT_ID`
+    /// can't exceed `u64::MAX`: such a `slot-id` (which gets a `pkcs11 warning:` when
+    /// parsed) results in `None`, as does one that isn't numeric (which only a mapping
+    /// parsed without the `validation` feature may hold).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mapping = pk11_uri_parser::parse("pkcs11:slot-id=0042").expect("valid mapping");
+    /// assert_eq!(mapping.slot_id_num(), Some(42));
+    /// ```
+    pub fn slot_id_num(&self) -> Option<u64> {
+        self.slot_id
+            // (`u64::from_str` would also accept a leading '+')
+            .filter(|slot_id| slot_id.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|slot_id| slot_id.parse().ok())
+    }
+    // serialization:
+    /// Reconstructs the canonical PKCS#11 URI of the mapping (as per its `Display`
+    /// implementation), verifying the result is itself a valid PKCS#11 URI. A mapping
+    /// produced by [parse] always results in `Ok`; a mapping holding values which can't
+    /// be expressed in a valid URI results in the [PK11URIError] of parsing the
+    /// reconstructed URI (assuming the `validation` feature is enabled).
+    ///
+    /// The `TryFrom<&PK11URIMapping>` implementation for `String` is equivalent.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), pk11_uri_parser::PK11URIError> {
+    /// let pk11_uri = "pkcs11:type=cert;
+    ///                        object=my-certificate";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri)?;
+    /// assert_eq!(mapping.to_uri()?, "pkcs11:object=my-certificate;type=cert");
+    ///
+    /// let pk11_uri: String = (&mapping).try_into()?;
+    /// assert_eq!(pk11_uri, "pkcs11:object=my-certificate;type=cert");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_uri(&self) -> Result<String, PK11URIError> {
+        let pk11_uri = self.to_string();
+        parse(&pk11_uri)?;
+        Ok(pk11_uri)
+    }
+
+    /// Reconstructs the PKCS#11 URI with its attributes (vendor-specific ones included) in the
+    /// order, and in the component, they appeared in the parsed one, given the mapping was
+    /// parsed with [ParseOptions::preserve_order] (see [parse_preserving_order]). Values are
+    /// written exactly as they were parsed, so a PKCS#11 URI without formatting whitespace is
+    /// reproduced byte for byte. Otherwise, there's no order to preserve and the result is the
+    /// canonical PKCS#11 URI of the `Display` implementation.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:type=cert;v-attr=a;object=my-certificate?v-attr=b&pin-source=file:/etc/token_pin";
+    /// let mapping = pk11_uri_parser::parse_preserving_order(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(mapping.to_string_preserving_order(), pk11_uri);
+    /// assert_eq!(
+    ///     mapping.to_string(),
+    ///     "pkcs11:object=my-certificate;type=cert?pin-source=file:/etc/token_pin&v-attr=a&v-attr=b"
+    /// );
+    /// ```
+    pub fn to_string_preserving_order(&self) -> String {
+        if self.source_order.is_empty() {
+            return self.to_string();
+        }
+
+        let mut pk11_uri = String::from(PKCS11_SCHEME);
+        let (mut path_delimiter, mut query_delimiter) = ("", "?");
+        // the number of values of each vendor-specific attribute written so far:
+        let mut vendor_written: VendorMap<&str, usize> = VendorMap::new();
+        for &(attr, component) in &self.source_order {
+            let standard = self
+                .path_attributes()
+                .into_iter()
+                .chain(self.query_attributes())
+                .find(|(standard_attr, _)| *standard_attr == attr);
+            let value = match standard {
+                Some((_, value)) => value,
+                None => {
+                    let written = vendor_written.entry(attr).or_default();
+                    *written += 1;
+                    self.vendor
+                        .get(attr)
+                        .and_then(|values| values.get(*written - 1))
+                        .copied()
+                }
+            };
+            // (an attribute skipped while recovering from errors has no value)
+            let Some(value) = value else { continue };
+            let delimiter = match component {
+                Component::Path => core::mem::replace(&mut path_delimiter, ";"),
+                Component::Query => core::mem::replace(&mut query_delimiter, "&"),
+            };
+            pk11_uri.push_str(delimiter);
+            pk11_uri.push_str(attr);
+            pk11_uri.push('=');
+            pk11_uri.push_str(value);
+        }
+        pk11_uri
+    }
+
+    /// Records the name of the successfully assigned `pk11_attr` (and the `component` it
+    /// appeared in) when preserving the order of attributes. Just as its value is kept
+    /// only once, so is a standard attribute (or a vendor-specific one of the path).
+    fn record_source_order(&mut self, pk11_attr: &'a str, component: Component) {
+        let Ok((attr, _)) = common::split_attribute(pk11_attr) else {
+            return;
+        };
+        let once = component == Component::Path || standard_component(attr).is_some();
+        if once
+            && self
+                .source_order
+                .iter()
+                .any(|(recorded, _)| *recorded == attr)
+        {
+            return;
+        }
+        self.source_order.push((attr, component));
+    }
+    // relations:
+    /// Whether every attribute parsed into this mapping was also parsed into `other`
+    /// with an equal value, ie, whether `other` is the same or a more specific PKCS#11 URI.
+    /// Values are compared exactly as they were parsed (no percent-decoding takes place).
+    ///
+    /// A *vendor-specific* attribute participates with its whole list of values: each of
+    /// this mapping's values must also be among `other`'s values for that attribute
+    /// (regardless of order).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let general = pk11_uri_parser::parse("pkcs11:token=my-token;v-attr=a").expect("valid mapping");
+    /// let specific = pk11_uri_parser::parse("pkcs11:token=my-token;object=my-key?v-attr=b&v-attr=a")
+    ///     .expect("valid mapping");
+    /// assert!(general.is_subset_of(&specific));
+    /// assert!(!specific.is_subset_of(&general));
+    /// ```
+    pub fn is_subset_of(&self, other: &PK11URIMapping) -> bool {
+        let standard_subset = self
+            .path_attributes()
+            .into_iter()
+            .zip(other.path_attributes())
+            .chain(
+                self.query_attributes()
+                    .into_iter()
+                    .zip(other.query_attributes()),
+            )
+            .all(|((_, value), (_, other_value))| value.is_none() || value == other_value);
+
+        standard_subset
+            && self.vendor.iter().all(|(name, values)| {
+                other.vendor.get(name).is_some_and(|other_values| {
+                    values.iter().all(|value| other_values.contains(value))
+                })
+            })
+    }
+    // filtering:
+    /// A copy of the mapping retaining all of its standard attributes, both path and
+    /// query, but none of its vendor-specific attributes; such as for sanitizing a
+    /// PKCS#11 URI before sharing it. Displaying the copy results in the vendor-free
+    /// canonical PKCS#11 URI.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=my-key;v-attr=val?module-name=p11-kit&v-attr=other";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(
+    ///     mapping.standard_only().to_string(),
+    ///     "pkcs11:object=my-key?module-name=p11-kit"
+    /// );
+    /// ```
+    pub fn standard_only(&self) -> PK11URIMapping<'a> {
+        PK11URIMapping {
+            vendor: VendorMap::new(),
+            attribute_order: self.attribute_order.clone(),
+            source_order: self
+                .source_order
+                .iter()
+                .copied()
+                .filter(|(name, _)| standard_component(name).is_some())
+                .collect(),
+            ..*self
+        }
+    }
+
+    /// The standard `pk11-pattr` names paired with their (possibly parsed) values, in RFC7512 order.
+    fn path_attributes(&self) -> [(&'static str, Option<&'a str>); 13] {
+        [
+            ("token", self.token),
+            ("manufacturer", self.manufacturer),
+            ("serial", self.serial),
+            ("model", self.model),
+            ("library-manufacturer", self.library_manufacturer),
+            ("library-version", self.library_version),
+            ("library-description", self.library_description),
+            ("object", self.object),
+            ("type", self.r#type),
+            ("id", self.id),
+            ("slot-description", self.slot_description),
+            ("slot-manufacturer", self.slot_manufacturer),
+            ("slot-id", self.slot_id),
+        ]
+    }
+
+    /// Formats the attributes (but not their order) as a struct named `name` would be by
+    /// `#[derive(Debug)]`.
+    pub(crate) fn fmt_attributes(&self, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(name)
+            .field("token", &self.token)
+            .field("manufacturer", &self.manufacturer)
+            .field("serial", &self.serial)
+            .field("model", &self.model)
+            .field("library_manufacturer", &self.library_manufacturer)
+            .field("library_version", &self.library_version)
+            .field("library_description", &self.library_description)
+            .field("object", &self.object)
+            .field("type", &self.r#type)
+            .field("id", &self.id)
+            .field("slot_description", &self.slot_description)
+            .field("slot_manufacturer", &self.slot_manufacturer)
+            .field("slot_id", &self.slot_id)
+            .field("pin_source", &self.pin_source)
+            .field("pin_value", &self.pin_value)
+            .field("module_name", &self.module_name)
+            .field("module_path", &self.module_path)
+            .field("vendor", &self.vendor)
+            .finish()
+    }
+
+    /// The value of the (standard or vendor-specific) attribute named `attr`, the
+    /// first one of a vendor-specific attribute.
+    fn attribute_value(&self, attr: &str) -> Option<&'a str> {
+        match standard_component(attr) {
+            Some(_) => self
+                .iter()
+                .find_map(|(standard_attr, value)| (standard_attr == attr).then_some(value)),
+            None => self.vendor_first(attr),
+        }
+    }
+
+    /// The field of the standard attribute named `attr`, if there is one.
+    fn standard_attribute_mut(&mut self, attr: &str) -> Option<&mut Option<&'a str>> {
+        Some(match attr {
+            "token" => &mut self.token,
+            "manufacturer" => &mut self.manufacturer,
+            "serial" => &mut self.serial,
+            "model" => &mut self.model,
+            "library-manufacturer" => &mut self.library_manufacturer,
+            "library-version" => &mut self.library_version,
+            "library-description" => &mut self.library_description,
+            "object" => &mut self.object,
+            "type" => &mut self.r#type,
+            "id" => &mut self.id,
+            "slot-description" => &mut self.slot_description,
+            "slot-manufacturer" => &mut self.slot_manufacturer,
+            "slot-id" => &mut self.slot_id,
+            "pin-source" => &mut self.pin_source,
+            "pin-value" => &mut self.pin_value,
+            "module-name" => &mut self.module_name,
+            "module-path" => &mut self.module_path,
+            _ => return None,
+        })
+    }
+
+    /// The standard `pk11-qattr` names paired with their (possibly parsed) values, in RFC7512 order.
+    fn query_attributes(&self) -> [(&'static str, Option<&'a str>); 4] {
+        [
+            ("pin-source", self.pin_source),
+            ("pin-value", self.pin_value),
+            ("module-name", self.module_name),
+            ("module-path", self.module_path),
+        ]
+    }
+
+    /// Vendor-specific entries sorted by attribute name (the underlying
+    /// `HashMap` has no stable iteration order).
+    fn sorted_vendor(&self) -> Vec<(&'a str, &Vec<&'a str>)> {
+        let mut vendor: Vec<_> = self
+            .vendor
+            .iter()
+            .map(|(name, values)| (*name, values))
+            .collect();
+        vendor.sort_unstable_by_key(|(name, _)| *name);
+        vendor
+    }
+}
+
+/// Formats the attributes of the mapping, leaving out the order in which they were parsed
+/// (see [attribute_order][PK11URIMapping::attribute_order()]).
+impl fmt::Debug for PK11URIMapping<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_attributes("PK11URIMapping", f)
+    }
+}
+
+/// Compares mappings semantically: the order in which attributes appeared in their
+/// PKCS#11 URIs (see [attribute_order][PK11URIMapping::attribute_order()]) is irrelevant,
+/// so `pkcs11:object=my-key;type=cert` equals `pkcs11:type=cert;object=my-key`.
+///
+/// The *only* order-sensitive part is the sequence of values of a single vendor-specific
+/// attribute (as in `?v-attr=a&v-attr=b`), which is kept as given. Values are compared
+/// exactly as they were parsed, so differently percent-encoded values (such as `my-key`
+/// and `my%2Dkey`) are not equal; compare [normalized][PK11URIMapping::normalize()]
+/// PKCS#11 URIs to disregard encoding differences.
+///
+/// ```
+/// let pk11_uri = "pkcs11:object=my-key;type=cert;v-attr=a?module-name=p11-kit&v-attr=b";
+/// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+///
+/// let pk11_uri = "pkcs11:type=cert;object=my-key?v-attr=a&module-name=p11-kit&v-attr=b";
+/// assert_eq!(mapping, pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid"));
+///
+/// let pk11_uri = "pkcs11:type=cert;object=my-key?v-attr=b&module-name=p11-kit&v-attr=a";
+/// assert_ne!(mapping, pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid"));
+/// ```
+impl PartialEq for PK11URIMapping<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.path_attributes() == other.path_attributes()
+            && self.query_attributes() == other.query_attributes()
+            && self.vendor == other.vendor
+    }
+}
+
+impl Eq for PK11URIMapping<'_> {}
+
+/// Hashes mappings consistently with their semantic `PartialEq`, so equal mappings hash
+/// equally and a mapping may key a `HashMap` (or be deduplicated in a `HashSet`): the
+/// attribute order is irrelevant, whereas the sequence of a vendor-specific attribute's
+/// values participates in the hash, just as it does in the comparison.
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// let mappings: HashSet<_> = [
+///     "pkcs11:object=my-key;type=cert?v-attr=a&v-attr=b",
+///     "pkcs11:type=cert;v-attr=a;object=my-key?v-attr=b",
+///     "pkcs11:object=my-key;type=cert?v-attr=b&v-attr=a",
+/// ]
+/// .into_iter()
+/// .map(|pk11_uri| pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid"))
+/// .collect();
+/// assert_eq!(mappings.len(), 2);
+/// ```
+impl hash::Hash for PK11URIMapping<'_> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.path_attributes().hash(state);
+        self.query_attributes().hash(state);
+        // (vendor-specific attributes hashed in a stable, sorted, order)
+        self.sorted_vendor().hash(state);
+    }
+}
+
+/// Reconstructs the canonical PKCS#11 URI of the mapping; see [PK11URIMapping::to_uri()].
+impl TryFrom<&PK11URIMapping<'_>> for String {
+    type Error = PK11URIError;
+
+    fn try_from(mapping: &PK11URIMapping<'_>) -> Result<Self, Self::Error> {
+        mapping.to_uri()
+    }
+}
+
+/// The PKCS#11 URI component an attribute belongs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Component {
+    /// The `pk11-path` (attributes separated by `;`).
+    Path,
+    /// The `pk11-query` following the `?` (attributes separated by `&`).
+    Query,
+}
+
+/// Identifies the [Component] the *standard* attribute `name` belongs in: one of the thirteen
+/// `pk11-pattr` names results in [Component::Path], one of the four `pk11-qattr` names results
+/// in [Component::Query]. Vendor-specific (or unknown) names have no fixed placement so
+/// result in `None`.
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::{standard_component, Component};
+///
+/// assert_eq!(standard_component("object"), Some(Component::Path));
+/// assert_eq!(standard_component("pin-source"), Some(Component::Query));
+/// assert_eq!(standard_component("vendor-attribute"), None);
+/// ```
+pub fn standard_component(name: &str) -> Option<Component> {
+    if common::PK11_PATTR_NAMES.contains(&name) {
+        Some(Component::Path)
+    } else if common::PK11_QATTR_NAMES.contains(&name) {
+        Some(Component::Query)
+    } else {
+        None
+    }
 }
 
 /// Parses and verifies the contents of the given `pk11_uri` &str, making
@@ -282,19 +1102,424 @@ impl<'a> PK11URIMapping<'a> {
 /// specifications will result in issuing a [PK11URIError].
 ///
 /// The contents of the `PK11URIMapping` are string slices of the `pk11_uri`,
-/// so if you need the mapping to outlive the pk11_uri, simply clone it.
+/// so if you need the mapping to outlive the pk11_uri, use [parse_owned] (or
+/// [to_owned_mapping][PK11URIMapping::to_owned_mapping()]) instead.
+///
+/// The first '?' always begins the query, so a '?' within a *path* attribute's value
+/// must be percent-encoded as `%3F` (as in `object=what%3Fnot`); assuming the `validation`
+/// feature is enabled, an unencoded one which evidently wasn't meant to begin the query
+/// is pointed out as such.
 ///
 /// [rfc7512]: <https://datatracker.ietf.org/doc/html/rfc7512>
-pub fn parse(pk11_uri: &str) -> Result<PK11URIMapping, PK11URIError> {
+pub fn parse(pk11_uri: &str) -> Result<PK11URIMapping<'_>, PK11URIError> {
+    parse_with_options(pk11_uri, &ParseOptions::default())
+}
+
+/// Like [parse], but adjusted according to the given [ParseOptions].
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::{parse_with_options, ParseOptions};
+///
+/// let options = ParseOptions {
+///     extra_query_attributes: &["pin-id"],
+///     ..ParseOptions::default()
+/// };
+/// let pk11_uri = "pkcs11:object=my-key?pin-id=01";
+/// let mapping = parse_with_options(pk11_uri, &options).expect("mapping should be valid");
+/// assert_eq!(mapping.vendor("pin-id"), Some(&vec!["01"]));
+///
+/// # #[cfg(feature = "validation")]
+/// parse_with_options("pkcs11:?pin-id=01&pin-id=02", &options)
+///     .expect_err("duplicate pin-id attribute names should not be valid");
+/// ```
+pub fn parse_with_options<'a>(
+    pk11_uri: &'a str,
+    options: &ParseOptions,
+) -> Result<PK11URIMapping<'a>, PK11URIError> {
+    parse_reporting_warnings(pk11_uri, options, &mut String::new(), None)
+}
+
+/// Like [parse], but records the order in which the attributes appeared (see
+/// [ParseOptions::preserve_order]), so that the mapping can be reserialized faithfully using
+/// [to_string_preserving_order][PK11URIMapping::to_string_preserving_order()], such as for
+/// signed PKCS#11 URIs.
+///
+/// ## Examples
+///
+/// ```
+/// let pk11_uri = "pkcs11:v-attr=a;type=cert;object=my-certificate";
+/// let mapping = pk11_uri_parser::parse_preserving_order(pk11_uri).expect("mapping should be valid");
+/// assert_eq!(mapping.to_string_preserving_order(), pk11_uri);
+/// ```
+pub fn parse_preserving_order(pk11_uri: &str) -> Result<PK11URIMapping<'_>, PK11URIError> {
+    parse_with_options(
+        pk11_uri,
+        &ParseOptions {
+            preserve_order: true,
+            ..ParseOptions::default()
+        },
+    )
+}
+
+/// Like [parse], but reuses `buf` as scratch space for locating a violation within the
+/// tidied path or query rather than allocating afresh; `buf`'s prior content is discarded.
+/// This saves an allocation per violation when parsing many PKCS#11 URIs, such as in a
+/// validation pipeline where errors are frequent. The error path isn't allocation-free,
+/// however: a [PK11URIError] owns its own (tidied) copy of the uri, which is always
+/// allocated.
+///
+/// ## Examples
+///
+/// ```
+/// let mut buf = String::new();
+/// for pk11_uri in ["pkcs11:object=my-key", "pkcs11:object=my key", "pkcs11:type=cert"] {
+///     match pk11_uri_parser::parse_with_buf(pk11_uri, &mut buf) {
+///         Ok(mapping) => println!("{mapping}"),
+///         Err(err) => println!("{err}"),
+///     }
+/// }
+/// ```
+pub fn parse_with_buf<'a>(
+    pk11_uri: &'a str,
+    buf: &mut String,
+) -> Result<PK11URIMapping<'a>, PK11URIError> {
+    parse_reporting_warnings(pk11_uri, &ParseOptions::default(), buf, None)
+}
+
+/// Like [parse], but for a PKCS#11 URI given as raw bytes (such as read from a socket).
+///
+/// A URI is made of ASCII characters only (as per [RFC3986][rfc3986]), anything else
+/// having to be percent-encoded, so a byte which isn't ASCII results in a [PK11URIError]
+/// of kind [ViolationKind::NonAsciiByte] identifying the first such byte, rather than
+/// having to (lossily) convert the input to UTF-8 beforehand.
+///
+/// [rfc3986]: <https://datatracker.ietf.org/doc/html/rfc3986#section-2>
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::{parse_bytes, ViolationKind};
+///
+/// let mapping = parse_bytes(b"pkcs11:object=my-key").expect("mapping should be valid");
+/// assert_eq!(mapping.object(), Some("my-key"));
+///
+/// let err = parse_bytes(b"pkcs11:object=caf\xC3\xA9").expect_err("non-ASCII bytes");
+/// assert_eq!(err.kind(), ViolationKind::NonAsciiByte);
+/// assert_eq!(err.error_span(), (17, 19));
+/// ```
+pub fn parse_bytes(pk11_uri: &[u8]) -> Result<PK11URIMapping<'_>, PK11URIError> {
+    match pk11_uri.iter().position(|byte| !byte.is_ascii()) {
+        None => parse(core::str::from_utf8(pk11_uri).expect("ASCII is valid UTF-8")),
+        Some(offset) => {
+            let lossy = String::from_utf8_lossy(pk11_uri);
+            // (everything preceding the offending byte is ASCII, hence left as-is)
+            let start = tidy_len(&lossy[..offset]);
+            let len = lossy[offset..].chars().next().map_or(1, char::len_utf8);
+            let byte = pk11_uri[offset];
+            Err(PK11URIError {
+                pk11_uri: tidy(&lossy),
+                error_span: (start, start + len),
+                kind: ViolationKind::NonAsciiByte,
+                violation: String::from(
+                    "Invalid `pk11-URI`: Section 2 of [RFC3986] specifies a URI consists of ASCII characters only.",
+                ),
+                help: format!(
+                    "Percent-encode the non-ASCII byte `0x{byte:02X}` (at offset {offset}) as `%{byte:02X}`."
+                ),
+                secondary_span: None,
+            })
+        }
+    }
+}
+
+/// Parses as much of the `pk11_uri` as possible for diagnostic purposes ("show me what
+/// you could read"): rather than failing upon the first violation, every attribute is
+/// attempted, those in violation are skipped, and their [PK11URIError]s are returned
+/// alongside the mapping of the attributes which could be parsed.
+///
+/// The mapping is therefore possibly *incomplete* (the errors being empty when it's not)
+/// and should not be relied upon to identify a PKCS#11 object as the uri intends. Of a
+/// duplicated attribute, the first occurrence is kept. Only a uri which isn't a PKCS#11
+/// URI at all (such as one lacking the `pkcs11:` scheme) results in no mapping.
+///
+/// ## Examples
+///
+/// ```
+/// let pk11_uri = "pkcs11:token=my token;object=my-key;type=private-key?module-name=p11-kit";
+/// let (mapping, errors) = pk11_uri_parser::parse_best_effort(pk11_uri);
+/// let mapping = mapping.expect("a partial mapping");
+/// assert_eq!(mapping.object(), Some("my-key"));
+/// assert_eq!(mapping.module_name(), Some("p11-kit"));
+/// # #[cfg(feature = "validation")]
+/// # {
+/// assert_eq!((mapping.token(), mapping.r#type()), (None, None));
+/// assert_eq!(errors.len(), 2);
+/// # }
+/// ```
+pub fn parse_best_effort(pk11_uri: &str) -> (Option<PK11URIMapping<'_>>, Vec<PK11URIError>) {
+    let mut errors = vec![];
+    match parse_reporting_warnings(
+        pk11_uri,
+        &ParseOptions::default(),
+        &mut String::new(),
+        Some(&mut errors),
+    ) {
+        Ok(mapping) => (Some(mapping), errors),
+        Err(err) => (None, vec![err]),
+    }
+}
+
+/// Like [parse], but rather than stopping at the first violation, results in *every*
+/// one of them: each offending attribute is reported by its own [PK11URIError], spanning
+/// the attribute within the (tidied) uri. Intended for lint-style usage, where the whole
+/// list of issues should be fixed at once; [parse_best_effort] additionally results in
+/// the mapping of the attributes which could be parsed.
+///
+/// A shorthand for [parse_with_options] given an [error_sink][ParseOptions::error_sink].
+///
+/// ## Examples
+///
+/// ```
+/// let pk11_uri = "pkcs11:token=my token;object=my-key;type=private-key";
+/// # #[cfg(feature = "validation")]
+/// # {
+/// let errors = pk11_uri_parser::parse_all(pk11_uri).expect_err("two violations");
+/// let error_spans: Vec<_> = errors.iter().map(|err| err.error_span()).collect();
+/// assert_eq!(error_spans, [(7, 21), (36, 52)]);
+/// # }
+/// ```
+pub fn parse_all(pk11_uri: &str) -> Result<PK11URIMapping<'_>, Vec<PK11URIError>> {
+    let errors = RefCell::new(vec![]);
+    let error_sink = |err| errors.borrow_mut().push(err);
+    let options = ParseOptions::default().error_sink(&error_sink);
+    match parse_with_options(pk11_uri, &options) {
+        Ok(mapping) if errors.borrow().is_empty() => Ok(mapping),
+        Ok(_) => Err(errors.into_inner()),
+        Err(err) => Err(vec![err]),
+    }
+}
+
+/// Parses the `pk11_uri`, printing `pkcs11 warning:` messages if relevant to the build
+/// (or handing them to the [warning_sink][ParseOptions::warning_sink]). Unless `recovered`
+/// is given, the errors of individual attributes are handed to the
+/// [error_sink][ParseOptions::error_sink] if there is one.
+fn parse_reporting_warnings<'a>(
+    pk11_uri: &'a str,
+    options: &ParseOptions,
+    buf: &mut String,
+    recovered: Option<&mut Vec<PK11URIError>>,
+) -> Result<PK11URIMapping<'a>, PK11URIError> {
+    let mut sunk_errors = vec![];
+    let recovered = match (recovered, options.error_sink) {
+        (None, Some(_)) => Some(&mut sunk_errors),
+        (recovered, _) => recovered,
+    };
+
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    let mut warnings = Warnings::new(pk11_uri);
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    let result = parse_uri(pk11_uri, options, buf, recovered, &mut warnings);
+    #[cfg(not(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    )))]
+    let result = parse_uri(pk11_uri, options, buf, recovered);
+
+    if let Some(error_sink) = options.error_sink {
+        sunk_errors.into_iter().for_each(error_sink);
+    }
+
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    let warnings = warnings.into_vec();
+    #[cfg(feature = "warnings")]
+    let result = match (result, warnings.first()) {
+        (Ok(_), Some(warning)) if options.strict_advisories => {
+            Err(warning.clone().into_strict_error(pk11_uri))
+        }
+        (result, _) => result,
+    };
+    #[cfg(feature = "warnings")]
+    if let Some(warning_sink) = options.warning_sink {
+        warnings.into_iter().for_each(warning_sink);
+        return result;
+    }
+
+    #[cfg(all(debug_assertions, feature = "debug_warnings"))]
+    for warning in warnings {
+        println!("{warning}");
+    }
+
+    result
+}
+
+/// Like [parse], but rather than printing `pkcs11 warning:` messages, returns any
+/// [PK11URIWarning]s alongside the mapping; this is available for `--release` builds
+/// as well. The warnings are in the order their attributes appear in the `pk11_uri`,
+/// followed by those concerning the combination of attributes. Each advisory that fired
+/// is a warning of its own, so a test may assert exactly how many a PKCS#11 URI results in.
+///
+/// ## Examples
+///
+/// ```
+/// let pk11_uri = "pkcs11:x-muppet=cookie<monster";
+/// let (mapping, warnings) = pk11_uri_parser::parse_with_warnings(pk11_uri).expect("mapping should be valid");
+/// assert_eq!(mapping.vendor("x-muppet"), Some(&vec!["cookie<monster"]));
+///
+/// assert_eq!(warnings.len(), 2);
+/// // the deprecated "x-" prefix:
+/// assert_eq!((warnings[0].attribute(), warnings[0].offset()), ("x-muppet", 7));
+/// // the '<' that SHOULD be percent-encoded:
+/// assert_eq!((warnings[1].attribute(), warnings[1].offset()), ("x-muppet", 22));
+/// ```
+#[cfg(feature = "warnings")]
+pub fn parse_with_warnings(
+    pk11_uri: &str,
+) -> Result<(PK11URIMapping<'_>, Vec<PK11URIWarning>), PK11URIError> {
+    parse_with_warnings_and_options(pk11_uri, &ParseOptions::default())
+}
+
+/// Like [parse_with_warnings], but adjusted according to the given [ParseOptions] (such
+/// as [vendor_allowed_chars][ParseOptions::vendor_allowed_chars], which quiets warnings).
+/// The warnings are returned rather than handed to any
+/// [warning_sink][ParseOptions::warning_sink].
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::{parse_with_warnings_and_options, ParseOptions};
+///
+/// let options = ParseOptions {
+///     vendor_allowed_chars: &['{', '}'],
+///     ..ParseOptions::default()
+/// };
+/// let pk11_uri = "pkcs11:v-attr={a}?v-query={b}";
+/// let (_mapping, warnings) = parse_with_warnings_and_options(pk11_uri, &options)
+///     .expect("mapping should be valid");
+/// assert!(warnings.is_empty());
+/// ```
+#[cfg(feature = "warnings")]
+pub fn parse_with_warnings_and_options<'a>(
+    pk11_uri: &'a str,
+    options: &ParseOptions,
+) -> Result<(PK11URIMapping<'a>, Vec<PK11URIWarning>), PK11URIError> {
+    let warnings = RefCell::new(vec![]);
+    let warning_sink = |warning| warnings.borrow_mut().push(warning);
+    let options = options.clone().warning_sink(&warning_sink);
+    let mapping = parse_with_options(pk11_uri, &options)?;
+    Ok((mapping, warnings.into_inner()))
+}
+
+/// Like [parse_with_warnings], but hands each [PK11URIWarning] to the given `sink` (such as a
+/// logger) rather than returning them. Unlike `parse_with_warnings`, any warnings issued before
+/// a [PK11URIError] are still handed over, so the sink sees everything that was amiss.
+///
+/// A shorthand for [parse_with_options] given a [warning_sink][ParseOptions::warning_sink].
+///
+/// ## Examples
+///
+/// ```
+/// let pk11_uri = "pkcs11:object=my<key>";
+/// let mut advisories = vec![];
+/// let mapping = pk11_uri_parser::parse_with_sink(pk11_uri, &mut |warning| advisories.push(warning.to_string()))
+///     .expect("mapping should be valid");
+/// assert_eq!(mapping.object(), Some("my<key>"));
+/// assert_eq!(advisories.len(), 2);
+/// ```
+#[cfg(feature = "warnings")]
+pub fn parse_with_sink<'a>(
+    pk11_uri: &'a str,
+    sink: &mut dyn FnMut(PK11URIWarning),
+) -> Result<PK11URIMapping<'a>, PK11URIError> {
+    let sink = RefCell::new(sink);
+    let warning_sink = |warning| (sink.borrow_mut())(warning);
+    parse_with_options(
+        pk11_uri,
+        &ParseOptions::default().warning_sink(&warning_sink),
+    )
+}
+
+/// Like [parse], but also refuses a PKCS#11 URI which merits a warning (such as an `id`
+/// which isn't percent-encoded, a vendor-specific attribute with the deprecated "x-" prefix,
+/// or both `module-name` and `module-path`), failing with a [ViolationKind::StrictAdvisory]
+/// [PK11URIError] identifying the first of them. For deployments wanting every RFC7512
+/// "SHOULD/SHOULD NOT" guideline enforced.
+///
+/// A shorthand for [parse_with_options] given
+/// [strict_advisories][ParseOptions::strict_advisories].
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::ViolationKind;
+///
+/// assert!(pk11_uri_parser::parse_strict("pkcs11:object=my-key;id=%01%02").is_ok());
+///
+/// let err = pk11_uri_parser::parse_strict("pkcs11:object=my<key>").expect_err("unencoded '<'");
+/// assert_eq!(err.kind(), ViolationKind::StrictAdvisory);
+/// assert_eq!(err.error_span(), (16, 17));
+/// ```
+#[cfg(feature = "warnings")]
+pub fn parse_strict(pk11_uri: &str) -> Result<PK11URIMapping<'_>, PK11URIError> {
+    parse_with_options(pk11_uri, &ParseOptions::default().strict_advisories(true))
+}
+
+/// Implementation of [parse_with_options], collecting warnings (if they're relevant to the build) into `warnings`.
+/// Given `recovered`, the errors of individual attributes are recorded there (the attribute being skipped)
+/// rather than failing the parse.
+fn parse_uri<'a>(
+    pk11_uri: &'a str,
+    options: &ParseOptions,
+    buf: &mut String,
+    mut recovered: Option<&mut Vec<PK11URIError>>,
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    warnings: &mut Warnings<'a>,
+) -> Result<PK11URIMapping<'a>, PK11URIError> {
     #[cfg(feature = "validation")]
-    if !pk11_uri.starts_with(PKCS11_SCHEME) {
+    // (per RFC3986, the scheme is case-insensitive)
+    if !starts_with_ignore_ascii_case(pk11_uri, PKCS11_SCHEME) {
+        // So close! `pkcs11` lacking its ':' is an all too common typo:
+        let scheme_name = &PKCS11_SCHEME[..PKCS11_SCHEME_LEN - 1];
+        let (error_span, help) = if starts_with_ignore_ascii_case(pk11_uri, scheme_name) {
+            (
+                (scheme_name.len(), scheme_name.len()),
+                String::from("Add the missing ':' after `pkcs11`."),
+            )
+        } else {
+            match pk11_uri.split_once(':') {
+                // Some other scheme, such as a copy-pasted `pkcs12:`:
+                Some((scheme, _)) if is_scheme(scheme) => (
+                    (0, tidy_len(scheme) + 1),
+                    format!("Found `{}:`, expected `pkcs11:`.", tidy(scheme)),
+                ),
+                _ => (
+                    (0, tidy_len(pk11_uri)),
+                    String::from("PKCS#11 URI must start with `pkcs11:`."),
+                ),
+            }
+        };
         return Err(PK11URIError {
             pk11_uri: tidy(pk11_uri),
-            error_span: (0, 0),
+            error_span,
+            kind: ViolationKind::MissingScheme,
             violation: String::from(
                 r#"Invalid `pk11-URI`: expected `"pkcs11:" pk11-path [ "?" pk11-query ]`."#,
             ),
-            help: String::from("PKCS#11 URI must start with `pkcs11:`."),
+            help,
+            secondary_span: None,
         });
     }
 
@@ -307,102 +1532,387 @@ pub fn parse(pk11_uri: &str) -> Result<PK11URIMapping, PK11URIError> {
     // If we've got a `pk11-path`, attempt to assign its `pk11-pattr` values:
     if let Some(pk11_path) = pk11_uri
         .get(PKCS11_SCHEME_LEN..query_component_index.unwrap_or(pk11_uri.len()))
+        .map(|pk11_path| without_trailing_delimiter(pk11_path, ';', options))
         .filter(|pk11_path| !pk11_path.is_empty())
     {
         pk11_path
             .split(';')
             .enumerate()
             .try_for_each(|(count, pk11_pattr)| {
-                pk11_pattr::assign(pk11_pattr, &mut mapping).map_err(|validation_err| {
-                    let tidy_pk11_uri = tidy(pk11_uri);
-                    let tidy_pk11_path = tidy(pk11_path);
-                    let tidy_pk11_pattr = tidy(pk11_pattr);
+                #[cfg(any(
+                    feature = "warnings",
+                    all(debug_assertions, feature = "debug_warnings")
+                ))]
+                let assigned = pk11_pattr::assign(pk11_pattr, &mut mapping, options, warnings);
+                #[cfg(not(any(
+                    feature = "warnings",
+                    all(debug_assertions, feature = "debug_warnings")
+                )))]
+                let assigned = pk11_pattr::assign(pk11_pattr, &mut mapping, options);
+                if options.preserve_order && assigned.is_ok() {
+                    mapping.record_source_order(pk11_pattr, Component::Path);
+                }
+                let assigned = assigned.map_err(|validation_err| {
+                    // The tidied path followed by the tidied pattr:
+                    buf.clear();
+                    tidy_into(pk11_path, buf);
+                    let tidy_pk11_path_len = buf.len();
+                    tidy_into(pk11_pattr, buf);
+                    let (tidy_pk11_path, tidy_pk11_pattr) = buf.split_at(tidy_pk11_path_len);
 
+                    let span = validation_err.span;
+                    let focus = validation_err.focus;
+                    let related_span = validation_err
+                        .duplicate_of
+                        .map(|first_value| related_span(pk11_uri, first_value));
+                    let mut kind = validation_err.kind;
                     let mut violation = validation_err.violation;
                     let mut help = validation_err.help;
+                    if kind == ViolationKind::MalformedComponent {
+                        help = truncated_value_help(pk11_path, pk11_pattr, ';').unwrap_or(help);
+                    }
 
                     let error_start = if !tidy_pk11_pattr.is_empty() {
-                        tidy_pk11_path.find(&tidy_pk11_pattr).unwrap()
+                        // (locating the pattr by its offset rather than by searching for
+                        // it, as an identical pattr may well precede it)
+                        tidy_len(&pk11_path[..offset_within(pk11_path, pk11_pattr)])
                     } else {
                         // assign this here rather than adding O(n) runtime checks
                         // for basically an unlikely outlier type of error:
+                        kind = ViolationKind::MisplacedDelimiter;
                         violation = String::from("Misplaced path delimiter.");
                         help = String::from("Remove the misplaced ';' delimiter.");
-                        find_empty_attr_index(&tidy_pk11_path, count, ';')
+                        find_empty_attr_index(tidy_pk11_path, count, ';')
                     } + PKCS11_SCHEME_LEN;
+                    let focus_span = focus.map(|offset| {
+                        error_span(error_start, pk11_pattr, Some((offset, offset + 1)))
+                    });
                     PK11URIError {
-                        pk11_uri: tidy_pk11_uri,
-                        error_span: (error_start, error_start + tidy_pk11_pattr.len()),
+                        pk11_uri: tidy(pk11_uri),
+                        error_span: error_span(error_start, pk11_pattr, span),
+                        kind,
                         violation,
                         help,
+                        secondary_span: related_span
+                            .map(SecondarySpan::Related)
+                            .or(focus_span.map(SecondarySpan::Focus)),
                     }
-                })
+                });
+                recover(assigned, recovered.as_deref_mut())
             })?;
     }
 
+    // A '?' meant to be part of the last path value (rather than beginning the query)
+    // would otherwise result in a confusing error about the "query" following it:
+    #[cfg(feature = "validation")]
+    let query_component_index = match query_component_index
+        .and_then(|query_component_index| stray_query_delimiter(pk11_uri, query_component_index))
+    {
+        Some(stray) => {
+            recover(Err(stray), recovered.as_deref_mut())?;
+            None
+        }
+        None => query_component_index,
+    };
+
     // If we've got a `pk11-query`, attempt to assign its `pk11-qattr` values:
-    if query_component_index.is_some() {
+    if let Some(query_component_index) = query_component_index {
         // Assuming it's not empty, query component is from
         // the identified '?' to the remainder of the `pk11_uri`:
         if let Some(pk11_query) = pk11_uri
-            .get(query_component_index.unwrap() + 1..)
+            .get(query_component_index + 1..)
+            .map(|pk11_query| without_trailing_delimiter(pk11_query, '&', options))
             .filter(|pk11_query| !pk11_query.is_empty())
         {
             pk11_query
                 .split('&')
                 .enumerate()
                 .try_for_each(|(count, pk11_qattr)| {
-                    pk11_qattr::assign(pk11_qattr, &mut mapping).map_err(|validation_err| {
-                        let tidy_pk11_uri = tidy(pk11_uri);
-                        let tidy_pk11_query = tidy(pk11_query);
-                        let tidy_pk11_qattr = tidy(pk11_qattr);
+                    #[cfg(any(
+                        feature = "warnings",
+                        all(debug_assertions, feature = "debug_warnings")
+                    ))]
+                    let assigned = pk11_qattr::assign(pk11_qattr, &mut mapping, options, warnings);
+                    #[cfg(not(any(
+                        feature = "warnings",
+                        all(debug_assertions, feature = "debug_warnings")
+                    )))]
+                    let assigned = pk11_qattr::assign(pk11_qattr, &mut mapping, options);
+                    if options.preserve_order && assigned.is_ok() {
+                        mapping.record_source_order(pk11_qattr, Component::Query);
+                    }
+                    let assigned = assigned.map_err(|validation_err| {
+                        // The tidied query followed by the tidied qattr:
+                        buf.clear();
+                        tidy_into(pk11_query, buf);
+                        let tidy_pk11_query_len = buf.len();
+                        tidy_into(pk11_qattr, buf);
+                        let (tidy_pk11_query, tidy_pk11_qattr) = buf.split_at(tidy_pk11_query_len);
 
+                        let span = validation_err.span;
+                        let focus = validation_err.focus;
+                        let related_span = validation_err
+                            .duplicate_of
+                            .map(|first_value| related_span(pk11_uri, first_value));
+                        let mut kind = validation_err.kind;
                         let mut violation = validation_err.violation;
                         let mut help = validation_err.help;
+                        if kind == ViolationKind::MalformedComponent {
+                            help =
+                                truncated_value_help(pk11_query, pk11_qattr, '&').unwrap_or(help);
+                        }
 
                         let error_start = if !tidy_pk11_qattr.is_empty() {
-                            tidy_pk11_query.find(&tidy_pk11_qattr).unwrap()
+                            tidy_len(&pk11_query[..offset_within(pk11_query, pk11_qattr)])
                         } else {
                             // assign this here rather than adding O(n) runtime checks
                             // for basically an unlikely outlier type of error:
+                            kind = ViolationKind::MisplacedDelimiter;
                             violation = String::from("Misplaced query delimiter.");
                             help = String::from("Remove the misplaced '&' delimiter.");
-                            find_empty_attr_index(&tidy_pk11_query, count, '&')
-                        } + tidy_pk11_uri.find('?').unwrap()
+                            find_empty_attr_index(tidy_pk11_query, count, '&')
+                        } + tidy_len(&pk11_uri[..query_component_index])
                             + 1;
+                        let focus_span = focus.map(|offset| {
+                            error_span(error_start, pk11_qattr, Some((offset, offset + 1)))
+                        });
                         PK11URIError {
-                            pk11_uri: tidy_pk11_uri,
-                            error_span: (error_start, error_start + tidy_pk11_qattr.len()),
+                            pk11_uri: tidy(pk11_uri),
+                            error_span: error_span(error_start, pk11_qattr, span),
+                            kind,
                             violation,
                             help,
+                            secondary_span: related_span
+                                .map(SecondarySpan::Related)
+                                .or(focus_span.map(SecondarySpan::Focus)),
                         }
-                    })
+                    });
+                    recover(assigned, recovered.as_deref_mut())
                 })?;
         }
 
+        // "If a URI contains both "pin-source" and "pin-value" query attributes, the URI SHOULD be refused as invalid."
+        #[cfg(feature = "validation")]
+        if let (Some(pin_source), Some(pin_value)) = (mapping.pin_source, mapping.pin_value) {
+            // The error identifies whichever of the two came second:
+            let attribute_span =
+                |value: &str| related_span(pk11_uri, common::first_occurrence(value));
+            let pin_value_second = pin_source.as_ptr() < pin_value.as_ptr();
+            let (first, second) = match pin_value_second {
+                true => (pin_source, pin_value),
+                false => (pin_value, pin_source),
+            };
+            let conflict = Err(PK11URIError {
+                pk11_uri: tidy(pk11_uri),
+                error_span: attribute_span(second),
+                kind: ViolationKind::ConflictingAttributes,
+                violation: String::from(
+                    r#"Conflicting `pk11-qattr`: a PKCS#11 URI containing both "pin-source" and "pin-value" query attributes SHOULD be refused as invalid."#,
+                ),
+                help: String::from("Remove either the `pin-source` or the `pin-value` attribute."),
+                secondary_span: Some(SecondarySpan::Related(attribute_span(first))),
+            });
+            recover(conflict, recovered)?;
+            // (having recovered, the second of the two is skipped)
+            match pin_value_second {
+                true => mapping.pin_value = None,
+                false => mapping.pin_source = None,
+            }
+        }
+
         // "...semantics of using both attributes in the same URI string is implementation specific
         //  but such use SHOULD be avoided.  Attribute "module-name" is preferred to "module-path" due
         //  to its system-independent nature, but the latter may be more suitable for development and debugging."
-        #[cfg(all(debug_assertions, feature = "debug_warnings"))]
-        if mapping.module_name.is_some() && mapping.module_path.is_some() {
-            println!(
-                "pkcs11 warning: using both `module-name` and `module-path` SHOULD be avoided. \
-            Attribute `module-name` is preferred due to its system-independent nature."
+        #[cfg(any(
+            feature = "warnings",
+            all(debug_assertions, feature = "debug_warnings")
+        ))]
+        if let (Some(_), Some(module_path)) = (mapping.module_name, mapping.module_path) {
+            warnings.warn(
+                warning::WarningKind::ModuleNameAndPath,
+                "module-path",
+                module_path,
+                String::from(
+                    "using both `module-name` and `module-path` SHOULD be avoided. \
+            Attribute `module-name` is preferred due to its system-independent nature.",
+                ),
             );
         }
 
         // "If a URI contains both "pin-source" and "pin-value" query attributes, the URI SHOULD be refused as invalid."
-        #[cfg(all(debug_assertions, feature = "debug_warnings"))]
-        if mapping.pin_source.is_some() && mapping.pin_value.is_some() {
-            println!(
-                r#"pkcs11 warning: a PKCS#11 URI containing both "pin-source" and "pin-value" query attributes SHOULD be refused as invalid."#
+        #[cfg(any(
+            feature = "warnings",
+            all(debug_assertions, feature = "debug_warnings")
+        ))]
+        if let (Some(_), Some(pin_value)) = (mapping.pin_source, mapping.pin_value) {
+            warnings.warn(
+                warning::WarningKind::PinSourceAndPinValue,
+                "pin-value",
+                pin_value,
+                String::from(
+                    r#"a PKCS#11 URI containing both "pin-source" and "pin-value" query attributes SHOULD be refused as invalid."#,
+                ),
             );
         }
     }
 
+    // RFC7512's examples always pair `type` with the `object` label: a `type` alone, while
+    // technically legal, matches every object of the type and is usually a misconfiguration.
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    if let (None, Some(r#type)) = (mapping.object, mapping.r#type) {
+        warnings.warn_with_help(
+            warning::WarningKind::TypeWithoutObject,
+            "type",
+            r#type,
+            format!("the `type={type}` attribute is given without an `object` attribute, so identifies every such object rather than a particular one."),
+            Some(format!("Add the object's label alongside its type, ie, `object=my-key;type={type}`.")),
+        );
+    }
+
     Ok(mapping)
 }
 
+/// Helper function to record an attribute's error in `recovered` (when parsing
+/// recovers from such errors) rather than failing the parse.
+fn recover(
+    result: Result<(), PK11URIError>,
+    recovered: Option<&mut Vec<PK11URIError>>,
+) -> Result<(), PK11URIError> {
+    match (result, recovered) {
+        (Err(err), Some(recovered)) => {
+            recovered.push(err);
+            Ok(())
+        }
+        (result, _) => result,
+    }
+}
+
+/// Helper function to determine the span of an error within the tidied uri given
+/// the tidied `error_start` of the offending (untidied) `component`: either the
+/// component as a whole, or the narrower component-relative `span` if one was given.
+fn error_span(error_start: usize, component: &str, span: Option<(usize, usize)>) -> (usize, usize) {
+    match span {
+        Some((start, end)) => (
+            error_start + tidy_len(&component[..start]),
+            error_start + tidy_len(&component[..end]),
+        ),
+        None => (error_start, error_start + tidy_len(component)),
+    }
+}
+
+/// Helper function to determine whether `scheme` is an RFC3986 scheme name:
+/// `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )`.
+fn is_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['+', '-', '.'].contains(&c))
+}
+
+/// Helper function to identify a '?' which was meant to be part of the last path
+/// attribute's value (as in `object=a?b`) rather than begin the query: the "query"
+/// then begins with something other than a `name=value` attribute, such as `b` or
+/// `b;type=cert` (a bare standard attribute name is left to be reported as such).
+#[cfg(feature = "validation")]
+fn stray_query_delimiter(pk11_uri: &str, query_component_index: usize) -> Option<PK11URIError> {
+    let last_pk11_pattr = pk11_uri[PKCS11_SCHEME_LEN..query_component_index]
+        .rsplit(';')
+        .next()
+        .map(str::trim)?;
+    let (attribute, _) = last_pk11_pattr.split_once('=')?;
+    let first_pk11_qattr = pk11_uri[query_component_index + 1..].split('&').next()?;
+    let stray = match first_pk11_qattr.split_once('=') {
+        Some((name, _)) => name.contains(';'),
+        None => {
+            let name = first_pk11_qattr.split(';').next().map(str::trim)?;
+            !name.is_empty() && standard_component(name).is_none()
+        }
+    };
+    if !stray {
+        return None;
+    }
+    let remainder = first_pk11_qattr.split(';').next()?.trim();
+    let error_start = tidy_len(&pk11_uri[..query_component_index]);
+    Some(PK11URIError {
+        pk11_uri: tidy(pk11_uri),
+        error_span: (error_start, error_start + 1),
+        kind: ViolationKind::MisplacedDelimiter,
+        violation: String::from(
+            r#"Invalid component value: a '?' begins the `pk11-query`, so must be percent-encoded within a path value."#,
+        ),
+        help: format!(
+            "Percent-encode the '?' of the `{}` value as `%3F`: `{last_pk11_pattr}%3F{remainder}`.",
+            attribute.trim()
+        ),
+        secondary_span: None,
+    })
+}
+
+/// The help for a `pk11_attr` lacking its '=' which immediately follows a `name=value`
+/// attribute of the `pk11_component`: a ';' (or '&') meant to be part of that value, as in
+/// `token=My;Token`, has instead truncated it, and must be percent-encoded.
+fn truncated_value_help(pk11_component: &str, pk11_attr: &str, delimiter: char) -> Option<String> {
+    if pk11_attr.contains('=') {
+        return None;
+    }
+    let preceding = pk11_component[..offset_within(pk11_component, pk11_attr)]
+        .strip_suffix(delimiter)?
+        .rsplit(delimiter)
+        .next()?;
+    let (name, value) = common::split_attribute(preceding).ok()?;
+    if name.is_empty() {
+        return None;
+    }
+    let (name, value, attribute) = (tidy(name), tidy(value), tidy(pk11_attr.trim()));
+    let encoded = if delimiter == ';' { "%3B" } else { "%26" };
+    Some(format!(
+        "If `{attribute}` is part of the `{name}` value, percent-encode the '{delimiter}' as \
+        `{encoded}`, ie, `{name}={value}{encoded}{attribute}`; otherwise, attributes take \
+        the form `name=value`, ie, `{attribute}=value`."
+    ))
+}
+
+/// The `component` less a single trailing `delimiter` (along with any formatting following
+/// it) when the `options` tolerate one.
+fn without_trailing_delimiter<'a>(
+    component: &'a str,
+    delimiter: char,
+    options: &ParseOptions,
+) -> &'a str {
+    if !options.tolerate_trailing_delimiter {
+        return component;
+    }
+    component
+        .trim_end_matches(['\n', '\t'])
+        .strip_suffix(delimiter)
+        .unwrap_or(component)
+}
+
+/// Helper function to determine the offset of the `component` within the `whole`
+/// it was split from.
+fn offset_within(whole: &str, component: &str) -> usize {
+    component.as_ptr() as usize - whole.as_ptr() as usize
+}
+
+/// Helper function to determine the span, within the tidied uri, of the first
+/// occurrence of a duplicated attribute given the address and length of its value.
+fn related_span(pk11_uri: &str, (value_address, value_len): (usize, usize)) -> (usize, usize) {
+    let value_start = value_address - pk11_uri.as_ptr() as usize;
+    // The attribute begins after the delimiter preceding its name (and any formatting):
+    let attribute_start = pk11_uri[..value_start]
+        .rfind([':', ';', '?', '&'])
+        .map_or(0, |delimiter| delimiter + 1);
+    let attribute = &pk11_uri[attribute_start..value_start];
+    let attribute_start = attribute_start + attribute.len() - attribute.trim_start().len();
+    (
+        tidy_len(&pk11_uri[..attribute_start]),
+        tidy_len(&pk11_uri[..value_start + value_len]),
+    )
+}
+
 /// Helper function to identify the location of an empty path|query component.
 /// An empty component is a phenomena of a superfluous ';' or '&' delimiter such
 /// as `pkcs11:foo=bar;`
@@ -411,12 +1921,44 @@ fn find_empty_attr_index(tidy_attr: &str, split_count: usize, delimiter: char) -
     tidy_attr
         .match_indices(delimiter)
         .nth(split_count)
-        .unwrap_or((tidy_attr.len() - 1, "_"))
+        .unwrap_or((tidy_attr.len().saturating_sub(1), "_"))
         .0
 }
 
-/// Establish the basis for reliable error reporting by removing '\n' newline
-/// and '\t' tab formatting.
-fn tidy(maybe_messy: &str) -> String {
+/// Whether `value` begins with `prefix`, disregarding ASCII case.
+#[cfg(feature = "validation")]
+fn starts_with_ignore_ascii_case(value: &str, prefix: &str) -> bool {
+    value
+        .as_bytes()
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix.as_bytes()))
+}
+
+/// Removes the '\n' newline and '\t' tab formatting of a PKCS#11 URI spread over
+/// multiple lines (such as one copied from a tool's output), which is also the basis
+/// for reliable error reporting.
+///
+/// Empty spaces are *not* removed: they remain invalid in values and must be
+/// percent-encoded as `%20`.
+///
+/// ## Examples
+///
+/// ```
+/// let pk11_uri = "pkcs11:token=my-token;
+///     \tobject=my-key";
+/// assert_eq!(pk11_uri_parser::tidy(pk11_uri), "pkcs11:token=my-token;    object=my-key");
+/// assert_eq!(pk11_uri_parser::tidy("pkcs11:object=my\n-key"), "pkcs11:object=my-key");
+/// ```
+pub fn tidy(maybe_messy: &str) -> String {
     maybe_messy.replace(['\n', '\t'], "")
 }
+
+/// Appends the tidied `maybe_messy` to `buf` (avoiding [tidy]'s allocation).
+fn tidy_into(maybe_messy: &str, buf: &mut String) {
+    buf.extend(maybe_messy.chars().filter(|c| !matches!(c, '\n' | '\t')));
+}
+
+/// The length `maybe_messy` would have once tidied.
+fn tidy_len(maybe_messy: &str) -> usize {
+    maybe_messy.len() - maybe_messy.matches(['\n', '\t']).count()
+}
@@ -1,12 +1,12 @@
 /// Simple helper to encapsulate attribute field access and provide method docs.
 macro_rules! attr_access {
-    ($fn_name:ident, $component:meta, $attr_name:literal) => {
+    ($fn_name:ident, $component:meta, $attr_name:literal $(, $convert:ident)?) => {
         #[doc = "Retrieve the value of the"]
         #[doc = stringify!($attr_name)]
         #[$component]
         #[doc = "attribute if one was parsed."]
         pub fn $fn_name(&self) -> Option<&str> {
-            self.$fn_name
+            self.$fn_name$(.$convert())?
         }
     };
     ($pattr_fn:ident for pk11-pattr $pattr_name:literal) => {
@@ -15,6 +15,36 @@ macro_rules! attr_access {
     ($qattr_fn:ident for pk11-qattr $qattr_name:literal) => {
         attr_access!($qattr_fn, doc = "query", $qattr_name);
     };
+    // `Option<String>` fields of an owned mapping:
+    ($pattr_fn:ident for owned pk11-pattr $pattr_name:literal) => {
+        attr_access!($pattr_fn, doc = "path", $pattr_name, as_deref);
+    };
+    ($qattr_fn:ident for owned pk11-qattr $qattr_name:literal) => {
+        attr_access!($qattr_fn, doc = "query", $qattr_name, as_deref);
+    };
+}
+
+/// Percent-decoded companions of the `attr_access` accessors.
+macro_rules! attr_decoded {
+    ($fn_name:ident, $attr:ident, $component:meta, $attr_name:literal) => {
+        #[doc = "Retrieve the percent-decoded value of the"]
+        #[doc = stringify!($attr_name)]
+        #[$component]
+        #[doc = "attribute if one was parsed."]
+        #[doc = ""]
+        #[doc = "The value is borrowed whenever there's nothing to decode. A `%` not followed"]
+        #[doc = "by two hexadecimal digits, or decoded octets which aren't valid UTF-8, result"]
+        #[doc = "in a [DecodeError]."]
+        pub fn $fn_name(&self) -> Result<Option<Cow<'a, str>>, DecodeError> {
+            self.$attr.map(percent::percent_decode_str).transpose()
+        }
+    };
+    ($pattr_fn:ident from $pattr:ident for pk11-pattr $pattr_name:literal) => {
+        attr_decoded!($pattr_fn, $pattr, doc = "path", $pattr_name);
+    };
+    ($qattr_fn:ident from $qattr:ident for pk11-qattr $qattr_name:literal) => {
+        attr_decoded!($qattr_fn, $qattr, doc = "query", $qattr_name);
+    };
 }
 
 /// Takes care of the boilerplate machinery for establishing PKCS#11
@@ -34,49 +64,28 @@ macro_rules! pk11_attributes {
             type Error = ValidationErr;
 
             fn try_from(pk11_attr: &'a str) -> Result<Self, Self::Error> {
-                // Intentionally *not* putting the empty check here
-                // (and incurring its associated O(n) runtime cost);
-                // the empty check gets handled further downstream if
-                // the below "Malformed component." `ok_or` arm is invoked.
-                let (attribute, value) = pk11_attr
-                    .split_once('=')
-                    .map(|(attribute, value)| (attribute.trim(), value.trim()))
-                    .ok_or(ValidationErr {
-                        violation: String::from("Malformed component."),
-                        help: String::from("Please refer to RFC7512 for acceptable path|query attribute values."),
-                    })?;
+                let (attribute, value) = split_attribute(pk11_attr)?;
 
                 let attr = PK11Attribute::try_from(attribute)?;
 
                 // Implementation specific (hand-coded) callback:
-                attr.validate(value)?;
-
-                #[cfg(all(debug_assertions, feature = "debug_warnings"))]
-                attr.maybe_warn(value);
+                attr.validate(value).map_err(|err| err.within(value, pk11_attr))?;
 
                 Ok(PK11Attr { attr, value })
             }
         }
 
+        // Even without validation, an attribute lacking its '=' can't be parsed:
         #[cfg(not(feature = "validation"))]
-        impl<'a> From<&'a str> for PK11Attr<'a> {
-
-            fn from(pk11_attr: &'a str) -> Self {
-                // Intentionally *not* putting the empty check here
-                // (and incurring its associated O(n) runtime cost);
-                // the empty check gets handled further downstream if
-                // the below "Malformed component." `ok_or` arm is invoked.
-                let (attribute, value) = pk11_attr
-                    .split_once('=')
-                    .map(|(attribute, value)| (attribute.trim(), value.trim()))
-                    .expect("attribute/value pair should be valid");
+        impl<'a> TryFrom<&'a str> for PK11Attr<'a> {
+            type Error = ValidationErr;
 
-                let attr = PK11Attribute::from(attribute);
+            fn try_from(pk11_attr: &'a str) -> Result<Self, Self::Error> {
+                let (attribute, value) = split_attribute(pk11_attr)?;
 
-                #[cfg(all(debug_assertions, feature = "debug_warnings"))]
-                attr.maybe_warn(value);
+                let attr = PK11Attribute::from(attribute);
 
-                PK11Attr { attr, value }
+                Ok(PK11Attr { attr, value })
             }
         }
 
@@ -85,8 +94,8 @@ macro_rules! pk11_attributes {
         #[allow(non_camel_case_types)]
         #[derive(Debug)]
         enum PK11Attribute<'a> {
-            $( #[cfg(any(feature = "validation", all(debug_assertions, feature = "debug_warnings")))] $name(&'static str), )+
-            $( #[cfg(not(any(feature = "validation", all(debug_assertions, feature = "debug_warnings"))))] $name(), )+
+            $( #[cfg(any(feature = "validation", feature = "warnings", all(debug_assertions, feature = "debug_warnings")))] $name(&'static str), )+
+            $( #[cfg(not(any(feature = "validation", feature = "warnings", all(debug_assertions, feature = "debug_warnings"))))] $name(), )+
             VAttr(VendorAttribute<'a>),
         }
 
@@ -97,8 +106,8 @@ macro_rules! pk11_attributes {
             fn try_from(value: &'a str) -> Result<Self, Self::Error> {
                 let attribute = match value {
                     // standard attribute names:
-                    $( #[cfg(any(feature = "validation", all(debug_assertions, feature = "debug_warnings")))] $text => $name($text), )+
-                    $( #[cfg(not(any(feature = "validation", all(debug_assertions, feature = "debug_warnings"))))] $text => $name(), )+
+                    $( #[cfg(any(feature = "validation", feature = "warnings", all(debug_assertions, feature = "debug_warnings")))] $text => $name($text), )+
+                    $( #[cfg(not(any(feature = "validation", feature = "warnings", all(debug_assertions, feature = "debug_warnings"))))] $text => $name(), )+
                     // non-standard: possibly a vendor-specific,
                     // misplaced standard, or empty attribute:
                     non_standard => VAttr(VendorAttribute::try_from(non_standard)?)
@@ -113,8 +122,8 @@ macro_rules! pk11_attributes {
             fn from(value: &'a str) -> Self {
                 match value {
                     // standard attribute names:
-                    $( #[cfg(any(feature = "validation", all(debug_assertions, feature = "debug_warnings")))] $text => $name($text), )+
-                    $( #[cfg(not(any(feature = "validation", all(debug_assertions, feature = "debug_warnings"))))] $text => $name(), )+
+                    $( #[cfg(any(feature = "validation", feature = "warnings", all(debug_assertions, feature = "debug_warnings")))] $text => $name($text), )+
+                    $( #[cfg(not(any(feature = "validation", feature = "warnings", all(debug_assertions, feature = "debug_warnings"))))] $text => $name(), )+
                     // non-standard:
                     non_standard => VAttr(VendorAttribute::from(non_standard))
                 }
@@ -124,13 +133,23 @@ macro_rules! pk11_attributes {
         impl <'a> PK11Attribute<'a> {
 
             // Used for warning messages:
-            #[cfg(all(debug_assertions, feature = "debug_warnings"))]
+            #[cfg(any(feature = "warnings", all(debug_assertions, feature = "debug_warnings")))]
             fn to_str(&self) -> &'a str {
                 match self {
                     $( $name(name) => name, )+
                     VAttr(vendor_attribute) => vendor_attribute.0
                 }
             }
+
+            // Any warnings concerning the attribute's name, followed by
+            // the hand-coded `Warning` trait's checks of its value:
+            #[cfg(any(feature = "warnings", all(debug_assertions, feature = "debug_warnings")))]
+            fn warn(&self, value: &'a str, options: &ParseOptions, warnings: &mut Warnings<'a>) {
+                if let VAttr(vendor_attribute) = self {
+                    vendor_attribute.maybe_warn(options, warnings);
+                }
+                self.maybe_warn(value, options, warnings);
+            }
         }
     };
 }
@@ -156,11 +175,16 @@ macro_rules! path_attributes {
                 match self {
                     $( Self::$name(attribute) => {
                         if mapping.$name.is_none() {
-                            mapping.$name = Some(value)
+                            mapping.$name = Some(value);
+                            mapping.attribute_order.push($text);
                         } else {
                             return Err(ValidationErr {
+                                kind: ViolationKind::DuplicatePathAttr,
                                 violation: format!(r#"Duplicate `pk11-pattr` standard name: "{attribute}"."#),
-                                help: String::from("A PKCS #11 URI must not contain duplicate attributes of the same name in the URI path component.")
+                                help: String::from("A PKCS #11 URI must not contain duplicate attributes of the same name in the URI path component."),
+                                span: None,
+                                focus: None,
+                                duplicate_of: mapping.$name.map(first_occurrence),
                             })
                         }
                     }, )+
@@ -169,8 +193,12 @@ macro_rules! path_attributes {
                             mapping.vendor.insert(vendor_attribute.0, vec![value]);
                         } else {
                             return Err(ValidationErr{
+                                kind: ViolationKind::DuplicatePathAttr,
                                 violation: format!(r#"Duplicate `pk11-v-pattr` vendor-specific name: "{}"."#, vendor_attribute.0),
-                                help: String::from("A PKCS #11 URI must not contain duplicate vendor attributes of the same name in the URI path component.")
+                                help: String::from("A PKCS #11 URI must not contain duplicate vendor attributes of the same name in the URI path component."),
+                                span: None,
+                                focus: None,
+                                duplicate_of: mapping.vendor.get(vendor_attribute.0).map(|values| first_occurrence(values[0])),
                             })
                         }
                     }
@@ -182,7 +210,10 @@ macro_rules! path_attributes {
             fn assign(self, value: &'a str, mapping: &mut PK11URIMapping<'a>) -> Result<(), ValidationErr> {
                 match self {
                     $( Self::$name(..) => {
-                        mapping.$name = Some(value)
+                        // (the last of any duplicates is kept, in the first one's place)
+                        if mapping.$name.replace(value).is_none() {
+                            mapping.attribute_order.push($text);
+                        }
                     }, )+
                     VAttr(vendor_attribute) => {
                         mapping.vendor.insert(vendor_attribute.0, vec![value]);
@@ -214,11 +245,16 @@ macro_rules! query_attributes {
                 match self {
                     $( Self::$name(attribute) => {
                         if mapping.$name.is_none() {
-                            mapping.$name = Some(value)
+                            mapping.$name = Some(value);
+                            mapping.attribute_order.push($text);
                         } else {
                             return Err(ValidationErr{
+                                kind: ViolationKind::DuplicateQueryAttr,
                                 violation: format!(r#"Duplicate `pk11-qattr` standard name: "{attribute}"."#),
-                                help: String::from("A PKCS #11 URI must not contain duplicate standard attributes of the same name in the URI query component.")
+                                help: String::from("A PKCS #11 URI must not contain duplicate standard attributes of the same name in the URI query component."),
+                                span: None,
+                                focus: None,
+                                duplicate_of: mapping.$name.map(first_occurrence),
                             })
                         }
                     }, )+
@@ -231,7 +267,10 @@ macro_rules! query_attributes {
             fn assign(self, value: &'a str, mapping: &mut PK11URIMapping<'a>) -> Result<(), ValidationErr> {
                 match self {
                     $( Self::$name(..) => {
-                        mapping.$name = Some(value)
+                        // (the last of any duplicates is kept, in the first one's place)
+                        if mapping.$name.replace(value).is_none() {
+                            mapping.attribute_order.push($text);
+                        }
                     }, )+
                     VAttr(vendor_attribute) => mapping.vendor.entry(vendor_attribute.0).or_default().push(value)
                 }
@@ -240,3 +279,22 @@ macro_rules! query_attributes {
         }
     };
 }
+
+/// Copy-with-one-change companions of the `attr_access` accessors.
+macro_rules! attr_with {
+    ($fn_name:ident, $component:meta, $attr_name:literal) => {
+        #[doc = "A copy of the mapping whose"]
+        #[doc = stringify!($attr_name)]
+        #[$component]
+        #[doc = "attribute is the given (percent-encoded) `value`, sharing every other value."]
+        pub fn $fn_name(&self, value: &'a str) -> PK11URIMapping<'a> {
+            self.with_attribute($attr_name, value)
+        }
+    };
+    ($pattr_fn:ident for pk11-pattr $pattr_name:literal) => {
+        attr_with!($pattr_fn, doc = "path", $pattr_name);
+    };
+    ($qattr_fn:ident for pk11-qattr $qattr_name:literal) => {
+        attr_with!($qattr_fn, doc = "query", $qattr_name);
+    };
+}
@@ -0,0 +1,63 @@
+use super::PK11URIMapping;
+
+/// How [merge][PK11URIMapping::merge()] combines the values of a *vendor-specific*
+/// attribute both mappings have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VendorMergePolicy {
+    /// The other mapping's values replace the existing ones.
+    #[default]
+    Replace,
+    /// The other mapping's values are appended to the existing ones.
+    Append,
+}
+
+impl<'a> PK11URIMapping<'a> {
+    /// Layers the `other` mapping over this one, such as an overriding PKCS#11 URI over a
+    /// base (or template) one: each standard attribute `other` has is taken from `other`,
+    /// its value winning over any this mapping had (a differing `type`, for instance,
+    /// becomes that of `other`), while standard attributes `other` lacks are kept as-is.
+    /// The values of a vendor-specific attribute both mappings have are combined as per
+    /// the `vendor_policy`; those of any other vendor-specific attribute are kept or taken.
+    ///
+    /// Attributes newly taken from `other` follow the existing ones in the
+    /// [attribute_order][PK11URIMapping::attribute_order()], while any order recorded by
+    /// [parse_preserving_order][super::parse_preserving_order] is discarded. Nothing is
+    /// validated: merging a `pin-value` over a `pin-source`, for instance, results in a
+    /// mapping having both, which [to_uri][PK11URIMapping::to_uri()] would refuse.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use pk11_uri_parser::VendorMergePolicy;
+    ///
+    /// let base = "pkcs11:token=my-token;type=private;v-attr=a?module-name=mypkcs11";
+    /// let mut mapping = pk11_uri_parser::parse(base).expect("mapping should be valid");
+    ///
+    /// let overrides = "pkcs11:object=my-key;type=cert;v-attr=b";
+    /// let overrides = pk11_uri_parser::parse(overrides).expect("mapping should be valid");
+    ///
+    /// mapping.merge(&overrides, VendorMergePolicy::Append);
+    /// assert_eq!(
+    ///     mapping.to_string(),
+    ///     "pkcs11:token=my-token;object=my-key;type=cert?module-name=mypkcs11&v-attr=a&v-attr=b"
+    /// );
+    /// ```
+    pub fn merge(&mut self, other: &PK11URIMapping<'a>, vendor_policy: VendorMergePolicy) {
+        for (attr, value) in other.iter() {
+            let field = self
+                .standard_attribute_mut(attr)
+                .expect("iterated attributes are standard");
+            if field.replace(value).is_none() {
+                self.attribute_order.push(attr);
+            }
+        }
+        for (name, values) in &other.vendor {
+            let existing = self.vendor.entry(name).or_default();
+            if vendor_policy == VendorMergePolicy::Replace {
+                existing.clear();
+            }
+            existing.extend(values);
+        }
+        self.source_order.clear();
+    }
+}
@@ -0,0 +1,196 @@
+use super::{parse, OwnedPK11URIMapping, PK11URIError, PK11URIMapping};
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+// 64-bit FNV-1a parameters (http://www.isthe.com/chongo/tech/comp/fnv/):
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl PK11URIMapping<'_> {
+    /// A copy of the mapping in which the hexadecimal digits of every percent-encoded octet
+    /// of every value are uppercased, as per [RFC3986 section 6.2.2.1][rfc3986]; nothing is
+    /// decoded. Mappings whose values differ only in the case of their percent-encodings
+    /// (such as `%3b` and `%3B`) then compare equal. Unlike [normalize][Self::normalize()],
+    /// the result is a mapping rather than a PKCS#11 URI.
+    ///
+    /// [rfc3986]: <https://datatracker.ietf.org/doc/html/rfc3986#section-6.2.2.1>
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:id=%Ba%dd%Ca%fe;object=my%2dkey";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// let normalized = mapping.normalized();
+    /// assert_eq!(normalized.id(), Some("%BA%DD%CA%FE"));
+    /// assert_eq!(normalized.object(), Some("my%2Dkey"));
+    /// ```
+    pub fn normalized(&self) -> OwnedPK11URIMapping {
+        let mut normalized = self.to_owned_mapping();
+        normalized.for_each_value_mut(|value| uppercase_percent_encodings(value));
+        normalized
+    }
+
+    /// The normalized form of the mapping's PKCS#11 URI: its canonical (`Display`)
+    /// form in which percent-encoded *unreserved* characters (`A-Z a-z 0-9 - . _ ~`, which
+    /// [RFC3986][rfc3986] says SHOULD NOT be percent-encoded) are decoded and the hexadecimal
+    /// digits of every other percent-encoded octet are uppercased. PKCS#11 URIs which differ
+    /// only in attribute order, formatting, or the needless (or differently cased)
+    /// percent-encoding of characters normalize to the same string.
+    ///
+    /// The `id` value is the exception to decoding: RFC7512 suggests its whole value SHOULD
+    /// be percent-encoded, so its octets are only uppercased.
+    ///
+    /// [rfc3986]: <https://datatracker.ietf.org/doc/html/rfc3986#section-2.3>
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:type=cert;id=%3e%41;object=my%2dcertificate%3f";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(mapping.normalize(), "pkcs11:object=my-certificate%3F;type=cert;id=%3E%41");
+    /// ```
+    pub fn normalize(&self) -> String {
+        let mut normalized = String::new();
+        self.normalize_into(&mut normalized);
+        normalized
+    }
+
+    /// Writes the [normalized][Self::normalize()] form of the mapping's PKCS#11 URI into
+    /// `buf`, replacing its prior content. Reusing the same `buf` avoids allocating a
+    /// `String` per mapping when normalizing many of them.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mut buf = String::new();
+    /// for pk11_uri in ["pkcs11:id=%0a;object=my-key", "pkcs11:id=%0b;object=my-key"] {
+    ///     let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    ///     mapping.normalize_into(&mut buf);
+    ///     println!("{buf}");
+    /// }
+    /// ```
+    pub fn normalize_into(&self, buf: &mut String) {
+        buf.clear();
+        write!(buf, "{self}").expect("writing to a String");
+
+        // The `id` value (if any) keeps all of its percent-encodings:
+        let id_range = self.id.and_then(|id| {
+            buf.match_indices("id=")
+                .map(|(start, _)| start + 3)
+                .find(|&start| {
+                    buf[..start - 3].ends_with([':', ';']) && buf[start..].starts_with(id)
+                })
+                .map(|start| start..start + id.len())
+        });
+
+        // Decode the unreserved characters and uppercase the (up to two)
+        // hexadecimal digits of the other octets following each '%':
+        let mut offset = 0;
+        let mut decoded_len = 0; // (by how much decoding has shifted the `id_range`)
+        while let Some(percent) = buf[offset..].find('%') {
+            let percent = offset + percent;
+            let hex_start = percent + 1;
+            let unreserved = buf
+                .get(hex_start..hex_start + 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .map(char::from)
+                .filter(|c| c.is_ascii_alphanumeric() || ['-', '.', '_', '~'].contains(c))
+                .filter(|_| {
+                    !id_range
+                        .as_ref()
+                        .is_some_and(|id| id.contains(&(percent + decoded_len)))
+                });
+            if let Some(unreserved) = unreserved {
+                buf.replace_range(percent..hex_start + 2, unreserved.encode_utf8(&mut [0; 4]));
+                decoded_len += 2;
+                offset = hex_start;
+                continue;
+            }
+            let hex_len = buf[hex_start..]
+                .bytes()
+                .take(2)
+                .take_while(u8::is_ascii_hexdigit)
+                .count();
+            buf[hex_start..hex_start + hex_len].make_ascii_uppercase();
+            offset = hex_start + hex_len;
+        }
+    }
+
+    /// Removes the exact duplicates among the values of each *vendor-specific* attribute
+    /// (as in `v-attr=a&v-attr=a`), keeping the first occurrence of each value in the
+    /// order they were parsed. Useful before comparing or hashing mappings.
+    ///
+    /// RFC7512 permits a vendor-specific attribute to have multiple values, duplicates
+    /// included, so parsing keeps every one of them: this is an opt-in normalization.
+    /// Values are compared as given, so `%2d` and `-` (for instance) aren't duplicates.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:v-attr=a?v-attr=b&v-attr=a&v-attr=b&v-attr=%61";
+    /// let mut mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// mapping.dedup_vendor_values();
+    /// assert_eq!(mapping.vendor("v-attr"), Some(&vec!["a", "b", "%61"]));
+    /// ```
+    pub fn dedup_vendor_values(&mut self) {
+        for values in self.vendor.values_mut() {
+            let mut seen = Vec::with_capacity(values.len());
+            values.retain(|value| {
+                let first = !seen.contains(value);
+                if first {
+                    seen.push(*value);
+                }
+                first
+            });
+        }
+    }
+}
+
+/// Uppercases the (up to two) hexadecimal digits following each '%' of `value`.
+fn uppercase_percent_encodings(value: &mut str) {
+    let mut offset = 0;
+    while let Some(percent) = value[offset..].find('%') {
+        let hex_start = offset + percent + 1;
+        let hex_len = value[hex_start..]
+            .bytes()
+            .take(2)
+            .take_while(u8::is_ascii_hexdigit)
+            .count();
+        value[hex_start..hex_start + hex_len].make_ascii_uppercase();
+        offset = hex_start + hex_len;
+    }
+}
+
+/// Computes a fingerprint of the given `pk11_uri` suitable for cache keys and
+/// change-detection: the 64-bit [FNV-1a][fnv] hash of its [normalized][PK11URIMapping::normalize()]
+/// form, so equivalent PKCS#11 URIs share a fingerprint. Violations to RFC7512 result
+/// in the [PK11URIError] of parsing the uri.
+///
+/// Unlike `std`'s `DefaultHasher`, the fingerprint is stable: it doesn't vary across
+/// process runs, platforms, or Rust versions, and any change in how this library
+/// normalizes URIs (and therefore fingerprints them) is treated as a breaking change.
+///
+/// [fnv]: <http://www.isthe.com/chongo/tech/comp/fnv/>
+///
+/// ## Examples
+///
+/// ```
+/// # fn main() -> Result<(), pk11_uri_parser::PK11URIError> {
+/// use pk11_uri_parser::fingerprint;
+///
+/// assert_eq!(
+///     fingerprint("pkcs11:object=my-key;id=%0a")?,
+///     fingerprint("pkcs11:id=%0A;
+///                         object=my-key")?
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn fingerprint(pk11_uri: &str) -> Result<u64, PK11URIError> {
+    Ok(parse(pk11_uri)?
+        .normalize()
+        .bytes()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        }))
+}
@@ -0,0 +1,200 @@
+use super::{PK11URIError, PK11URIMapping, ViolationKind};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::fmt;
+
+/// The `pk11-type` values: `"type" "=" ( "public" / "private" / "cert" / "secret-key" / "data" )`.
+///
+/// Each type corresponds to a PKCS#11 object class (`CKA_CLASS`), allowing a
+/// URI's `type` to be used as search criteria and a discovered object's class
+/// to be expressed as a URI `type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectType {
+    /// `type=public`, a `CKO_PUBLIC_KEY` object.
+    Public,
+    /// `type=private`, a `CKO_PRIVATE_KEY` object.
+    Private,
+    /// `type=cert`, a `CKO_CERTIFICATE` object.
+    Cert,
+    /// `type=secret-key`, a `CKO_SECRET_KEY` object.
+    SecretKey,
+    /// `type=data`, a `CKO_DATA` object.
+    Data,
+}
+
+// PKCS#11 object classes (`CK_OBJECT_CLASS`):
+const CKO_DATA: u64 = 0x0000_0000;
+const CKO_CERTIFICATE: u64 = 0x0000_0001;
+const CKO_PUBLIC_KEY: u64 = 0x0000_0002;
+const CKO_PRIVATE_KEY: u64 = 0x0000_0003;
+const CKO_SECRET_KEY: u64 = 0x0000_0004;
+
+impl ObjectType {
+    /// The `type` attribute value of this object type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ObjectType::Public => "public",
+            ObjectType::Private => "private",
+            ObjectType::Cert => "cert",
+            ObjectType::SecretKey => "secret-key",
+            ObjectType::Data => "data",
+        }
+    }
+
+    /// The object type of a `type` attribute value, if it's one RFC7512 defines.
+    pub(crate) fn from_type_value(value: &str) -> Option<ObjectType> {
+        [
+            ObjectType::Public,
+            ObjectType::Private,
+            ObjectType::Cert,
+            ObjectType::SecretKey,
+            ObjectType::Data,
+        ]
+        .into_iter()
+        .find(|object_type| object_type.as_str() == value)
+    }
+
+    /// The PKCS#11 object class (`CKA_CLASS` value) of this object type.
+    ///
+    /// ```
+    /// use pk11_uri_parser::ObjectType;
+    ///
+    /// // CKO_PRIVATE_KEY:
+    /// assert_eq!(ObjectType::Private.to_object_class(), 3);
+    /// ```
+    pub fn to_object_class(&self) -> u64 {
+        match self {
+            ObjectType::Public => CKO_PUBLIC_KEY,
+            ObjectType::Private => CKO_PRIVATE_KEY,
+            ObjectType::Cert => CKO_CERTIFICATE,
+            ObjectType::SecretKey => CKO_SECRET_KEY,
+            ObjectType::Data => CKO_DATA,
+        }
+    }
+
+    /// The object type of a PKCS#11 object class (`CKA_CLASS` value), the inverse of
+    /// [to_object_class][ObjectType::to_object_class()]. Classes without a `pk11-type`
+    /// counterpart (such as `CKO_HW_FEATURE` or vendor-defined classes) result in `None`.
+    ///
+    /// ```
+    /// use pk11_uri_parser::ObjectType;
+    ///
+    /// // CKO_CERTIFICATE:
+    /// assert_eq!(ObjectType::from_object_class(1), Some(ObjectType::Cert));
+    /// // CKO_HW_FEATURE:
+    /// assert_eq!(ObjectType::from_object_class(5), None);
+    /// ```
+    pub fn from_object_class(class: u64) -> Option<ObjectType> {
+        match class {
+            CKO_PUBLIC_KEY => Some(ObjectType::Public),
+            CKO_PRIVATE_KEY => Some(ObjectType::Private),
+            CKO_CERTIFICATE => Some(ObjectType::Cert),
+            CKO_SECRET_KEY => Some(ObjectType::SecretKey),
+            CKO_DATA => Some(ObjectType::Data),
+            _ => None,
+        }
+    }
+}
+
+/// Displays the `type` attribute value, eg. `secret-key`.
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'a> PK11URIMapping<'a> {
+    /// Retrieve the `type` path attribute as an [ObjectType] if one was parsed, allowing
+    /// exhaustive matching rather than comparing against the `r#type` string. A `type`
+    /// other than those RFC7512 defines (which only a mapping parsed without the
+    /// `validation` feature may hold) also results in `None`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use pk11_uri_parser::ObjectType;
+    ///
+    /// let mapping = pk11_uri_parser::parse("pkcs11:object=my-key;type=secret-key").expect("mapping should be valid");
+    /// match mapping.object_type() {
+    ///     Some(ObjectType::SecretKey) => println!("found a secret key"),
+    ///     Some(other) => println!("found a {other} object"),
+    ///     None => println!("any type of object"),
+    /// }
+    /// ```
+    pub fn object_type(&self) -> Option<ObjectType> {
+        self.r#type.and_then(ObjectType::from_type_value)
+    }
+
+    /// Retrieve the `object` and `type` path attributes, requiring the mapping to specify
+    /// both: the most common means of identifying a PKCS#11 object. Whichever is missing
+    /// (or a `type` other than those RFC7512 defines, which only a mapping parsed without
+    /// the `validation` feature may hold) results in a [PK11URIError] naming it, spanning
+    /// the canonical (`Display`) form of the mapping's PKCS#11 URI.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use pk11_uri_parser::ObjectType;
+    ///
+    /// let mapping = pk11_uri_parser::parse("pkcs11:object=my-key;type=private").expect("mapping should be valid");
+    /// assert_eq!(mapping.object_and_type_required().ok(), Some(("my-key", ObjectType::Private)));
+    ///
+    /// let mapping = pk11_uri_parser::parse("pkcs11:object=my-key").expect("mapping should be valid");
+    /// let err = mapping.object_and_type_required().expect_err("type is missing");
+    /// assert!(err.to_string().contains("Missing required `type` path attribute."));
+    /// ```
+    pub fn object_and_type_required(&self) -> Result<(&'a str, ObjectType), PK11URIError> {
+        const OBJECT_EXAMPLE: &str = "`object=my-key`";
+        const TYPE_EXAMPLE: &str =
+            "`type=private` (one of `public`, `private`, `cert`, `secret-key`, or `data`)";
+
+        let (violation, help) = match (self.object, self.r#type) {
+            (Some(object), Some(type_value)) => match ObjectType::from_type_value(type_value) {
+                Some(object_type) => return Ok((object, object_type)),
+                None => {
+                    let pk11_uri = self.to_string();
+                    // (the canonical path always has `object` preceding `type`)
+                    let attribute = format!("type={type_value}");
+                    let start = pk11_uri
+                        .find(&format!(";{attribute}"))
+                        .expect("canonical uri contains the type attribute")
+                        + 1;
+                    return Err(PK11URIError {
+                        error_span: (start, start + attribute.len()),
+                        pk11_uri,
+                        kind: ViolationKind::InvalidType,
+                        violation: String::from(r#"Invalid `pk11-pattr`: `pk11-type` = `"type" "=" ( "public" / "private" / "cert" / "secret-key" / "data" )`."#),
+                        help: format!("Replace `{type_value}` value with one of `public`, `private`, `cert`, `secret-key`, or `data`."),
+                        secondary_span: None,
+                    });
+                }
+            },
+            (None, Some(_)) => (
+                "Missing required `object` path attribute.",
+                format!("Add the object's label, ie, {OBJECT_EXAMPLE}."),
+            ),
+            (Some(_), None) => (
+                "Missing required `type` path attribute.",
+                format!("Add the object's type, ie, {TYPE_EXAMPLE}."),
+            ),
+            (None, None) => (
+                "Missing required `object` and `type` path attributes.",
+                format!("Add the object's label, ie, {OBJECT_EXAMPLE}, and its type, ie, {TYPE_EXAMPLE}."),
+            ),
+        };
+
+        // The missing attributes belong at the end of the path:
+        let pk11_uri = self.to_string();
+        let path_end = pk11_uri.find('?').unwrap_or(pk11_uri.len());
+        Err(PK11URIError {
+            pk11_uri,
+            error_span: (path_end, path_end),
+            kind: ViolationKind::MissingRequiredAttr,
+            violation: String::from(violation),
+            help,
+            secondary_span: None,
+        })
+    }
+}
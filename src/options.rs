@@ -0,0 +1,190 @@
+use super::PK11URIError;
+#[cfg(feature = "warnings")]
+use super::PK11URIWarning;
+use core::fmt;
+
+/// Adjusts how [parse_with_options][super::parse_with_options] treats a PKCS#11 URI.
+/// The `Default` options result in exactly the same treatment as [parse][super::parse].
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::ParseOptions;
+///
+/// let options = ParseOptions {
+///     extra_query_attributes: &["pin-id"],
+///     ..ParseOptions::default()
+/// };
+///
+/// // or, by way of the builder-style setters:
+/// let options = ParseOptions::default()
+///     .extra_query_attributes(&["pin-id"])
+///     .validate_pin_source(true);
+/// ```
+#[derive(Clone, Default)]
+pub struct ParseOptions<'o> {
+    /// Additional query attribute names to be recognized as *standard* (in the manner of
+    /// `pin-source` or `module-name`) rather than vendor-specific, for ecosystems defining
+    /// well-known query attributes beyond those of RFC7512.
+    ///
+    /// Such an attribute may have only a *single* value: assuming the `validation` feature
+    /// is enabled, it's a violation for the attribute to appear more than once in the query
+    /// or to appear in the path at all. Its value is retrieved using
+    /// [vendor][super::PK11URIMapping::vendor()], which results in exactly one value.
+    /// Names of RFC7512's own attributes are always treated as such and therefore have
+    /// no effect here.
+    ///
+    /// Parsed mappings do not retain the options they were parsed with, so such an
+    /// attribute is serialized (by `Display`, etc.) just as any vendor-specific one is.
+    pub extra_query_attributes: &'o [&'o str],
+
+    /// Whether an `http` or `https` `pin-source` must be a syntactically well-formed URL
+    /// (a scheme followed by `//`, an authority with a host and an optional numeric port,
+    /// and a path), assuming the `validation` feature is enabled. Other `pin-source`
+    /// values, such as `file:/etc/token_pin`, are unaffected.
+    ///
+    /// Only the URL's syntax is verified: parsing never performs network I/O. Resolving
+    /// the PIN (fetching the URL, see [PinSource][super::PinSource]) is a separate step.
+    pub validate_pin_source_url: bool,
+
+    /// Whether a `pin-source` must take one of the shapes RFC7512 describes, assuming the
+    /// `validation` feature is enabled: an absolute `file:` URI (`file:/etc/token_pin`),
+    /// another URI with a scheme (such as `https://pins.example.com/token`), or a `|`
+    /// followed by a command to run (`|/usr/lib/pinomatic`). A bare path (be it relative
+    /// or absolute) and an empty `pin-source=` are violations.
+    pub validate_pin_source: bool,
+
+    /// Whether the percent-decoded values of the standard *text* attributes (every one but
+    /// the binary `id`) must be valid UTF-8, assuming the `validation` feature is enabled,
+    /// catching an encoded UTF-8 sequence (such as `%C3%A1`) corrupted by hand-editing.
+    /// Vendor-specific values, whose encoding is up to the vendor, are unaffected.
+    pub validate_utf8: bool,
+
+    /// Whether whitespace adjacent to an attribute's '=' (as in `token =foo` or `token= foo`)
+    /// is a violation, assuming the `validation` feature is enabled, rather than being
+    /// trimmed from the attribute's name and value. Whitespace *formatting* between
+    /// attributes (such as newlines and indentation) remains acceptable.
+    pub strict_attribute_names: bool,
+
+    /// Whether the mapping records the order in which *all* of its attributes (vendor-specific
+    /// ones included) appeared, and the component each appeared in, so that
+    /// [to_string_preserving_order][super::PK11URIMapping::to_string_preserving_order()]
+    /// reproduces them as given rather than in canonical order. See also
+    /// [parse_preserving_order][super::parse_preserving_order].
+    pub preserve_order: bool,
+
+    /// Additional characters which needn't be percent-encoded in the values of
+    /// *vendor-specific* attributes, for vendors whose conventions make use of characters
+    /// RFC7512 would otherwise have percent-encoded (such as `{` and `}`). Such characters
+    /// are no longer warned about, while the warnings of standard attributes' values are
+    /// unaffected. Characters which can't appear unencoded in a value at all (such as ' '
+    /// or '#') remain violations regardless.
+    pub vendor_allowed_chars: &'o [char],
+
+    /// Whether vendor-specific attribute names may knowingly follow the deprecated `x-`
+    /// prefix convention (as in `x-muppet`) without being warned about. The other warnings
+    /// of such an attribute (and of every other attribute) are unaffected.
+    pub allow_x_vendor_prefix: bool,
+
+    /// Whether a single trailing ';' of the path or '&' of the query (as in
+    /// `pkcs11:object=my-key;`), such as some tools emit, is silently dropped rather than
+    /// being a misplaced delimiter. This is a convenience for lenient ingestion which RFC7512
+    /// doesn't allow for: the mapping's serialized form never has the trailing delimiter.
+    pub tolerate_trailing_delimiter: bool,
+
+    /// Whether a PKCS#11 URI which merits a warning (such as an `id` which isn't
+    /// percent-encoded, a vendor-specific attribute with the deprecated "x-" prefix, or both
+    /// `module-name` and `module-path`) is refused, failing with a
+    /// [ViolationKind::StrictAdvisory][super::ViolationKind::StrictAdvisory] [PK11URIError]
+    /// identifying the first of them. For deployments wanting every RFC7512 "SHOULD/SHOULD
+    /// NOT" guideline enforced.
+    #[cfg(feature = "warnings")]
+    pub strict_advisories: bool,
+
+    /// Hands each [PK11URIWarning] to the given sink (such as a logger) rather than printing
+    /// it. Any warnings issued before a [PK11URIError] are still handed over, so the sink
+    /// sees everything that was amiss.
+    #[cfg(feature = "warnings")]
+    pub warning_sink: Option<&'o dyn Fn(PK11URIWarning)>,
+
+    /// Hands the [PK11URIError] of each attribute in violation to the given sink, the
+    /// attribute being skipped, rather than failing upon the first of them (as
+    /// [parse_best_effort][super::parse_best_effort] does). The resulting mapping is
+    /// therefore possibly *incomplete*. Only a uri which isn't a PKCS#11 URI at all (such
+    /// as one lacking the `pkcs11:` scheme) still fails.
+    pub error_sink: Option<&'o dyn Fn(PK11URIError)>,
+}
+
+impl fmt::Debug for ParseOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ParseOptions");
+        debug
+            .field("extra_query_attributes", &self.extra_query_attributes)
+            .field("validate_pin_source_url", &self.validate_pin_source_url)
+            .field("validate_pin_source", &self.validate_pin_source)
+            .field("validate_utf8", &self.validate_utf8)
+            .field("strict_attribute_names", &self.strict_attribute_names)
+            .field("preserve_order", &self.preserve_order)
+            .field("vendor_allowed_chars", &self.vendor_allowed_chars)
+            .field("allow_x_vendor_prefix", &self.allow_x_vendor_prefix)
+            .field(
+                "tolerate_trailing_delimiter",
+                &self.tolerate_trailing_delimiter,
+            );
+        #[cfg(feature = "warnings")]
+        debug
+            .field("strict_advisories", &self.strict_advisories)
+            .field(
+                "warning_sink",
+                &self.warning_sink.map(|_| "Fn(PK11URIWarning)"),
+            );
+        debug
+            .field("error_sink", &self.error_sink.map(|_| "Fn(PK11URIError)"))
+            .finish()
+    }
+}
+
+/// Generates the builder-style setter of an option.
+macro_rules! options_setter {
+    ($option:ident: $type:ty) => {
+        #[doc = concat!("Set the [", stringify!($option), "][ParseOptions::", stringify!($option), "] option.")]
+        pub fn $option(mut self, $option: $type) -> Self {
+            self.$option = $option;
+            self
+        }
+    };
+}
+
+impl<'o> ParseOptions<'o> {
+    options_setter!(extra_query_attributes: &'o [&'o str]);
+    options_setter!(validate_pin_source_url: bool);
+    options_setter!(validate_pin_source: bool);
+    options_setter!(validate_utf8: bool);
+    options_setter!(strict_attribute_names: bool);
+    options_setter!(preserve_order: bool);
+    options_setter!(vendor_allowed_chars: &'o [char]);
+    options_setter!(allow_x_vendor_prefix: bool);
+    options_setter!(tolerate_trailing_delimiter: bool);
+    #[cfg(feature = "warnings")]
+    options_setter!(strict_advisories: bool);
+
+    /// Set the [warning_sink][ParseOptions::warning_sink] option.
+    #[cfg(feature = "warnings")]
+    pub fn warning_sink(mut self, warning_sink: &'o dyn Fn(PK11URIWarning)) -> Self {
+        self.warning_sink = Some(warning_sink);
+        self
+    }
+
+    /// Set the [error_sink][ParseOptions::error_sink] option.
+    pub fn error_sink(mut self, error_sink: &'o dyn Fn(PK11URIError)) -> Self {
+        self.error_sink = Some(error_sink);
+        self
+    }
+}
+
+impl ParseOptions<'_> {
+    /// Whether `attribute` was registered as an extra *standard* query attribute.
+    pub(crate) fn is_extra_query_attribute(&self, attribute: &str) -> bool {
+        self.extra_query_attributes.contains(&attribute)
+    }
+}
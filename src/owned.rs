@@ -0,0 +1,292 @@
+use super::{parse, Component, PK11URIError, PK11URIMapping, VendorMap};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// An owned counterpart of [PK11URIMapping], holding `String` copies of its values
+/// rather than slices of the parsed PKCS#11 URI. Suited to long-lived structures (such
+/// as a server's configuration) which shouldn't have to keep the URI around.
+///
+/// The owned mapping offers the same attribute accessors; for anything else (decoding,
+/// serialization, matching, etc.) [borrow][OwnedPK11URIMapping::as_mapping()] it as a
+/// `PK11URIMapping`.
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::OwnedPK11URIMapping;
+///
+/// fn load_config() -> OwnedPK11URIMapping {
+///     let pk11_uri = String::from("pkcs11:object=my-key;type=private");
+///     pk11_uri_parser::parse_owned(&pk11_uri).expect("mapping should be valid")
+/// }
+///
+/// let mapping = load_config();
+/// assert_eq!(mapping.object(), Some("my-key"));
+/// assert_eq!(mapping.as_mapping().to_string(), "pkcs11:object=my-key;type=private");
+/// ```
+#[derive(Default, Clone)]
+pub struct OwnedPK11URIMapping {
+    // pk11-pattr:
+    token: Option<String>,
+    manufacturer: Option<String>,
+    serial: Option<String>,
+    model: Option<String>,
+    library_manufacturer: Option<String>,
+    library_version: Option<String>,
+    library_description: Option<String>,
+    object: Option<String>,
+    r#type: Option<String>,
+    id: Option<String>,
+    slot_description: Option<String>,
+    slot_manufacturer: Option<String>,
+    slot_id: Option<String>,
+    // pk11-qattr:
+    pin_source: Option<String>,
+    pin_value: Option<String>,
+    module_name: Option<String>,
+    module_path: Option<String>,
+    // vendor-specific:
+    vendor: VendorMap<String, Vec<String>>,
+    // standard attribute names in the order they were parsed:
+    attribute_order: Vec<&'static str>,
+    // every attribute name and its component in the order they were parsed (if preserved):
+    source_order: Vec<(String, Component)>,
+}
+
+impl OwnedPK11URIMapping {
+    // pk11-pattr:
+    attr_access!(token for owned pk11-pattr "token");
+    attr_access!(manufacturer for owned pk11-pattr "manufacturer");
+    attr_access!(serial for owned pk11-pattr "serial");
+    attr_access!(model for owned pk11-pattr "model");
+    attr_access!(library_manufacturer for owned pk11-pattr "library-manufacturer");
+    attr_access!(library_version for owned pk11-pattr "library-version");
+    attr_access!(library_description for owned pk11-pattr "library-description");
+    attr_access!(object for owned pk11-pattr "object");
+    attr_access!(r#type for owned pk11-pattr "type");
+    attr_access!(id for owned pk11-pattr "id");
+    attr_access!(slot_description for owned pk11-pattr "slot-description");
+    attr_access!(slot_manufacturer for owned pk11-pattr "slot-manufacturer");
+    attr_access!(slot_id for owned pk11-pattr "slot-id");
+    // pk11-qattr:
+    attr_access!(pin_source for owned pk11-qattr "pin-source");
+    attr_access!(pin_value for owned pk11-qattr "pin-value");
+    attr_access!(module_name for owned pk11-qattr "module-name");
+    attr_access!(module_path for owned pk11-qattr "module-path");
+    // vendor-specific:
+    /// Retrieve the values for the *vendor-specific* `vendor_attr` if parsed.
+    pub fn vendor(&self, vendor_attr: &str) -> Option<&Vec<String>> {
+        self.vendor.get(vendor_attr)
+    }
+
+    /// Retrieve the first value of the *vendor-specific* `vendor_attr` if parsed.
+    pub fn vendor_first(&self, vendor_attr: &str) -> Option<&str> {
+        self.vendor
+            .get(vendor_attr)
+            .and_then(|values| values.first())
+            .map(String::as_str)
+    }
+
+    /// The names of the standard attributes in the order they appeared in the parsed
+    /// PKCS#11 URI (see [PK11URIMapping::attribute_order()]).
+    pub fn attribute_order(&self) -> &[&'static str] {
+        &self.attribute_order
+    }
+
+    /// Applies `f` to every (standard and vendor-specific) value of the mapping.
+    pub(crate) fn for_each_value_mut(&mut self, f: impl Fn(&mut String)) {
+        [
+            &mut self.token,
+            &mut self.manufacturer,
+            &mut self.serial,
+            &mut self.model,
+            &mut self.library_manufacturer,
+            &mut self.library_version,
+            &mut self.library_description,
+            &mut self.object,
+            &mut self.r#type,
+            &mut self.id,
+            &mut self.slot_description,
+            &mut self.slot_manufacturer,
+            &mut self.slot_id,
+            &mut self.pin_source,
+            &mut self.pin_value,
+            &mut self.module_name,
+            &mut self.module_path,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(self.vendor.values_mut().flatten())
+        .for_each(f);
+    }
+
+    /// Borrows the owned mapping as a [PK11URIMapping].
+    pub fn as_mapping(&self) -> PK11URIMapping<'_> {
+        PK11URIMapping {
+            token: self.token.as_deref(),
+            manufacturer: self.manufacturer.as_deref(),
+            serial: self.serial.as_deref(),
+            model: self.model.as_deref(),
+            library_manufacturer: self.library_manufacturer.as_deref(),
+            library_version: self.library_version.as_deref(),
+            library_description: self.library_description.as_deref(),
+            object: self.object.as_deref(),
+            r#type: self.r#type.as_deref(),
+            id: self.id.as_deref(),
+            slot_description: self.slot_description.as_deref(),
+            slot_manufacturer: self.slot_manufacturer.as_deref(),
+            slot_id: self.slot_id.as_deref(),
+            pin_source: self.pin_source.as_deref(),
+            pin_value: self.pin_value.as_deref(),
+            module_name: self.module_name.as_deref(),
+            module_path: self.module_path.as_deref(),
+            vendor: self
+                .vendor
+                .iter()
+                .map(|(name, values)| (name.as_str(), values.iter().map(String::as_str).collect()))
+                .collect(),
+            attribute_order: self.attribute_order.clone(),
+            source_order: self
+                .source_order
+                .iter()
+                .map(|(name, component)| (name.as_str(), *component))
+                .collect(),
+        }
+    }
+}
+
+impl PK11URIMapping<'_> {
+    /// Copies the mapping's values into an [OwnedPK11URIMapping], which doesn't
+    /// borrow from the parsed PKCS#11 URI.
+    pub fn to_owned_mapping(&self) -> OwnedPK11URIMapping {
+        OwnedPK11URIMapping::from(self)
+    }
+
+    /// Retrieve an owned copy of the value of the attribute named `attr` if one was
+    /// parsed, for where a borrowed slice can't go (such as across an FFI boundary).
+    /// A *vendor-specific* attribute's first value is copied, as per
+    /// [vendor_first][PK11URIMapping::vendor_first()].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mapping = pk11_uri_parser::parse("pkcs11:object=my-key?v-attr=a&v-attr=b")
+    ///     .expect("mapping should be valid");
+    /// assert_eq!(mapping.get_owned("object"), Some(String::from("my-key")));
+    /// assert_eq!(mapping.get_owned("v-attr"), Some(String::from("a")));
+    /// assert_eq!(mapping.get_owned("type"), None);
+    /// ```
+    pub fn get_owned(&self, attr: &str) -> Option<String> {
+        self.attribute_value(attr).map(String::from)
+    }
+}
+
+impl From<&PK11URIMapping<'_>> for OwnedPK11URIMapping {
+    fn from(mapping: &PK11URIMapping<'_>) -> Self {
+        let owned = |value: Option<&str>| value.map(String::from);
+        OwnedPK11URIMapping {
+            token: owned(mapping.token),
+            manufacturer: owned(mapping.manufacturer),
+            serial: owned(mapping.serial),
+            model: owned(mapping.model),
+            library_manufacturer: owned(mapping.library_manufacturer),
+            library_version: owned(mapping.library_version),
+            library_description: owned(mapping.library_description),
+            object: owned(mapping.object),
+            r#type: owned(mapping.r#type),
+            id: owned(mapping.id),
+            slot_description: owned(mapping.slot_description),
+            slot_manufacturer: owned(mapping.slot_manufacturer),
+            slot_id: owned(mapping.slot_id),
+            pin_source: owned(mapping.pin_source),
+            pin_value: owned(mapping.pin_value),
+            module_name: owned(mapping.module_name),
+            module_path: owned(mapping.module_path),
+            vendor: mapping
+                .vendor
+                .iter()
+                .map(|(name, values)| {
+                    (
+                        String::from(*name),
+                        values.iter().copied().map(String::from).collect(),
+                    )
+                })
+                .collect(),
+            attribute_order: mapping.attribute_order.clone(),
+            source_order: mapping
+                .source_order
+                .iter()
+                .map(|(name, component)| (String::from(*name), *component))
+                .collect(),
+        }
+    }
+}
+
+impl From<PK11URIMapping<'_>> for OwnedPK11URIMapping {
+    fn from(mapping: PK11URIMapping<'_>) -> Self {
+        OwnedPK11URIMapping::from(&mapping)
+    }
+}
+
+/// Formats the attributes of the owned mapping, as per [PK11URIMapping]'s `Debug` implementation.
+impl fmt::Debug for OwnedPK11URIMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_mapping().fmt_attributes("OwnedPK11URIMapping", f)
+    }
+}
+
+/// Compares owned mappings semantically, as per [PK11URIMapping]'s `PartialEq` implementation.
+impl PartialEq for OwnedPK11URIMapping {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_mapping() == other.as_mapping()
+    }
+}
+
+impl Eq for OwnedPK11URIMapping {}
+
+/// Hashes owned mappings consistently with their `PartialEq`, as per [PK11URIMapping]'s
+/// `Hash` implementation, allowing one to key a long-lived cache.
+impl Hash for OwnedPK11URIMapping {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_mapping().hash(state);
+    }
+}
+
+/// Displays the canonical PKCS#11 URI, as per [PK11URIMapping]'s `Display` implementation.
+impl fmt::Display for OwnedPK11URIMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_mapping().fmt(f)
+    }
+}
+
+/// Parses the PKCS#11 URI as per [parse_owned], allowing `pk11_uri.parse()`.
+///
+/// ```
+/// use pk11_uri_parser::OwnedPK11URIMapping;
+///
+/// let mapping: OwnedPK11URIMapping = "pkcs11:object=my-key".parse().expect("mapping should be valid");
+/// assert_eq!(mapping.object(), Some("my-key"));
+/// ```
+impl FromStr for OwnedPK11URIMapping {
+    type Err = PK11URIError;
+
+    fn from_str(pk11_uri: &str) -> Result<Self, Self::Err> {
+        parse_owned(pk11_uri)
+    }
+}
+
+/// Like [parse], but results in an [OwnedPK11URIMapping] which doesn't borrow
+/// from the `pk11_uri`.
+///
+/// ## Examples
+///
+/// ```
+/// let mapping = pk11_uri_parser::parse_owned(&String::from("pkcs11:object=my-key"))
+///     .expect("mapping should be valid");
+/// assert_eq!(mapping.object(), Some("my-key"));
+/// ```
+pub fn parse_owned(pk11_uri: &str) -> Result<OwnedPK11URIMapping, PK11URIError> {
+    parse(pk11_uri).map(OwnedPK11URIMapping::from)
+}
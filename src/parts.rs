@@ -0,0 +1,127 @@
+use super::common::{PK11_PATTR_NAMES, PK11_QATTR_NAMES};
+use super::{parse, tidy, tidy_len, PK11URIError, PK11URIMapping, ViolationKind};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+impl<'a> PK11URIMapping<'a> {
+    /// Assembles a mapping from its `path` attributes, its `query` attributes and its
+    /// `vendor`-specific attributes (written to the query, a name given more than once
+    /// having each of its values kept), each a `(name, value)` pair. Values are given
+    /// percent-encoded, exactly as they'd appear within a PKCS#11 URI (unlike those given
+    /// to the [PK11URIBuilder][super::PK11URIBuilder]), and the mapping borrows them.
+    ///
+    /// The attributes are validated just as [parse] would validate the PKCS#11 URI they
+    /// make up, including where each is placed: a standard query attribute given as part
+    /// of the `path` (or vice versa) results in an error, which highlights the offending
+    /// attribute within that PKCS#11 URI. So too does a name or value whose delimiters
+    /// (such as a `;` within a path value) would be taken for those of the PKCS#11 URI,
+    /// rather than being parsed back as given.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use pk11_uri_parser::PK11URIMapping;
+    ///
+    /// let mapping = PK11URIMapping::from_parts(
+    ///     &[("token", "my-token"), ("object", "my-key")],
+    ///     &[("module-name", "mypkcs11")],
+    ///     &[("x-slot", "2")],
+    /// )
+    /// .expect("mapping should be valid");
+    /// assert_eq!(mapping.object(), Some("my-key"));
+    /// assert_eq!(mapping.vendor_first("x-slot"), Some("2"));
+    ///
+    /// # #[cfg(feature = "validation")]
+    /// # {
+    /// let err = PK11URIMapping::from_parts(&[("module-name", "mypkcs11")], &[], &[])
+    ///     .expect_err("module-name is a query attribute");
+    /// assert_eq!(err.error_span(), (7, 27));
+    /// # }
+    /// ```
+    pub fn from_parts(
+        path: &[(&'a str, &'a str)],
+        query: &[(&'a str, &'a str)],
+        vendor: &[(&'a str, &'a str)],
+    ) -> Result<PK11URIMapping<'a>, PK11URIError> {
+        let mut pk11_uri = String::from("pkcs11:");
+        // Each attribute's span within `pk11_uri`, alongside the delimiters it mustn't contain:
+        let mut spans = Vec::with_capacity(path.len() + query.len() + vendor.len());
+        let mut delimiter = "";
+        for (attr, value) in path {
+            pk11_uri.push_str(delimiter);
+            let start = pk11_uri.len();
+            pk11_uri.extend([attr, "=", value]);
+            spans.push((start..pk11_uri.len(), &[';', '?', '#'][..]));
+            delimiter = ";";
+        }
+        let mut delimiter = "?";
+        for (attr, value) in query.iter().chain(vendor) {
+            pk11_uri.push_str(delimiter);
+            let start = pk11_uri.len();
+            pk11_uri.extend([attr, "=", value]);
+            spans.push((start..pk11_uri.len(), &['&', '#'][..]));
+            delimiter = "&";
+        }
+        let parsed = parse(&pk11_uri)?;
+
+        // Every attribute must have been parsed back as given:
+        for ((span, delimiters), &(attr, value)) in spans
+            .into_iter()
+            .zip(path.iter().chain(query).chain(vendor))
+        {
+            let parsed_back = match parsed.vendor(attr) {
+                Some(values) => values.contains(&value),
+                None => parsed.attribute_value(attr) == Some(value),
+            };
+            if parsed_back {
+                continue;
+            }
+            let is_delimiter = |c: &char| delimiters.contains(c);
+            let help = if let Some(c) = attr.chars().find(|c| *c == '=' || is_delimiter(c)) {
+                format!("Attribute names can't contain a '{c}': rename the `{attr}` attribute.")
+            } else if let Some(c) = value.chars().find(is_delimiter) {
+                format!(
+                    "Percent-encode the '{c}' of the `{attr}` value as `%{:02X}`.",
+                    c as u8
+                )
+            } else {
+                format!("Percent-encode the `{attr}` value.")
+            };
+            return Err(PK11URIError {
+                pk11_uri: tidy(&pk11_uri),
+                error_span: (
+                    tidy_len(&pk11_uri[..span.start]),
+                    tidy_len(&pk11_uri[..span.end]),
+                ),
+                kind: ViolationKind::MisplacedDelimiter,
+                violation: String::from(
+                    r#"Invalid component value: the attribute doesn't parse back as given, its delimiters being taken for those of the PKCS#11 URI."#,
+                ),
+                help,
+                secondary_span: None,
+            });
+        }
+
+        // Having been parsed, the attributes are assigned just as parsing assigned them:
+        let mut mapping = PK11URIMapping::default();
+        for &(attr, value) in path.iter().chain(query).chain(vendor) {
+            match PK11_PATTR_NAMES
+                .iter()
+                .chain(&PK11_QATTR_NAMES)
+                .find(|standard_attr| **standard_attr == attr)
+            {
+                Some(standard_attr) => {
+                    let field = mapping
+                        .standard_attribute_mut(attr)
+                        .expect("standard attribute");
+                    if field.replace(value).is_none() {
+                        mapping.attribute_order.push(*standard_attr);
+                    }
+                }
+                None => mapping.vendor.entry(attr).or_default().push(value),
+            }
+        }
+        Ok(mapping)
+    }
+}
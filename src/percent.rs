@@ -0,0 +1,209 @@
+use super::common::{PK11_QUERY_RES_AVAIL, PK11_RES_AVAIL};
+use super::Component;
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+use core::{error, fmt};
+
+/// Issued when percent-decoding a value which contains a `%` that isn't
+/// followed by two hexadecimal digits (ie, not an RFC3986 `pct-encoded` octet),
+/// or, when decoding to text, whose decoded octets aren't valid UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// The offending value.
+    value: String,
+    /// Offset of the malformed `%` (or of the first invalid UTF-8 octet) within the value.
+    offset: usize,
+    /// Whether the decoded octets, rather than the encoding, are at fault.
+    invalid_utf8: bool,
+}
+
+impl DecodeError {
+    /// Offset of the malformed `%` within the decoded value, or of the first octet
+    /// (possibly a `%XX` triplet) which doesn't decode to valid UTF-8.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl error::Error for DecodeError {}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.invalid_utf8 {
+            return write!(
+                f,
+                "invalid UTF-8 at offset {} in `{}`: the decoded octets are not text",
+                self.offset, self.value
+            );
+        }
+        write!(
+            f,
+            "malformed percent-encoding at offset {} in `{}`: expected `%` followed by two hexadecimal digits",
+            self.offset, self.value
+        )
+    }
+}
+
+/// Decodes the `%XX` triplets of `value`; every other byte is taken as-is,
+/// so partially encoded values such as `ab%43cd` decode to `abCcd`. The
+/// value is borrowed whenever there's nothing to decode.
+pub(crate) fn percent_decode(value: &str) -> Result<Cow<'_, [u8]>, DecodeError> {
+    if !value.contains('%') {
+        return Ok(Cow::Borrowed(value.as_bytes()));
+    }
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut offset = 0;
+    while offset < bytes.len() {
+        if bytes[offset] == b'%' {
+            let octet = decode_octet(bytes, offset).ok_or_else(|| DecodeError {
+                value: value.to_string(),
+                offset,
+                invalid_utf8: false,
+            })?;
+            decoded.push(octet);
+            offset += 3;
+        } else {
+            decoded.push(bytes[offset]);
+            offset += 1;
+        }
+    }
+    Ok(Cow::Owned(decoded))
+}
+
+/// Like [percent_decode], but the decoded octets must also be valid UTF-8.
+pub(crate) fn percent_decode_str(value: &str) -> Result<Cow<'_, str>, DecodeError> {
+    match percent_decode(value)? {
+        Cow::Borrowed(_) => Ok(Cow::Borrowed(value)),
+        Cow::Owned(decoded) => String::from_utf8(decoded).map(Cow::Owned).map_err(|err| {
+            // Walk the value up to the decoded octet at which UTF-8 became invalid:
+            let bytes = value.as_bytes();
+            let mut offset = 0;
+            for _ in 0..err.utf8_error().valid_up_to() {
+                offset += if bytes[offset] == b'%' { 3 } else { 1 };
+            }
+            DecodeError {
+                value: value.to_string(),
+                offset,
+                invalid_utf8: true,
+            }
+        }),
+    }
+}
+
+/// Like [percent_decode], but a `%` not followed by two hexadecimal
+/// digits is taken as-is rather than failing the decoding.
+pub(crate) fn percent_decode_lossy(value: &str) -> Cow<'_, [u8]> {
+    if !value.contains('%') {
+        return Cow::Borrowed(value.as_bytes());
+    }
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let octet = match bytes[offset] {
+            b'%' => decode_octet(bytes, offset),
+            _ => None,
+        };
+        match octet {
+            Some(octet) => {
+                decoded.push(octet);
+                offset += 3;
+            }
+            _ => {
+                decoded.push(bytes[offset]);
+                offset += 1;
+            }
+        }
+    }
+    Cow::Owned(decoded)
+}
+
+/// The octet encoded by the two hexadecimal digits following the `%` at `offset`.
+fn decode_octet(bytes: &[u8], offset: usize) -> Option<u8> {
+    bytes
+        .get(offset + 1..offset + 3)
+        .and_then(|hex| core::str::from_utf8(hex).ok())
+        .filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+}
+
+/// Percent-encodes every character of `value` other than the ASCII alphanumerics,
+/// the characters allowed unencoded in both path and query values, and the given
+/// `addl_res_avail`. Non-ASCII characters are encoded as their UTF-8 octets.
+pub(crate) fn percent_encode(value: &str, addl_res_avail: &[char]) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() || PK11_RES_AVAIL.contains(&c) || addl_res_avail.contains(&c) {
+            encoded.push(c);
+        } else {
+            let mut utf8 = [0; 4];
+            for octet in c.encode_utf8(&mut utf8).bytes() {
+                write!(encoded, "%{octet:02X}").expect("writing to a String");
+            }
+        }
+    }
+    encoded
+}
+
+/// Percent-encodes the (unencoded) `value` for use in the given [Component]: exactly the
+/// characters that would otherwise be a violation or warrant a `pkcs11 warning:` message are
+/// encoded, while ASCII alphanumerics and the characters RFC7512 makes available unencoded
+/// are left untouched. Query values additionally keep '/', '?' and '|'; path values have '/'
+/// encoded, as well as '&' (lest it be taken for a misplaced query delimiter). Every '%'
+/// is encoded, since the `value` is taken to be unencoded text, and every non-ASCII
+/// character is encoded as its UTF-8 octets.
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::{percent_encode_value, Component};
+///
+/// assert_eq!(percent_encode_value("my key/1", Component::Path), "my%20key%2F1");
+/// assert_eq!(percent_encode_value("|/usr/bin/pin 1", Component::Query), "|/usr/bin/pin%201");
+/// assert_eq!(percent_encode_value("café", Component::Path), "caf%C3%A9");
+/// ```
+pub fn percent_encode_value(value: &str, component: Component) -> String {
+    match component {
+        Component::Path => percent_encode(value, &[]),
+        Component::Query => percent_encode(value, &PK11_QUERY_RES_AVAIL),
+    }
+}
+
+/// The percent-encoded replacement of `value` in which every character `keep` rejects
+/// (as well as any '%' not beginning a well-formed percent-encoding) is encoded, or
+/// `None` when there's nothing to fix. Well-formed percent-encodings are retained.
+pub(crate) fn percent_encoding_fix(value: &str, keep: impl Fn(char) -> bool) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut fixed = String::with_capacity(value.len());
+    let mut chars = value.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        if c == '%' && decode_octet(bytes, offset).is_some() {
+            fixed.push_str(&value[offset..offset + 3]);
+            chars.nth(1);
+        } else if c != '%' && keep(c) {
+            fixed.push(c);
+        } else {
+            let mut utf8 = [0; 4];
+            for octet in c.encode_utf8(&mut utf8).bytes() {
+                write!(fixed, "%{octet:02X}").expect("writing to a String");
+            }
+        }
+    }
+    (fixed != value).then_some(fixed)
+}
+
+/// Percent-encodes every octet of `bytes`.
+pub(crate) fn percent_encode_bytes(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() * 3);
+    for octet in bytes {
+        write!(encoded, "%{octet:02X}").expect("writing to a String");
+    }
+    encoded
+}
@@ -0,0 +1,400 @@
+use super::common::ValidationErr;
+use super::{is_scheme, PK11URIMapping, ViolationKind};
+use alloc::{format, string::String};
+
+/// A classified `pin-source` attribute value, as per RFC7512's `file:` and `|command`
+/// examples and `http(s)` URLs. The values remain percent-encoded.
+///
+/// Classifying a `pin-source` is purely syntactic: no network (or any other) I/O is
+/// performed. *Resolving* the PIN, such as fetching it from an `http(s)` URL, is a
+/// separate step left to the caller.
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::PinSource;
+///
+/// let pk11_uri = "pkcs11:object=my-key?pin-source=https://pins.example.com:8443/token?id=1";
+/// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+/// match mapping.parsed_pin_source() {
+///     Some(PinSource::Url(url)) => {
+///         assert_eq!(url.scheme(), "https");
+///         assert_eq!(url.host(), "pins.example.com");
+///         assert_eq!(url.port(), Some("8443"));
+///         assert_eq!(url.path(), "/token");
+///         assert_eq!(url.query(), Some("id=1"));
+///     }
+///     _ => unreachable!("pin-source is an https URL"),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinSource<'a> {
+    /// A `file:` URI, holding its path: `file:/etc/token_pin` (or `file:///etc/token_pin`,
+    /// or `file://localhost/etc/token_pin`) results in `/etc/token_pin`. A remote host is
+    /// retained as a UNC path (as per [RFC8089][rfc8089]): `file://host/token_pin` results
+    /// in `//host/token_pin`.
+    ///
+    /// [rfc8089]: <https://datatracker.ietf.org/doc/html/rfc8089#appendix-E.3>
+    File(&'a str),
+    /// A `|` followed by a command to run, holding the command: `|/usr/lib/pinomatic`
+    /// results in `/usr/lib/pinomatic`.
+    Command(&'a str),
+    /// A well-formed `http` or `https` URL.
+    Url(PinSourceUrl<'a>),
+    /// Any other `pin-source`, such as a URI of another scheme, a bare path, or an
+    /// `http(s)` URL which isn't well-formed.
+    Other(&'a str),
+}
+
+impl<'a> PinSource<'a> {
+    /// Classifies the (percent-encoded) `pin-source` value.
+    pub fn new(pin_source: &'a str) -> Self {
+        if let Some(command) = pin_source.strip_prefix('|') {
+            return PinSource::Command(command);
+        }
+        if let Some((_, path)) = pin_source
+            .split_at_checked(5)
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("file:"))
+        {
+            // (an empty authority is the local host: `file:///etc/token_pin`)
+            return PinSource::File(
+                path.strip_prefix("//")
+                    .map(strip_localhost)
+                    .filter(|path| path.starts_with('/'))
+                    .unwrap_or(path),
+            );
+        }
+        match parse_http_url(pin_source) {
+            Some(Ok(url)) => PinSource::Url(url),
+            _ => PinSource::Other(pin_source),
+        }
+    }
+}
+
+/// How a `pin-source` attribute value provides the PIN: the [PinSource] classification
+/// without the components of an `http(s)` URL, which is a [Uri][PinSourceKind::Uri] along
+/// with any other `pin-source`. The values remain percent-encoded.
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::PinSourceKind;
+///
+/// let pk11_uri = "pkcs11:object=my-key?pin-source=|/usr/lib/pinomatic";
+/// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+/// match mapping.pin_source_kind() {
+///     Some(PinSourceKind::File(path)) => println!("read the PIN from {path}"),
+///     Some(PinSourceKind::Command(command)) => assert_eq!(command, "/usr/lib/pinomatic"),
+///     Some(PinSourceKind::Uri(uri)) => println!("retrieve the PIN from {uri}"),
+///     None => println!("prompt for the PIN"),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinSourceKind<'a> {
+    /// As per [PinSource::File].
+    File(&'a str),
+    /// As per [PinSource::Command].
+    Command(&'a str),
+    /// Any other `pin-source`, such as an `https` URL, held as-is.
+    Uri(&'a str),
+}
+
+impl<'a> PinSourceKind<'a> {
+    /// Classifies the (percent-encoded) `pin-source` value.
+    pub fn new(pin_source: &'a str) -> Self {
+        PinSource::new(pin_source).into()
+    }
+}
+
+impl<'a> From<PinSource<'a>> for PinSourceKind<'a> {
+    fn from(pin_source: PinSource<'a>) -> Self {
+        match pin_source {
+            PinSource::File(path) => PinSourceKind::File(path),
+            PinSource::Command(command) => PinSourceKind::Command(command),
+            PinSource::Url(url) => PinSourceKind::Uri(url.as_str()),
+            PinSource::Other(pin_source) => PinSourceKind::Uri(pin_source),
+        }
+    }
+}
+
+/// Strips a (case-insensitive) `localhost` authority from the `file:` URI's
+/// hierarchical part, which is equivalent to an empty one.
+fn strip_localhost(authority_and_path: &str) -> &str {
+    const LOCALHOST: &str = "localhost";
+    match authority_and_path.split_at_checked(LOCALHOST.len()) {
+        Some((authority, path))
+            if authority.eq_ignore_ascii_case(LOCALHOST) && path.starts_with('/') =>
+        {
+            path
+        }
+        _ => authority_and_path,
+    }
+}
+
+/// The components of an `http` or `https` `pin-source` URL, as per [RFC3986][rfc3986]'s
+/// `scheme "://" authority path [ "?" query ]` (a PKCS#11 URI can't contain a raw '#',
+/// so there's never a fragment). Components are slices of the `pin-source` value and
+/// therefore remain percent-encoded.
+///
+/// [rfc3986]: <https://datatracker.ietf.org/doc/html/rfc3986#section-3>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinSourceUrl<'a> {
+    url: &'a str,
+    scheme: &'a str,
+    authority: &'a str,
+    host: &'a str,
+    port: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+}
+
+impl<'a> PinSourceUrl<'a> {
+    /// The entire URL, as given.
+    pub fn as_str(&self) -> &'a str {
+        self.url
+    }
+
+    /// The URL's scheme (`http` or `https`, in whatever case it was given).
+    pub fn scheme(&self) -> &'a str {
+        self.scheme
+    }
+
+    /// The URL's entire authority: `[ userinfo "@" ] host [ ":" port ]`.
+    pub fn authority(&self) -> &'a str {
+        self.authority
+    }
+
+    /// The host of the URL's authority (an IP literal retains its brackets).
+    pub fn host(&self) -> &'a str {
+        self.host
+    }
+
+    /// The port of the URL's authority if one was given.
+    pub fn port(&self) -> Option<&'a str> {
+        self.port
+    }
+
+    /// The URL's path, which is either empty or begins with '/'.
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// The URL's query (without its '?') if one was given.
+    pub fn query(&self) -> Option<&'a str> {
+        self.query
+    }
+}
+
+impl<'a> PK11URIMapping<'a> {
+    /// Retrieve the [classified][PinSource] value of the `pin-source` query attribute
+    /// if one was parsed.
+    pub fn parsed_pin_source(&self) -> Option<PinSource<'a>> {
+        self.pin_source.map(PinSource::new)
+    }
+
+    /// Retrieve the [kind][PinSourceKind] of the `pin-source` query attribute if one
+    /// was parsed.
+    pub fn pin_source_kind(&self) -> Option<PinSourceKind<'a>> {
+        self.pin_source.map(PinSourceKind::new)
+    }
+
+    /// Retrieve the (percent-encoded) filesystem path of the `pin-source` query attribute
+    /// if one was parsed and is a `file:` URI, as per [PinSource::File].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=my-key?pin-source=file:///etc/token_pin";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(mapping.pin_source_file_path(), Some("/etc/token_pin"));
+    ///
+    /// let pk11_uri = "pkcs11:object=my-key?pin-source=|/usr/lib/pinomatic";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(mapping.pin_source_file_path(), None);
+    /// ```
+    pub fn pin_source_file_path(&self) -> Option<&'a str> {
+        match self.parsed_pin_source()? {
+            PinSource::File(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// Verifies that an `http`/`https` `pin-source` is a syntactically well-formed URL
+/// (other `pin-source` values are of no concern here).
+#[cfg(feature = "validation")]
+pub(crate) fn validate_pin_source_url(pin_source: &str) -> Result<(), ValidationErr> {
+    match parse_http_url(pin_source) {
+        Some(Err(validation_err)) => Err(validation_err),
+        _ => Ok(()),
+    }
+}
+
+/// Verifies that `pin_source` is an absolute `file:` URI, another URI with a scheme,
+/// or a `|command`.
+#[cfg(feature = "validation")]
+pub(crate) fn validate_pin_source(pin_source: &str) -> Result<(), ValidationErr> {
+    let invalid = |violation: &str, help: String, span: Option<(usize, usize)>| {
+        Err(ValidationErr {
+            kind: ViolationKind::InvalidPinSource,
+            violation: format!("Invalid `pin-source`: {violation}"),
+            help,
+            span,
+            focus: None,
+            duplicate_of: None,
+        })
+    };
+
+    if pin_source.is_empty() {
+        return invalid(
+            "the value may not be empty.",
+            String::from("Remove the empty `pin-source` or specify where the PIN comes from, ie, `file:/etc/token_pin`."),
+            None,
+        );
+    }
+    if let Some(command) = pin_source.strip_prefix('|') {
+        if command.is_empty() {
+            return invalid(
+                "the '|' must be followed by a command.",
+                String::from("Specify the command providing the PIN, ie, `|/usr/lib/pinomatic`."),
+                None,
+            );
+        }
+        return Ok(());
+    }
+
+    match scheme(pin_source) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("file") => {
+            let path = &pin_source[scheme.len() + 1..];
+            if path.starts_with('/') {
+                return Ok(());
+            }
+            invalid(
+                "a `file:` URI must specify an absolute path.",
+                format!("Replace `{pin_source}` with `{scheme}:/{path}` (assuming that's the intended path)."),
+                Some((scheme.len() + 1, pin_source.len())),
+            )
+        }
+        Some(_) => Ok(()),
+        None if pin_source.starts_with('/') => invalid(
+            "a path must be given as a `file:` URI.",
+            format!("Replace `{pin_source}` with `file:{pin_source}`."),
+            None,
+        ),
+        None => invalid(
+            "expected a `file:` URI, another URI with a scheme, or a `|command`.",
+            format!("A relative path is ambiguous: specify the absolute path as a `file:` URI, ie, `file:/path/to/{pin_source}`."),
+            None,
+        ),
+    }
+}
+
+/// The RFC3986 scheme of the `pin-source` URI, if it begins with one.
+pub(crate) fn scheme(pin_source: &str) -> Option<&str> {
+    pin_source
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| is_scheme(scheme))
+}
+
+/// Parses `pin_source` into its URL components, resulting in `None` when
+/// its scheme is neither `http` nor `https`.
+fn parse_http_url(pin_source: &str) -> Option<Result<PinSourceUrl<'_>, ValidationErr>> {
+    let (scheme, hier_part) = pin_source.split_once(':')?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+
+    let help = "An `http(s)` `pin-source` takes the form `scheme://host[:port][/path][?query]`, ie, `https://pins.example.com/token`.";
+    let malformed = |violation: &str, start: usize, end: usize| {
+        Some(Err(ValidationErr {
+            kind: ViolationKind::InvalidPinSourceUrl,
+            violation: format!("Invalid `pin-source` URL: {violation}"),
+            help: String::from(help),
+            span: Some((start, end)),
+            focus: None,
+            duplicate_of: None,
+        }))
+    };
+
+    let authority_start = scheme.len() + 3;
+    let Some(rest) = hier_part.strip_prefix("//") else {
+        return malformed(
+            "the scheme must be followed by `//` and an authority.",
+            0,
+            scheme.len() + 1,
+        );
+    };
+    let authority_end = authority_start + rest.find(['/', '?']).unwrap_or(rest.len());
+    let authority = &pin_source[authority_start..authority_end];
+
+    // [ userinfo "@" ] host [ ":" port ]
+    let host_start = authority.rfind('@').map_or(0, |at| at + 1);
+    let host_port = &authority[host_start..];
+    let host_len = if host_port.starts_with('[') {
+        match host_port.find(']') {
+            Some(close) => close + 1,
+            None => host_port.len(),
+        }
+    } else {
+        host_port.find(':').unwrap_or(host_port.len())
+    };
+    let (host, port) = host_port.split_at(host_len);
+    let host_start = authority_start + host_start;
+    let host_end = host_start + host.len();
+
+    if host.is_empty() {
+        return malformed(
+            "the authority must specify a host.",
+            authority_start - 2,
+            authority_end,
+        );
+    }
+    let is_ip_literal = host.starts_with('[');
+    let valid_host = if is_ip_literal {
+        host.len() > 2
+            && host.ends_with(']')
+            && host[1..host.len() - 1]
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c == ':' || c == '.')
+    } else {
+        host.chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['-', '.', '_', '~', '%'].contains(&c))
+    };
+    if !valid_host {
+        return malformed(
+            &format!("`{host}` is not a valid host."),
+            host_start,
+            host_end,
+        );
+    }
+
+    let port = match port.strip_prefix(':') {
+        Some(port) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => Some(port),
+        None if port.is_empty() => None,
+        _ => {
+            return malformed(
+                "a port must follow the host's ':' and consist only of digits.",
+                host_end,
+                authority_end,
+            )
+        }
+    };
+
+    // path [ "?" query ]
+    let rest = &pin_source[authority_end..];
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (rest, None),
+    };
+
+    Some(Ok(PinSourceUrl {
+        url: pin_source,
+        scheme,
+        authority,
+        host,
+        port,
+        path,
+        query,
+    }))
+}
@@ -1,32 +1,74 @@
+#[cfg(not(feature = "validation"))]
+use super::common::PK11_QATTR_NAMES;
 #[cfg(feature = "validation")]
-use super::common::{common_validation, Validation};
-use super::common::{ValidationErr, VendorAttribute};
-use super::PK11URIMapping;
+use super::common::{
+    common_validation, find_misused_delimiter, first_occurrence, missing_attribute_name,
+    strict_attribute_name, valid_utf8_value, whitespace_only_value, Validation, PK11_PATTR_NAMES,
+    PK11_QATTR_NAMES,
+};
+use super::common::{split_attribute, ValidationErr, VendorAttribute};
+#[cfg(feature = "validation")]
+use super::ViolationKind;
+use super::{PK11URIMapping, ParseOptions};
+#[cfg(feature = "validation")]
+use alloc::string::String;
+use alloc::vec;
+#[cfg(any(
+    feature = "validation",
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use alloc::{boxed::Box, format};
 #[cfg(any(
     feature = "validation",
+    feature = "warnings",
     all(debug_assertions, feature = "debug_warnings")
 ))]
-use once_cell::sync::Lazy;
+use once_cell::race::OnceBox;
 #[cfg(any(
     feature = "validation",
+    feature = "warnings",
     all(debug_assertions, feature = "debug_warnings")
 ))]
 use regex::Regex;
 
-#[cfg(all(debug_assertions, feature = "debug_warnings"))]
-use super::common::{maybe_suggest_percent_encoding, Warning};
-#[cfg(all(debug_assertions, feature = "debug_warnings"))]
-static PERCENT_ENCODING_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^(%[a-f?A-F?\d?]{2})+$").expect("regex for percent-encoding validation")
-});
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use super::common::{maybe_suggest_percent_encoding, Warning, PK11_PATH_RES_AVAIL};
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use super::percent::{percent_decode_lossy, percent_encode_bytes};
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use super::warning::{WarningKind, Warnings};
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+static PERCENT_ENCODING_REGEX: OnceBox<Regex> = OnceBox::new();
 
 #[cfg(feature = "validation")]
-static LIBRARY_VERSION_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\d+(\.\d+){0,1}$").expect("regex for library-version validation"));
+static LIBRARY_VERSION_REGEX: OnceBox<Regex> = OnceBox::new();
 
 #[cfg(feature = "validation")]
-static SLOT_ID_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\d+$").expect("regex for slot-id validation"));
+static SLOT_ID_REGEX: OnceBox<Regex> = OnceBox::new();
+
+/// The `regex` compiled from `pattern` on first use (`OnceBox` rather than a `Lazy`,
+/// needing only `alloc`).
+#[cfg(any(
+    feature = "validation",
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+fn regex<'r>(regex: &'r OnceBox<Regex>, pattern: &str) -> &'r Regex {
+    regex.get_or_init(|| Box::new(Regex::new(pattern).expect("regex for value validation")))
+}
 
 path_attributes!(
     token for "token",
@@ -47,6 +89,23 @@ path_attributes!(
 #[cfg(feature = "validation")]
 impl<'a> Validation<'a> for PK11PAttr<'a> {
     fn validate(&self, value: &'a str) -> Result<(), ValidationErr> {
+        // The '&' is allowed in path values, but not when it's evidently been used
+        // in place of the ';' delimiter, as in `object=my-key&type=cert`:
+        if let Some(offset) = find_misused_delimiter(value, '&', |attribute| {
+            PK11_PATTR_NAMES.contains(&attribute) || PK11_QATTR_NAMES.contains(&attribute)
+        }) {
+            return Err(ValidationErr {
+                kind: ViolationKind::MisplacedDelimiter,
+                violation: String::from(
+                    "Invalid `pk11-path`: path attributes are separated by the ';' delimiter.",
+                ),
+                help: String::from("Use ';' to separate path attributes, not '&'."),
+                span: Some((offset, offset + 1)),
+                focus: None,
+                duplicate_of: None,
+            });
+        }
+
         match self {
             token(_)
             | manufacturer(_)
@@ -67,37 +126,53 @@ impl<'a> Validation<'a> for PK11PAttr<'a> {
                 // (however, the '/' is perfectly fine for query attribute values)
                 if value.contains('/') {
                     return Err(ValidationErr {
+                        kind: ViolationKind::SlashInPathValue,
                         violation: String::from("Invalid `pk11-pattr`: The general '/' delimiter must always be percent-encoded in a path component."),
-                        help: format!("Replace `{value}` with `{fixed}`.", fixed=value.replace('/', "%2F"))
+                        help: format!("Replace `{value}` with `{fixed}`.", fixed=value.replace('/', "%2F")),
+                        span: None,
+                        focus: None,
+                        duplicate_of: None,
                     });
                 }
             }
             r#type(_) => {
                 if !["public", "private", "cert", "secret-key", "data"].contains(&value) {
                     return Err(ValidationErr {
+                        kind: ViolationKind::InvalidType,
                         violation: String::from(r#"Invalid `pk11-pattr`: `pk11-type` = `"type" "=" ( "public" / "private" / "cert" / "secret-key" / "data" )`."#),
                         help: format!("Replace `{value}` value with one of `public`, `private`, `cert`, `secret-key`, or `data`."),
+                        span: None,
+                        focus: None,
+                        duplicate_of: None,
                     });
                 }
             }
             library_version(_) => {
                 // Regex validation for `1*DIGIT [ "." 1*DIGIT ]`:
-                if !LIBRARY_VERSION_REGEX.is_match(value) {
+                if !regex(&LIBRARY_VERSION_REGEX, r"^\d+(\.\d+){0,1}$").is_match(value) {
                     return Err(ValidationErr{
+                        kind: ViolationKind::InvalidLibraryVersion,
                         violation: String::from(r#"Invalid `pk11-pattr`: `pk11-lib-ver` = `"library-version" "=" 1*DIGIT [ "." 1*DIGIT ]`."#),
                         help: String::from("The `library-version` attribute represents the major and minor version decimal \
                         number of the library and its format is `M.N`. The major version is required."),
+                        span: None,
+                        focus: None,
+                        duplicate_of: None,
                     });
                 }
             }
             slot_id(_) => {
                 // Regex validation for `1*DIGIT`:
-                if !SLOT_ID_REGEX.is_match(value) {
+                if !regex(&SLOT_ID_REGEX, r"^\d+$").is_match(value) {
                     return Err(ValidationErr {
+                        kind: ViolationKind::NonNumericSlotId,
                         violation: String::from(
                             r#"Invalid `pk11-pattr`: `pk11-slot-id` = `"slot-id" "=" 1*DIGIT`."#,
                         ),
                         help: String::from("The `slot-id` value may only be numeric."),
+                        span: None,
+                        focus: None,
+                        duplicate_of: None,
                     });
                 }
             }
@@ -106,14 +181,36 @@ impl<'a> Validation<'a> for PK11PAttr<'a> {
     }
 }
 
-#[cfg(all(debug_assertions, feature = "debug_warnings"))]
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
 impl<'a> Warning<'a> for PK11PAttr<'a> {
-    fn maybe_warn(&self, value: &'a str) {
+    fn maybe_warn(&self, value: &'a str, options: &ParseOptions, warnings: &mut Warnings<'a>) {
         match self {
-            id(_) => {
-                if !PERCENT_ENCODING_REGEX.is_match(value) {
-                    println!("pkcs11 warning: the whole value of the `id` attribute SHOULD be percent-encoded: id={value}.");
-                }
+            id(_) if !regex(&PERCENT_ENCODING_REGEX, r"^(%[a-f?A-F?\d?]{2})+$").is_match(value) => {
+                // (suggesting the value's octets, those already encoded included, all encoded)
+                let fix =
+                    (!value.is_empty()).then(|| percent_encode_bytes(&percent_decode_lossy(value)));
+                warnings.warn_fixable(
+                    WarningKind::IdNotPercentEncoded,
+                    self.to_str(),
+                    value,
+                    format!("the whole value of the `id` attribute SHOULD be percent-encoded: id={value}."),
+                    fix,
+                );
+            }
+            slot_id(_)
+                if !value.is_empty()
+                    && value.bytes().all(|b| b.is_ascii_digit())
+                    && value.parse::<u64>().is_err() =>
+            {
+                warnings.warn(
+                    WarningKind::SlotIdOverflow,
+                    self.to_str(),
+                    value,
+                    format!("the `slot-id` value exceeds the range of a 64-bit `CK_SLOT_ID`: slot-id={value}."),
+                );
             }
             token(_)
             | manufacturer(_)
@@ -123,23 +220,107 @@ impl<'a> Warning<'a> for PK11PAttr<'a> {
             | library_description(_)
             | object(_)
             | slot_description(_)
-            | slot_manufacturer(_)
-            | VAttr(_) => {
-                const PK11_PATH_RES_AVAIL: [char; 1] = ['&'];
-                maybe_suggest_percent_encoding(self.to_str(), value, PK11_PATH_RES_AVAIL);
+            | slot_manufacturer(_) => {
+                maybe_suggest_percent_encoding(
+                    self.to_str(),
+                    value,
+                    PK11_PATH_RES_AVAIL,
+                    &[],
+                    warnings,
+                );
+            }
+            VAttr(_) => {
+                maybe_suggest_percent_encoding(
+                    self.to_str(),
+                    value,
+                    PK11_PATH_RES_AVAIL,
+                    options.vendor_allowed_chars,
+                    warnings,
+                );
             }
             _ => {}
         }
+        // An unencoded '=' is allowed in a value, but is more than likely a mistyped
+        // attribute (as in `object=a=b`):
+        let text = !matches!(self, id(_) | library_version(_) | r#type(_) | slot_id(_));
+        let allowed = matches!(self, VAttr(_)) && options.vendor_allowed_chars.contains(&'=');
+        if text && !allowed {
+            if let Some(offset) = value.find('=') {
+                warnings.warn_fixable(
+                    WarningKind::UnencodedEquals,
+                    self.to_str(),
+                    value,
+                    format!("the `=` identified at offset {offset} in `{value}` of component `{attribute}={value}` SHOULD be percent-encoded as `%3D`, lest it be taken for a mistyped attribute.", attribute = self.to_str()),
+                    Some(value.replace('=', "%3D")),
+                );
+            }
+        }
     }
 }
 
 pub(crate) fn assign<'a>(
     pk11_pattr: &'a str,
     mapping: &mut PK11URIMapping<'a>,
+    options: &ParseOptions,
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    warnings: &mut Warnings<'a>,
 ) -> Result<(), ValidationErr> {
     #[cfg(feature = "validation")]
+    if options.strict_attribute_names {
+        strict_attribute_name(pk11_pattr)?;
+    }
+    #[cfg(feature = "validation")]
+    missing_attribute_name(pk11_pattr)?;
+    #[cfg(feature = "validation")]
+    whitespace_only_value(pk11_pattr)?;
     let PathAttribute { attr, value } = PathAttribute::try_from(pk11_pattr)?;
+
+    // Without validation, a misplaced standard query attribute is assigned where
+    // it belongs (rather than being taken for a vendor-specific attribute):
     #[cfg(not(feature = "validation"))]
-    let PathAttribute { attr, value } = PathAttribute::from(pk11_pattr);
+    if matches!(&attr, VAttr(vendor_attribute) if PK11_QATTR_NAMES.contains(&vendor_attribute.0)) {
+        #[cfg(any(
+            feature = "warnings",
+            all(debug_assertions, feature = "debug_warnings")
+        ))]
+        return super::pk11_qattr::assign(pk11_pattr, mapping, options, warnings);
+        #[cfg(not(any(
+            feature = "warnings",
+            all(debug_assertions, feature = "debug_warnings")
+        )))]
+        return super::pk11_qattr::assign(pk11_pattr, mapping, options);
+    }
+
+    // Misplaced (registered) extra query attribute?
+    #[cfg(feature = "validation")]
+    if let VAttr(vendor_attribute) = &attr {
+        if options.is_extra_query_attribute(vendor_attribute.0) {
+            return Err(ValidationErr {
+                kind: ViolationKind::PathQueryNamingCollision,
+                violation: String::from("Naming collision with standard query component."),
+                help: format!(
+                    "Move `{}` and its value to the PKCS#11 URI query.",
+                    vendor_attribute.0
+                ),
+                span: None,
+                focus: None,
+                duplicate_of: None,
+            });
+        }
+    }
+    // (the `id` is binary, and a vendor-specific value's encoding is up to the vendor)
+    #[cfg(feature = "validation")]
+    if options.validate_utf8 && !matches!(attr, id(_) | VAttr(_)) {
+        valid_utf8_value(pk11_pattr)?;
+    }
+
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    attr.warn(value, options, warnings);
     attr.assign(value, mapping)
 }
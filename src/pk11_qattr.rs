@@ -1,10 +1,39 @@
+#[cfg(not(feature = "validation"))]
+use super::common::PK11_PATTR_NAMES;
 #[cfg(feature = "validation")]
-use super::common::{common_validation, Validation};
-use super::common::{ValidationErr, VendorAttribute};
-use super::PK11URIMapping;
+use super::common::{
+    common_validation, find_misused_delimiter, first_occurrence, missing_attribute_name,
+    strict_attribute_name, valid_utf8_value, whitespace_only_value, Validation,
+};
+use super::common::{split_attribute, ValidationErr, VendorAttribute};
+#[cfg(feature = "validation")]
+use super::pin_source::{validate_pin_source, validate_pin_source_url};
+#[cfg(feature = "validation")]
+use super::ViolationKind;
+use super::{PK11URIMapping, ParseOptions};
+#[cfg(any(
+    feature = "validation",
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use alloc::format;
+#[cfg(feature = "validation")]
+use alloc::string::String;
+use alloc::vec;
 
-#[cfg(all(debug_assertions, feature = "debug_warnings"))]
-use super::common::{maybe_suggest_percent_encoding, Warning};
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use super::common::{maybe_suggest_percent_encoding, Warning, PK11_QUERY_RES_AVAIL};
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+use super::{
+    tidy,
+    warning::{WarningKind, Warnings},
+};
 
 query_attributes!(
     pin_source for "pin-source",
@@ -19,33 +48,170 @@ impl<'a> Validation<'a> for PK11QAttr<'a> {
         if let Some(validation_err) = common_validation(value) {
             return Err(validation_err);
         }
+
+        // A raw ';' is tolerated within a query value, unless it's evidently been
+        // used in place of the '&' delimiter, as in `pin-source=x;module-name=y`:
+        let looks_like_attribute = |attribute: &str| {
+            !attribute.is_empty()
+                && attribute
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        };
+        if let Some(offset) = find_misused_delimiter(value, ';', looks_like_attribute) {
+            return Err(ValidationErr {
+                kind: ViolationKind::MisplacedDelimiter,
+                violation: String::from(
+                    "Invalid `pk11-query`: query attributes are separated by the '&' delimiter.",
+                ),
+                help: String::from("Use '&' to separate query attributes, not ';'."),
+                span: Some((offset, offset + 1)),
+                focus: None,
+                duplicate_of: None,
+            });
+        }
         Ok(())
     }
 }
 
-#[cfg(all(debug_assertions, feature = "debug_warnings"))]
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
 impl<'a> Warning<'a> for PK11QAttr<'a> {
-    fn maybe_warn(&self, value: &'a str) {
+    fn maybe_warn(&self, value: &'a str, options: &ParseOptions, warnings: &mut Warnings<'a>) {
         if matches!(self, module_name(_))
             && (value.starts_with("lib") || value.chars().any(|c| ['.', '/', '\\'].contains(&c)))
         {
-            println!(
-                r#"pkcs11 warning: the attribute "module-name" SHOULD contain a case-insensitive PKCS #11 module name (not path nor filename) without system-specific affices. Context: `module-name={value}`."#
+            // (a path or filename is more than likely meant as the `module-path`)
+            warnings.warn_with_help(
+                WarningKind::PathLikeModuleName,
+                self.to_str(),
+                value,
+                format!(r#"the attribute "module-name" SHOULD contain a case-insensitive PKCS #11 module name (not path nor filename) without system-specific affices. Context: `module-name={value}`."#),
+                Some(format!("Move the value to the `module-path` attribute, ie, `module-path={}`, as `module-name` is meant to be a system-independent module name (such as `mypkcs11`).", tidy(value))),
+            );
+        }
+        // A relative `module-path` depends on the working directory of whichever process loads
+        // the module, which is a common mistake (albeit a platform-specific one, hence a warning):
+        if matches!(self, module_path(_)) && !is_absolute_module_path(value) {
+            warnings.warn(
+                WarningKind::RelativeModulePath,
+                self.to_str(),
+                value,
+                format!(r#"the attribute "module-path" SHOULD be an absolute path (such as `/usr/lib/mypkcs11.so` or `C:%5Cmypkcs11.dll`) to a PKCS #11 module. Context: `module-path={value}`."#),
             );
         }
         // All query component values are `*pk11-qchar` so make a blanket call:
-        const PK11_QUERY_RES_AVAIL: [char; 3] = ['/', '?', '|'];
-        maybe_suggest_percent_encoding(self.to_str(), value, PK11_QUERY_RES_AVAIL);
+        let allowed = match self {
+            VAttr(_) => options.vendor_allowed_chars,
+            _ => &[],
+        };
+        maybe_suggest_percent_encoding(
+            self.to_str(),
+            value,
+            PK11_QUERY_RES_AVAIL,
+            allowed,
+            warnings,
+        );
+    }
+}
+
+/// Whether the `module-path` value is an absolute unix-style path or a Windows
+/// drive letter path (its '\\' likely percent-encoded).
+#[cfg(any(
+    feature = "warnings",
+    all(debug_assertions, feature = "debug_warnings")
+))]
+fn is_absolute_module_path(value: &str) -> bool {
+    match value.as_bytes() {
+        [b'/', ..] => true,
+        [drive, b':', rest @ ..] if drive.is_ascii_alphabetic() => {
+            rest.starts_with(b"/")
+                || rest.starts_with(b"\\")
+                || rest
+                    .get(..3)
+                    .is_some_and(|sep| sep.eq_ignore_ascii_case(b"%5C"))
+        }
+        _ => false,
     }
 }
 
 pub(crate) fn assign<'a>(
     pk11_qattr: &'a str,
     mapping: &mut PK11URIMapping<'a>,
+    options: &ParseOptions,
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    warnings: &mut Warnings<'a>,
 ) -> Result<(), ValidationErr> {
     #[cfg(feature = "validation")]
+    if options.strict_attribute_names {
+        strict_attribute_name(pk11_qattr)?;
+    }
+    #[cfg(feature = "validation")]
+    missing_attribute_name(pk11_qattr)?;
+    #[cfg(feature = "validation")]
+    whitespace_only_value(pk11_qattr)?;
     let QueryAttribute { attr, value } = QueryAttribute::try_from(pk11_qattr)?;
+
+    #[cfg(feature = "validation")]
+    if options.validate_pin_source && matches!(attr, pin_source(_)) {
+        validate_pin_source(value).map_err(|err| err.within(value, pk11_qattr))?;
+    }
+    #[cfg(feature = "validation")]
+    if options.validate_pin_source_url && matches!(attr, pin_source(_)) {
+        validate_pin_source_url(value).map_err(|err| err.within(value, pk11_qattr))?;
+    }
+    #[cfg(feature = "validation")]
+    if options.validate_utf8 && !matches!(attr, VAttr(_)) {
+        valid_utf8_value(pk11_qattr)?;
+    }
+
+    // Without validation, a misplaced standard path attribute is assigned where
+    // it belongs (rather than being taken for a vendor-specific attribute):
     #[cfg(not(feature = "validation"))]
-    let QueryAttribute { attr, value } = QueryAttribute::from(pk11_qattr);
-    attr.assign(value, mapping)
+    if matches!(&attr, VAttr(vendor_attribute) if PK11_PATTR_NAMES.contains(&vendor_attribute.0)) {
+        #[cfg(any(
+            feature = "warnings",
+            all(debug_assertions, feature = "debug_warnings")
+        ))]
+        return super::pk11_pattr::assign(pk11_qattr, mapping, options, warnings);
+        #[cfg(not(any(
+            feature = "warnings",
+            all(debug_assertions, feature = "debug_warnings")
+        )))]
+        return super::pk11_pattr::assign(pk11_qattr, mapping, options);
+    }
+    #[cfg(any(
+        feature = "warnings",
+        all(debug_assertions, feature = "debug_warnings")
+    ))]
+    attr.warn(value, options, warnings);
+    match attr {
+        // Registered extra query attributes are single-valued:
+        VAttr(vendor_attribute) if options.is_extra_query_attribute(vendor_attribute.0) => {
+            #[cfg(feature = "validation")]
+            if mapping.vendor.contains_key(vendor_attribute.0) {
+                return Err(ValidationErr {
+                    kind: ViolationKind::DuplicateQueryAttr,
+                    violation: format!(
+                        r#"Duplicate `pk11-qattr` standard name: "{}"."#,
+                        vendor_attribute.0
+                    ),
+                    help: String::from("A PKCS #11 URI must not contain duplicate standard attributes of the same name in the URI query component."),
+                    span: None,
+                    focus: None,
+                    duplicate_of: mapping
+                        .vendor
+                        .get(vendor_attribute.0)
+                        .map(|values| first_occurrence(values[0])),
+                });
+            }
+            mapping.vendor.insert(vendor_attribute.0, vec![value]);
+            Ok(())
+        }
+        attr => attr.assign(value, mapping),
+    }
 }
@@ -0,0 +1,56 @@
+use super::{standard_component, Component, PK11URIMapping};
+use alloc::string::{String, ToString};
+use core::{error, fmt};
+
+/// Issued when [requiring][PK11URIMapping::require()] an attribute the mapping lacks,
+/// naming the missing attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingAttrError {
+    /// The name of the missing attribute.
+    attribute: String,
+}
+
+impl MissingAttrError {
+    /// The name of the missing attribute (ie, `object` or `x-muppet`).
+    pub fn attribute(&self) -> &str {
+        &self.attribute
+    }
+}
+
+impl error::Error for MissingAttrError {}
+
+impl fmt::Display for MissingAttrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match standard_component(&self.attribute) {
+            Some(Component::Path) => "path",
+            Some(Component::Query) => "query",
+            None => "vendor-specific",
+        };
+        write!(f, "missing required `{}` {kind} attribute", self.attribute)
+    }
+}
+
+impl<'a> PK11URIMapping<'a> {
+    /// Retrieve the value of the attribute named `attr`, which the caller requires: a
+    /// missing attribute results in a [MissingAttrError] naming it, sparing call sites
+    /// the `ok_or` of each accessor. A *vendor-specific* attribute's first value is
+    /// retrieved, as per [vendor_first][PK11URIMapping::vendor_first()].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let mapping = pk11_uri_parser::parse("pkcs11:object=my-key?x-slot=2")
+    ///     .expect("mapping should be valid");
+    /// assert_eq!(mapping.require("object"), Ok("my-key"));
+    /// assert_eq!(mapping.require("x-slot"), Ok("2"));
+    ///
+    /// let err = mapping.require("type").expect_err("missing type attribute");
+    /// assert_eq!(err.attribute(), "type");
+    /// assert_eq!(err.to_string(), "missing required `type` path attribute");
+    /// ```
+    pub fn require(&self, attr: &str) -> Result<&'a str, MissingAttrError> {
+        self.attribute_value(attr).ok_or_else(|| MissingAttrError {
+            attribute: attr.to_string(),
+        })
+    }
+}
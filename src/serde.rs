@@ -0,0 +1,142 @@
+use super::{PK11URIError, PK11URIMapping, ViolationKind};
+use ::serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use ::serde::ser::{Serialize, SerializeMap, Serializer};
+use alloc::format;
+use core::fmt;
+
+/// The key of the nested vendor-specific attributes object.
+const VENDOR: &str = "vendor";
+
+/// Serializes the mapping as an object of its parsed attributes keyed by their RFC7512
+/// names (in RFC7512 order, omitting those that weren't parsed), followed by a nested
+/// `vendor` object of the vendor-specific attributes' values sorted by name (omitted
+/// when there are none). Values remain percent-encoded, exactly as they were parsed.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let pk11_uri = "pkcs11:object=my-key;type=private;v-attr=a?pin-source=file:/etc/token_pin";
+/// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+/// assert_eq!(
+///     serde_json::to_string(&mapping).expect("serializable mapping"),
+///     r#"{"object":"my-key","type":"private","pin-source":"file:/etc/token_pin","vendor":{"v-attr":["a"]}}"#
+/// );
+/// # }
+/// ```
+impl Serialize for PK11URIMapping<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (attr, value) in self
+            .path_attributes()
+            .into_iter()
+            .chain(self.query_attributes())
+        {
+            if let Some(value) = value {
+                map.serialize_entry(attr, value)?;
+            }
+        }
+        if !self.vendor.is_empty() {
+            map.serialize_entry(VENDOR, &SortedVendor(self))?;
+        }
+        map.end()
+    }
+}
+
+/// Serializes the vendor-specific attributes in a stable (sorted) order.
+struct SortedVendor<'m, 'a>(&'m PK11URIMapping<'a>);
+
+impl Serialize for SortedVendor<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.sorted_vendor())
+    }
+}
+
+/// Deserializes the object the `Serialize` implementation produces, borrowing every
+/// name and value from the input: a (JSON) string holding escape sequences can't be
+/// borrowed and results in an error.
+///
+/// The values are taken as-is and are *not* validated, so a mapping read from an untrusted
+/// source should be verified by way of [to_uri][PK11URIMapping::to_uri()]. Like any mapping
+/// which wasn't produced by parsing, the deserialized mapping has no
+/// [attribute_order][PK11URIMapping::attribute_order()].
+impl<'de> Deserialize<'de> for PK11URIMapping<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MappingVisitor)
+    }
+}
+
+struct MappingVisitor;
+
+impl<'de> Visitor<'de> for MappingVisitor {
+    type Value = PK11URIMapping<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of PKCS#11 URI attributes")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut mapping = PK11URIMapping::default();
+        while let Some(attr) = map.next_key::<&'de str>()? {
+            if attr == VENDOR {
+                if !mapping.vendor.is_empty() {
+                    return Err(de::Error::duplicate_field(VENDOR));
+                }
+                mapping.vendor = map.next_value()?;
+                continue;
+            }
+            let field = mapping.standard_attribute_mut(attr).ok_or_else(|| {
+                de::Error::custom(format!(
+                    "unknown standard attribute `{attr}` (vendor-specific attributes belong in the `{VENDOR}` object)"
+                ))
+            })?;
+            if field.replace(map.next_value()?).is_some() {
+                return Err(de::Error::custom(format!(
+                    "duplicate standard attribute `{attr}`"
+                )));
+            }
+        }
+        Ok(mapping)
+    }
+}
+
+/// Serializes the error as a diagnostic an editor (or other tooling) can render: an object
+/// of the tidied `uri`, the `span` of the violation within it as a `[start, end]` pair, the
+/// `kind` of violation, followed by the `violation` and `help` text. The `related_span` of a
+/// duplicated attribute's first occurrence is included whenever there is one, as is the
+/// `focus_span` of the first offending character (such as the first of a value's spaces).
+///
+/// ```
+/// # #[cfg(all(feature = "serde", feature = "validation"))]
+/// # {
+/// let err = pk11_uri_parser::parse("pkcs11:object=my key").expect_err("empty space in value");
+/// let json: serde_json::Value = serde_json::to_value(&err).expect("serializable error");
+/// assert_eq!(json["uri"], "pkcs11:object=my key");
+/// assert_eq!(json["span"], serde_json::json!([7, 20]));
+/// assert_eq!(json["kind"], "EmptySpaceInValue");
+/// assert_eq!(json["focus_span"], serde_json::json!([16, 17]));
+/// # }
+/// ```
+impl Serialize for PK11URIError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("uri", &self.pk11_uri)?;
+        map.serialize_entry("span", &self.error_span)?;
+        map.serialize_entry("kind", &self.kind)?;
+        map.serialize_entry("violation", &self.violation)?;
+        map.serialize_entry("help", &self.help)?;
+        if let Some(focus_span) = self.focus_span() {
+            map.serialize_entry("focus_span", &focus_span)?;
+        }
+        if let Some(related_span) = self.related_span() {
+            map.serialize_entry("related_span", &related_span)?;
+        }
+        map.end()
+    }
+}
+
+/// Serializes the kind as its name (ie, `"EmptySpaceInValue"`).
+impl Serialize for ViolationKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{self:?}"))
+    }
+}
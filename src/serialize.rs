@@ -0,0 +1,72 @@
+use super::{PK11URIMapping, PKCS11_SCHEME};
+use core::fmt;
+
+/// Reconstructs the canonical form of the mapping: standard path attributes in
+/// RFC7512 order followed by vendor-specific path attributes sorted by name, then
+/// (if there's anything to put there) the query, ordered likewise.  Values are
+/// written exactly as they were parsed, so any URI that parsed cleanly will parse
+/// again to an equivalent mapping.
+///
+/// ```
+/// let pk11_uri = "pkcs11:type=cert;object=my-certificate?pin-source=file:/etc/token_pin";
+/// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+/// assert_eq!(
+///     mapping.to_string(),
+///     "pkcs11:object=my-certificate;type=cert?pin-source=file:/etc/token_pin"
+/// );
+/// ```
+impl fmt::Display for PK11URIMapping<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let vendor = self.sorted_vendor();
+
+        f.write_str(PKCS11_SCHEME)?;
+
+        let mut delimiter = "";
+        for (attr, value) in self
+            .path_attributes()
+            .into_iter()
+            .filter_map(|(attr, value)| Some((attr, value?)))
+            .chain(
+                vendor
+                    .iter()
+                    .filter_map(|(attr, values)| Some((*attr, place_vendor_values(values).0?))),
+            )
+        {
+            write!(f, "{delimiter}{attr}={value}")?;
+            delimiter = ";";
+        }
+
+        let mut delimiter = "?";
+        for (attr, value) in self
+            .query_attributes()
+            .into_iter()
+            .filter_map(|(attr, value)| Some((attr, value?)))
+            .chain(vendor.iter().flat_map(|(attr, values)| {
+                place_vendor_values(values)
+                    .1
+                    .iter()
+                    .map(|value| (*attr, *value))
+            }))
+        {
+            write!(f, "{delimiter}{attr}={value}")?;
+            delimiter = "&";
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a vendor-specific attribute's values into the (optional) value
+/// written to the path component and the values written to the query
+/// component.  A lone value belongs in the path unless it holds a character
+/// only the query may carry (including a ';', tolerated there); multiple values belong in the query, except
+/// for a leading value containing '&' (which can only have come from the path).
+pub(crate) fn place_vendor_values<'v, 'a>(
+    values: &'v [&'a str],
+) -> (Option<&'a str>, &'v [&'a str]) {
+    match values {
+        [value] if !value.contains(['/', '?', '|', ';']) => (Some(value), &[]),
+        [first, rest @ ..] if first.contains('&') => (Some(first), rest),
+        _ => (None, values),
+    }
+}
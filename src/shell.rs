@@ -0,0 +1,88 @@
+use super::PK11URIMapping;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+impl PK11URIMapping<'_> {
+    /// The mapping's [normalized][PK11URIMapping::normalize()] URI quoted for use as a
+    /// single argument in a POSIX shell, as per [shell_quote].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:type=private;object=my%2dkey?pin-source=|/usr/lib/pinomatic";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// assert_eq!(
+    ///     mapping.to_shell_arg(),
+    ///     "'pkcs11:object=my-key;type=private?pin-source=|/usr/lib/pinomatic'"
+    /// );
+    /// ```
+    pub fn to_shell_arg(&self) -> String {
+        shell_quote(&self.normalize())
+    }
+
+    /// The mapping's attributes as environment variable assignments for shell scripts,
+    /// eg. `("PKCS11_OBJECT", "my-key")` given a `prefix` of `PKCS11_`.
+    ///
+    /// Each variable is named by the `prefix` followed by the attribute name in uppercase
+    /// with every '-' replaced by '_' (so `module-name` becomes `MODULE_NAME`). Variables
+    /// appear in the same order as the canonical URI's attributes: standard path attributes,
+    /// standard query attributes, then vendor-specific attributes sorted by name. Values are
+    /// exactly as parsed (ie, still percent-encoded); the multiple values of a vendor-specific
+    /// attribute are separated by a space (which can't otherwise appear in a value).
+    ///
+    /// `pin-value` is never included: a PIN in the environment is readable by any process
+    /// inspecting it.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// let pk11_uri = "pkcs11:object=my-key;type=private?module-name=mypkcs11&pin-value=123456";
+    /// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+    /// for (name, value) in mapping.to_env("PKCS11_") {
+    ///     println!("export {name}={value}");
+    /// }
+    /// ```
+    /// prints
+    /// ```terminal
+    /// export PKCS11_OBJECT=my-key
+    /// export PKCS11_TYPE=private
+    /// export PKCS11_MODULE_NAME=mypkcs11
+    /// ```
+    pub fn to_env(&self, prefix: &str) -> Vec<(String, String)> {
+        let env_name =
+            |attribute: &str| format!("{prefix}{}", attribute.to_uppercase().replace('-', "_"));
+        self.path_attributes()
+            .into_iter()
+            .chain(self.query_attributes())
+            .filter(|(attribute, _)| *attribute != "pin-value")
+            .filter_map(|(attribute, value)| Some((env_name(attribute), value?.to_string())))
+            .chain(
+                self.sorted_vendor()
+                    .into_iter()
+                    .map(|(attribute, values)| (env_name(attribute), values.join(" "))),
+            )
+            .collect()
+    }
+}
+
+/// Quotes `pk11_uri` so a POSIX shell passes it to a command (such as `p11tool`) as a
+/// single, literal argument; otherwise `;`, `&`, `|` and `?` would be interpreted by
+/// the shell. The whole value is wrapped in single quotes, within which the shell
+/// treats every character literally. A single quote can't appear inside such a string,
+/// so each embedded `'` is written as `'\''` (end the quoting, an escaped quote, resume
+/// the quoting).
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::shell_quote;
+///
+/// assert_eq!(shell_quote("pkcs11:object=my-key;type=private"), "'pkcs11:object=my-key;type=private'");
+/// assert_eq!(shell_quote("pkcs11:object=Joe's"), r"'pkcs11:object=Joe'\''s'");
+/// ```
+pub fn shell_quote(pk11_uri: &str) -> String {
+    format!("'{}'", pk11_uri.replace('\'', r"'\''"))
+}
@@ -0,0 +1,73 @@
+/// The category of an RFC7512 violation, allowing a [PK11URIError][super::PK11URIError]
+/// to be handled programmatically (such as for automated remediation) rather than by
+/// inspecting its human-friendly `violation` and `help` text.
+///
+/// ## Examples
+///
+/// ```
+/// use pk11_uri_parser::ViolationKind;
+///
+/// let pk11_uri = "pkcs11:object=my key";
+/// # #[cfg(feature = "validation")]
+/// # {
+/// let err = pk11_uri_parser::parse(pk11_uri).expect_err("empty spaces in value violation");
+/// match err.kind() {
+///     ViolationKind::EmptySpaceInValue => println!("percent-encode the spaces"),
+///     kind => println!("{kind:?}: {err}"),
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ViolationKind {
+    /// The uri doesn't begin with the `pkcs11:` scheme.
+    MissingScheme,
+    /// The uri holds a byte which isn't ASCII (see `parse_bytes`).
+    NonAsciiByte,
+    /// A value contains an empty space, which must be percent-encoded.
+    EmptySpaceInValue,
+    /// A value is nothing but whitespace, which would be trimmed away.
+    WhitespaceOnlyValue,
+    /// A value contains a '#', which must be percent-encoded.
+    HashInValue,
+    /// A value contains a '%' which isn't followed by two hexadecimal digits.
+    InvalidPercentEncoding,
+    /// A text value's percent-decoded octets aren't valid UTF-8 (see
+    /// `ParseOptions::validate_utf8`).
+    InvalidUtf8,
+    /// A path value contains a '/', which must be percent-encoded.
+    SlashInPathValue,
+    /// A path attribute appears more than once.
+    DuplicatePathAttr,
+    /// A standard (or registered extra) query attribute appears more than once.
+    DuplicateQueryAttr,
+    /// A standard path attribute appears in the query (or vice versa), or a
+    /// standard attribute is given as though it were vendor-specific.
+    PathQueryNamingCollision,
+    /// The `library-version` value isn't of the form `M.N`.
+    InvalidLibraryVersion,
+    /// The `slot-id` value isn't numeric.
+    NonNumericSlotId,
+    /// The `type` value isn't one RFC7512 defines.
+    InvalidType,
+    /// A delimiter is misplaced or is used in place of another, as in
+    /// `object=my-key&type=cert`.
+    MisplacedDelimiter,
+    /// A vendor-specific attribute name contains characters other than
+    /// alphanumerics, '-', or '_'.
+    InvalidVendorName,
+    /// A component isn't of the form `name=value`.
+    MalformedComponent,
+    /// An `http(s)` `pin-source` isn't a well-formed URL.
+    InvalidPinSourceUrl,
+    /// A `pin-source` is neither an absolute `file:` URI, another URI, nor a `|command`.
+    InvalidPinSource,
+    /// Attributes RFC7512 says a PKCS#11 URI shouldn't combine, such as `pin-source`
+    /// and `pin-value`.
+    ConflictingAttributes,
+    /// An attribute required of the mapping is missing.
+    MissingRequiredAttr,
+    /// An RFC7512 "SHOULD/SHOULD NOT" guideline isn't followed, which is otherwise
+    /// merely a warning (see `ParseOptions::strict_advisories`).
+    StrictAdvisory,
+}
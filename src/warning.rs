@@ -0,0 +1,194 @@
+use super::{tidy, Component};
+#[cfg(feature = "warnings")]
+use super::{tidy_len, PK11URIError, ViolationKind};
+use alloc::format;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// The category of a [PK11URIWarning], allowing warnings to be handled (or tallied, as
+/// by a [BatchReport][super::BatchReport]) programmatically rather than by inspecting
+/// their human-friendly message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A character which SHOULD be percent-encoded isn't.
+    UnencodedCharacter,
+    /// A '%' isn't followed by two hexadecimal digits.
+    MalformedPercentEncoding,
+    /// An '=' within a value (more than likely a mistyped attribute) isn't percent-encoded.
+    UnencodedEquals,
+    /// The `id` value isn't percent-encoded in its entirety.
+    IdNotPercentEncoded,
+    /// The `slot-id` value exceeds the range of a 64-bit `CK_SLOT_ID`.
+    SlotIdOverflow,
+    /// The `module-name` value looks like a path or filename rather than a module name.
+    PathLikeModuleName,
+    /// The `module-path` value isn't an absolute path.
+    RelativeModulePath,
+    /// A vendor-specific attribute name has the deprecated "x-" prefix.
+    DeprecatedVendorPrefix,
+    /// Both `module-name` and `module-path` are given.
+    ModuleNameAndPath,
+    /// Both `pin-source` and `pin-value` are given.
+    PinSourceAndPinValue,
+    /// A `type` is given without an `object`.
+    TypeWithoutObject,
+}
+
+/// Issued when [parsing][super::parse] a PKCS#11 URI whose values do not comply with
+/// [RFC7512][rfc7512] "SHOULD/SHOULD NOT" (etc.) guidelines.  Unlike a
+/// [PK11URIError][super::PK11URIError], a warning never prevents a mapping from being parsed.
+///
+/// Displaying the warning yields the same `pkcs11 warning:` message printed for debug builds.
+///
+/// [rfc7512]: <https://datatracker.ietf.org/doc/html/rfc7512>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PK11URIWarning {
+    /// The category of the issue.
+    kind: WarningKind,
+    /// The name of the attribute the warning concerns.
+    attribute: String,
+    /// The component of the uri the offending characters are in.
+    component: Component,
+    /// Byte offset within the parsed uri of the offending characters.
+    offset: usize,
+    /// Byte length of the offending characters.
+    len: usize,
+    /// Description of the issue.
+    message: String,
+    /// The help of the error promoting the warning, if there's anything more
+    /// specific to suggest than following the guideline.
+    help: Option<String>,
+}
+
+#[cfg(feature = "warnings")]
+impl PK11URIWarning {
+    /// The [category][WarningKind] of the issue, for handling it programmatically.
+    pub fn kind(&self) -> WarningKind {
+        self.kind
+    }
+
+    /// The name of the attribute the warning concerns (ie, `id` or `x-muppet`).
+    pub fn attribute(&self) -> &str {
+        &self.attribute
+    }
+
+    /// The [Component] of the PKCS#11 URI the offending characters are in (which, for
+    /// a vendor-specific attribute, is wherever it was given).
+    pub fn component(&self) -> Component {
+        self.component
+    }
+
+    /// Byte offset of the offending characters within the PKCS#11 URI given to `parse`
+    /// (as opposed to the "tidied" uri of a `PK11URIError`).
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Description of the issue (without the `pkcs11 warning:` prefix).
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[cfg(feature = "warnings")]
+impl PK11URIWarning {
+    /// The error promoting the warning when [parsing strictly][super::ParseOptions::strict_advisories], the
+    /// `error_span` identifying the offending characters within the tidied `pk11_uri`.
+    pub(crate) fn into_strict_error(self, pk11_uri: &str) -> PK11URIError {
+        let start = tidy_len(&pk11_uri[..self.offset]);
+        let offending = &pk11_uri[self.offset..self.offset + self.len];
+        let end = start + tidy_len(offending);
+        PK11URIError {
+            pk11_uri: tidy(pk11_uri),
+            error_span: (start, end),
+            kind: ViolationKind::StrictAdvisory,
+            violation: format!("Strict advisory: {}", self.message),
+            help: self.help.unwrap_or_else(|| {
+                String::from("Follow the RFC7512 guideline, or use `parse` to merely be warned.")
+            }),
+            secondary_span: None,
+        }
+    }
+}
+
+impl fmt::Display for PK11URIWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pkcs11 warning: {}", self.message)
+    }
+}
+
+/// Accumulates the warnings of a single `parse`, locating each one
+/// within the `pk11_uri` that every parsed value is a slice of.
+pub(crate) struct Warnings<'a> {
+    pk11_uri: &'a str,
+    warnings: Vec<PK11URIWarning>,
+}
+
+impl<'a> Warnings<'a> {
+    pub(crate) fn new(pk11_uri: &'a str) -> Self {
+        Warnings {
+            pk11_uri,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Records a `kind` of warning about `attribute`, where `offending` is the
+    /// slice of the uri (typically some part of a value) at fault.
+    pub(crate) fn warn(
+        &mut self,
+        kind: WarningKind,
+        attribute: &str,
+        offending: &'a str,
+        message: String,
+    ) {
+        self.warn_with_help(kind, attribute, offending, message, None);
+    }
+
+    /// Records a warning as per [warn][Warnings::warn()], along with the `fix`
+    /// (replacing the `offending` slice) that a strict error suggests.
+    pub(crate) fn warn_fixable(
+        &mut self,
+        kind: WarningKind,
+        attribute: &str,
+        offending: &'a str,
+        message: String,
+        fix: Option<String>,
+    ) {
+        let help = fix.map(|fix| format!("Replace `{}` with `{fix}`.", tidy(offending)));
+        self.warn_with_help(kind, attribute, offending, message, help);
+    }
+
+    /// Records a warning as per [warn][Warnings::warn()], along with the `help`
+    /// that a strict error gives in place of its generic one.
+    pub(crate) fn warn_with_help(
+        &mut self,
+        kind: WarningKind,
+        attribute: &str,
+        offending: &'a str,
+        message: String,
+        help: Option<String>,
+    ) {
+        let offset = offending.as_ptr() as usize - self.pk11_uri.as_ptr() as usize;
+        let component = match self.pk11_uri.find('?') {
+            Some(query_index) if offset > query_index => Component::Query,
+            _ => Component::Path,
+        };
+        self.warnings.push(PK11URIWarning {
+            kind,
+            attribute: attribute.to_string(),
+            component,
+            offset,
+            len: offending.len(),
+            message,
+            help,
+        });
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<PK11URIWarning> {
+        self.warnings
+    }
+}
@@ -0,0 +1,66 @@
+use super::{standard_component, Component, PK11URIMapping};
+
+/// Deriving a mapping which differs from this one by a single standard attribute, as in:
+///
+/// ```
+/// let pk11_uri = "pkcs11:token=my-token;object=my-key;type=private";
+/// let mapping = pk11_uri_parser::parse(pk11_uri).expect("mapping should be valid");
+///
+/// let certificate = mapping.with_type("cert").with_id("%01");
+/// assert_eq!(certificate.to_string(), "pkcs11:token=my-token;object=my-key;type=cert;id=%01");
+/// assert_eq!(mapping.to_string(), "pkcs11:token=my-token;object=my-key;type=private");
+/// ```
+///
+/// The standard values being mere slices, the new mapping shares them with this one (and
+/// the given `value` needn't outlive them); only the vendor-specific attributes and the
+/// attribute order are copied. A newly given attribute follows the existing ones in the
+/// [attribute_order][PK11URIMapping::attribute_order()] (and likewise those of its component
+/// in any order recorded by [parse_preserving_order][super::parse_preserving_order]). As with
+/// [PK11URIBuilder][super::PK11URIBuilder], nothing is validated until
+/// [to_uri][PK11URIMapping::to_uri()].
+impl<'a> PK11URIMapping<'a> {
+    // pk11-pattr:
+    attr_with!(with_token for pk11-pattr "token");
+    attr_with!(with_manufacturer for pk11-pattr "manufacturer");
+    attr_with!(with_serial for pk11-pattr "serial");
+    attr_with!(with_model for pk11-pattr "model");
+    attr_with!(with_library_manufacturer for pk11-pattr "library-manufacturer");
+    attr_with!(with_library_version for pk11-pattr "library-version");
+    attr_with!(with_library_description for pk11-pattr "library-description");
+    attr_with!(with_object for pk11-pattr "object");
+    attr_with!(with_type for pk11-pattr "type");
+    attr_with!(with_id for pk11-pattr "id");
+    attr_with!(with_slot_description for pk11-pattr "slot-description");
+    attr_with!(with_slot_manufacturer for pk11-pattr "slot-manufacturer");
+    attr_with!(with_slot_id for pk11-pattr "slot-id");
+    // pk11-qattr:
+    attr_with!(with_pin_source for pk11-qattr "pin-source");
+    attr_with!(with_pin_value for pk11-qattr "pin-value");
+    attr_with!(with_module_name for pk11-qattr "module-name");
+    attr_with!(with_module_path for pk11-qattr "module-path");
+
+    /// Implementation of the `with_*` methods, `attr` being a standard attribute name.
+    fn with_attribute(&self, attr: &'static str, value: &'a str) -> PK11URIMapping<'a> {
+        let mut mapping = self.clone();
+        let field = mapping
+            .standard_attribute_mut(attr)
+            .expect("`with_*` attributes are standard");
+        if field.replace(value).is_none() {
+            mapping.attribute_order.push(attr);
+            if !mapping.source_order.is_empty() {
+                let component = standard_component(attr).expect("standard attribute");
+                // (the path's attributes all precede the query's)
+                let index = match component {
+                    Component::Path => mapping
+                        .source_order
+                        .iter()
+                        .take_while(|(_, recorded)| *recorded == Component::Path)
+                        .count(),
+                    Component::Query => mapping.source_order.len(),
+                };
+                mapping.source_order.insert(index, (attr, component));
+            }
+        }
+        mapping
+    }
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use pk11_uri_parser::{parse, PK11URIMapping};
+
+/// Deterministic pseudo-random input for `Unstructured` (xorshift64).
+fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.max(1);
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+/// Arbitrary mappings serialize to URIs which re-parse to the same mapping.
+#[test]
+fn arbitrary_mappings_round_trip() {
+    for seed in 0..512 {
+        let bytes = random_bytes(seed, 1024);
+        let mapping =
+            PK11URIMapping::arbitrary(&mut Unstructured::new(&bytes)).expect("arbitrary mapping");
+        let pk11_uri = mapping.to_string();
+        let reparsed = parse(&pk11_uri).expect("serialized mapping should be valid");
+        assert_eq!(reparsed.to_string(), pk11_uri);
+    }
+}
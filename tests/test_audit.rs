@@ -0,0 +1,87 @@
+use pk11_uri_parser::parse;
+
+/// The audit summary is stable-ordered and never leaks PIN material.
+#[test]
+fn audit_summary_redacts_pin_material() {
+    let pk11_uri = "pkcs11:type=private;object=my-key;v-attr=1
+            ?module-name=mypkcs11&pin-source=file:/etc/token_pin&a-attr=x&v-attr=2";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.audit_summary(),
+        "object=my-key type=private pin-source=file module-name=mypkcs11 vendor[a-attr=x] vendor[v-attr=1] vendor[v-attr=2]"
+    );
+
+    let pk11_uri = "pkcs11:object=my-key?pin-value=123456";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.audit_summary(), "object=my-key");
+
+    let pk11_uri = "pkcs11:?pin-source=|/usr/lib/pinomatic%20--secret";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.audit_summary(), "pin-source=command");
+
+    let pk11_uri = "pkcs11:?pin-source=FILE:/etc/token_pin";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.audit_summary(), "pin-source=file");
+
+    let pk11_uri = "pkcs11:?pin-source=https://pins.example.com/token?id=1";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.audit_summary(), "pin-source=https");
+
+    let pk11_uri = "pkcs11:?pin-source=etc/token_pin";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.audit_summary(), "pin-source=other");
+
+    let pk11_uri = "pkcs11:";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.audit_summary(), "");
+}
+
+/// PIN values and pin-source commands are sensitive, other attributes aren't.
+#[test]
+fn pin_material_is_sensitive() {
+    for (pk11_uri, sensitive) in [
+        ("pkcs11:object=my-key?pin-value=123456", true),
+        ("pkcs11:?pin-source=|/usr/lib/pinomatic%20--secret", true),
+        ("pkcs11:?pin-source=file:/etc/token_pin", false),
+        (
+            "pkcs11:?pin-source=https://pins.example.com/token?id=1",
+            false,
+        ),
+        (
+            "pkcs11:object=my-key;v-attr=pin-value?module-name=mypkcs11",
+            false,
+        ),
+        ("pkcs11:", false),
+    ] {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(
+            mapping.contains_sensitive_material(),
+            sensitive,
+            "{pk11_uri}"
+        );
+    }
+}
+
+/// The redacted display masks PIN material only, rendering everything else as usual.
+#[test]
+fn redacted_display_masks_pin_material() {
+    for (pk11_uri, redacted) in [
+        (
+            "pkcs11:type=private;object=my-key;v-attr=1?pin-value=123456&v-attr=2",
+            "pkcs11:object=my-key;type=private?pin-value=***&v-attr=1&v-attr=2",
+        ),
+        (
+            "pkcs11:object=my-key?pin-source=|/usr/lib/pinomatic%20--secret",
+            "pkcs11:object=my-key?pin-source=|***",
+        ),
+        (
+            "pkcs11:object=my-key?pin-source=file:/etc/token_pin&module-name=mypkcs11",
+            "pkcs11:object=my-key?pin-source=file:/etc/token_pin&module-name=mypkcs11",
+        ),
+        ("pkcs11:", "pkcs11:"),
+    ] {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.redacted().to_string(), redacted, "{pk11_uri}");
+        parse(redacted).expect("redacted uri should be valid");
+    }
+}
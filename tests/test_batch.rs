@@ -0,0 +1,115 @@
+use pk11_uri_parser::{
+    parse_many, parse_many_with_options, validate_batch, ParseManyOptions, ParseOptions,
+};
+
+/// Every URI in a batch is validated, with failures correlated to their source.
+#[test]
+#[cfg(feature = "validation")]
+fn batch_reports_successes_and_failures() {
+    let uris = [
+        ("a.conf:1", "pkcs11:object=my-key;type=private"),
+        ("a.conf:2", "pkcs11:object=my key"),
+        ("b.conf:1", "pkcs11:?pin-source=file:/etc/token_pin"),
+        ("b.conf:7", "pkcs11:type=Private Key"),
+        ("c.conf:3", "pkcs11:"),
+    ];
+    let report = validate_batch(uris);
+    assert_eq!(report.successes(), 3);
+    assert_eq!(report.total(), 5);
+    assert!(!report.is_ok());
+
+    let failed: Vec<_> = report
+        .failures()
+        .iter()
+        .map(|(source_id, _)| *source_id)
+        .collect();
+    assert_eq!(failed, ["a.conf:2", "b.conf:7"]);
+
+    let summary = report.to_string();
+    assert!(summary.starts_with("a.conf:2:\npkcs11:object=my key\n"));
+    assert!(summary.ends_with("3 of 5 PKCS#11 URIs are valid."));
+}
+
+/// The warnings of every valid URI in a batch are tallied by kind.
+#[test]
+#[cfg(feature = "warnings")]
+fn batch_tallies_warning_kinds() {
+    use pk11_uri_parser::WarningKind;
+
+    let uris = [
+        ("a.conf:1", "pkcs11:object=my-key;id=abc"),
+        ("a.conf:2", "pkcs11:x-muppet=cookie<monster"),
+        ("a.conf:3", "pkcs11:object=my-key;id=def;x-slot=1"),
+        ("a.conf:4", "pkcs11:object=my-key;type=cert"),
+    ];
+    let report = validate_batch(uris);
+    assert!(report.is_ok());
+    assert_eq!(
+        report.warnings(),
+        [
+            (WarningKind::IdNotPercentEncoded, 2),
+            (WarningKind::DeprecatedVendorPrefix, 2),
+            (WarningKind::UnencodedCharacter, 1),
+        ]
+    );
+    let total: usize = report.warnings().iter().map(|(_, count)| count).sum();
+    assert_eq!(total, 5);
+}
+
+/// An empty batch is trivially valid.
+#[test]
+fn empty_batch_is_ok() {
+    let report = validate_batch([]);
+    assert!(report.is_ok());
+    assert_eq!(report.total(), 0);
+}
+
+/// Each line is parsed on its own, failures not stopping the remaining lines.
+#[test]
+#[cfg(feature = "validation")]
+fn lines_are_parsed_individually() {
+    let input = "pkcs11:object=my-key\r\npkcs11:object=my key\n\n# pkcs11:object=old-key\npkcs11:";
+    let results = parse_many(input);
+    assert_eq!(results.len(), 5);
+    assert_eq!(
+        results[0]
+            .as_ref()
+            .ok()
+            .and_then(|mapping| mapping.object()),
+        Some("my-key")
+    );
+    let err = results[1].as_ref().expect_err("space in value");
+    assert_eq!(err.error_span(), (7, 20));
+    // (blank and comment lines aren't PKCS#11 URIs)
+    assert!(results[2].is_err() && results[3].is_err());
+    assert!(results[4].is_ok());
+
+    let options = ParseManyOptions::default().skip_blank_and_comment_lines(true);
+    let results = parse_many_with_options(input, &options);
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok() && results[1].is_err() && results[2].is_ok());
+}
+
+/// Blank and `#` comment lines are skipped when so configured.
+#[test]
+fn blank_and_comment_lines_are_skipped() {
+    let input = "  # indented comment\n \t \npkcs11:token=my-token;\n\npkcs11:object=my-key";
+    let options = ParseManyOptions::default()
+        .skip_blank_and_comment_lines(true)
+        .parse_options(ParseOptions::default().tolerate_trailing_delimiter(true));
+    let results = parse_many_with_options(input, &options);
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results[0].as_ref().ok().and_then(|mapping| mapping.token()),
+        Some("my-token")
+    );
+    assert_eq!(
+        results[1]
+            .as_ref()
+            .ok()
+            .and_then(|mapping| mapping.object()),
+        Some("my-key")
+    );
+
+    assert!(parse_many("").is_empty());
+}
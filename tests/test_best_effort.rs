@@ -0,0 +1,110 @@
+use pk11_uri_parser::{parse_all, parse_best_effort};
+
+/// A valid uri results in its complete mapping without errors.
+#[test]
+fn valid_uris_have_no_errors() {
+    let (mapping, errors) = parse_best_effort("pkcs11:object=my-key;type=cert?pin-value=1234");
+    let mapping = mapping.expect("mapping should be valid");
+    assert_eq!(
+        mapping.to_string(),
+        "pkcs11:object=my-key;type=cert?pin-value=1234"
+    );
+    assert!(errors.is_empty());
+}
+
+/// Each offending attribute is skipped and reported with its own span.
+#[test]
+#[cfg(feature = "validation")]
+fn offending_attributes_are_skipped_and_reported() {
+    let pk11_uri = "pkcs11:slot-id=one;object=my-key;object=other;library-version=1.0?module-name=p11-kit&pin-source=a b";
+    let (mapping, errors) = parse_best_effort(pk11_uri);
+    let mapping = mapping.expect("a partial mapping");
+    assert_eq!(
+        mapping.to_string(),
+        "pkcs11:library-version=1.0;object=my-key?module-name=p11-kit"
+    );
+
+    let error_spans: Vec<_> = errors.iter().map(|err| err.error_span()).collect();
+    assert_eq!(error_spans, [(7, 18), (33, 45), (86, 100)]);
+    // the duplicate relates the (kept) first occurrence:
+    assert_eq!(errors[1].related_span(), Some((19, 32)));
+}
+
+/// The second of conflicting `pin-source` and `pin-value` attributes is skipped and reported.
+#[test]
+#[cfg(feature = "validation")]
+fn conflicting_pin_attributes_are_skipped_and_reported() {
+    let (mapping, errors) = parse_best_effort("pkcs11:?pin-value=1234&pin-source=file:/x");
+    let mapping = mapping.expect("a partial mapping");
+    assert_eq!(mapping.to_string(), "pkcs11:?pin-value=1234");
+    let error_spans: Vec<_> = errors.iter().map(|err| err.error_span()).collect();
+    assert_eq!(error_spans, [(23, 41)]);
+}
+
+/// Components which can't be parsed at all are reported without validation too.
+#[test]
+fn unparseable_attributes_are_skipped_and_reported() {
+    let pk11_uri = "pkcs11:token;object=my-key?module-name";
+    let (mapping, errors) = parse_best_effort(pk11_uri);
+    let mapping = mapping.expect("a partial mapping");
+    assert_eq!(mapping.to_string(), "pkcs11:object=my-key");
+    let error_spans: Vec<_> = errors.iter().map(|err| err.error_span()).collect();
+    assert_eq!(error_spans, [(7, 12), (27, 38)]);
+}
+
+/// A '?' within a path value is reported, the would-be query being skipped.
+#[test]
+#[cfg(feature = "validation")]
+fn stray_question_mark_skips_the_query() {
+    let (mapping, errors) = parse_best_effort("pkcs11:type=x;object=a?b&module-name=mypkcs11");
+    let mapping = mapping.expect("partial mapping");
+    assert_eq!(mapping.to_string(), "pkcs11:object=a");
+    let error_spans: Vec<_> = errors.iter().map(|err| err.error_span()).collect();
+    assert_eq!(error_spans, [(7, 13), (22, 23)]);
+}
+
+/// A uri lacking the `pkcs11:` scheme has no mapping at all.
+#[test]
+#[cfg(feature = "validation")]
+fn non_pkcs11_uris_have_no_mapping() {
+    let (mapping, errors) = parse_best_effort("pkcs11object=my-key");
+    assert!(mapping.is_none());
+    assert_eq!(errors.len(), 1);
+}
+
+/// `parse_all` results in the mapping only when there are no violations at all.
+#[test]
+fn parse_all_results_in_every_violation() {
+    let mapping = parse_all("pkcs11:object=my-key;type=cert").expect("mapping should be valid");
+    assert_eq!(mapping.to_string(), "pkcs11:object=my-key;type=cert");
+
+    let errors = parse_all("pkcs11:token;object=my-key?module-name")
+        .expect_err("unparseable attributes should not be valid");
+    let error_spans: Vec<_> = errors.iter().map(|err| err.error_span()).collect();
+    assert_eq!(error_spans, [(7, 12), (27, 38)]);
+}
+
+/// An error sink receives every violation, the mapping holding the attributes
+/// which could be parsed.
+#[test]
+fn error_sink_receives_every_violation() {
+    use pk11_uri_parser::{parse_with_options, ParseOptions};
+    use std::cell::RefCell;
+
+    let errors = RefCell::new(vec![]);
+    let error_sink = |err| errors.borrow_mut().push(err);
+    let options = ParseOptions::default().error_sink(&error_sink);
+    let mapping = parse_with_options("pkcs11:object=my-key;type=cert", &options)
+        .expect("mapping should be valid");
+    assert_eq!(mapping.to_string(), "pkcs11:object=my-key;type=cert");
+    assert!(errors.borrow().is_empty());
+
+    let mapping = parse_with_options("pkcs11:token;object=my-key?module-name", &options)
+        .expect("a partial mapping");
+    assert_eq!(mapping.to_string(), "pkcs11:object=my-key");
+    let error_spans: Vec<_> = errors.borrow().iter().map(|err| err.error_span()).collect();
+    assert_eq!(error_spans, [(7, 12), (27, 38)]);
+
+    #[cfg(feature = "validation")]
+    parse_with_options("pkcs11object=my-key", &options).expect_err("not a PKCS#11 URI");
+}
@@ -0,0 +1,129 @@
+use pk11_uri_parser::{parse, ObjectType, PK11URIBuilder, ViolationKind};
+
+/// Path setters only affect the path, query setters only the query.
+#[test]
+fn setters_place_attributes_in_their_component() {
+    let pk11_uri = PK11URIBuilder::new()
+        .pin_value("123456")
+        .object("my-key")
+        .module_name("mypkcs11")
+        .type_(ObjectType::Private)
+        .build()
+        .expect("valid PKCS#11 URI");
+    assert_eq!(
+        pk11_uri,
+        "pkcs11:object=my-key;type=private?pin-value=123456&module-name=mypkcs11"
+    );
+
+    let pk11_uri = PK11URIBuilder::new()
+        .pin_source("file:/etc/token_pin")
+        .build()
+        .expect("valid PKCS#11 URI");
+    assert_eq!(pk11_uri, "pkcs11:?pin-source=file:/etc/token_pin");
+
+    assert_eq!(
+        PK11URIBuilder::new().build().expect("valid PKCS#11 URI"),
+        "pkcs11:"
+    );
+}
+
+/// Values are percent-encoded as their component requires.
+#[test]
+fn values_are_percent_encoded() {
+    let pk11_uri = PK11URIBuilder::new()
+        .token("The Software PKCS#11 Softtoken")
+        .object("a/b&c;d")
+        .id(&[0x69, 0x95, 0x3E])
+        .slot_id(42)
+        .module_path("/usr/lib/my pkcs11.so")
+        .build()
+        .expect("valid PKCS#11 URI");
+    assert_eq!(
+        pk11_uri,
+        "pkcs11:token=The%20Software%20PKCS%2311%20Softtoken;object=a%2Fb%26c%3Bd;\
+        id=%69%95%3E;slot-id=42?module-path=/usr/lib/my%20pkcs11.so"
+    );
+
+    let mapping = parse(&pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("a%2Fb%26c%3Bd"));
+    assert_eq!(mapping.id_bytes(), Ok(Some(vec![0x69, 0x95, 0x3E])));
+}
+
+/// Vendor-specific values are placed according to how many there are.
+#[test]
+fn vendor_attributes_are_placed_by_value_count() {
+    let pk11_uri = PK11URIBuilder::new()
+        .vendor("v-attr", "val1")
+        .vendor("w-attr", "val2")
+        .vendor("w-attr", "val 3")
+        .build()
+        .expect("valid PKCS#11 URI");
+    assert_eq!(pk11_uri, "pkcs11:v-attr=val1?w-attr=val2&w-attr=val%203");
+
+    let mapping = parse(&pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.vendor("w-attr"), Some(&vec!["val2", "val%203"]));
+}
+
+/// Vendor-specific attributes may not use standard attribute names.
+#[test]
+fn vendor_attributes_with_standard_names_are_not_valid() {
+    let err = PK11URIBuilder::new()
+        .object("my-key")
+        .vendor("type", "cert")
+        .build()
+        .expect_err("vendor attribute named type should not be valid");
+    assert_eq!(
+        err.to_string(),
+        "pkcs11:object=my-key;type=cert\n                     ^^^^^^^^^ Naming collision with standard path component.\n\n\
+        help: `type` is a standard attribute: set it using the builder's `type_` method rather than `vendor`."
+    );
+
+    let err = PK11URIBuilder::new()
+        .vendor("pin-value", "123456")
+        .build()
+        .expect_err("vendor attribute named pin-value should not be valid");
+    assert!(err
+        .to_string()
+        .contains("Naming collision with standard query component."));
+    assert!(err.to_string().contains("`pin_value` method"));
+}
+
+/// Values RFC7512 doesn't allow are reported just as `parse` would.
+#[test]
+#[cfg(feature = "validation")]
+fn invalid_values_are_not_valid() {
+    let builder = PK11URIBuilder::new().library_version("one");
+    let err = builder
+        .build()
+        .expect_err("library-version of one should not be valid");
+    let parse_err = parse("pkcs11:library-version=one")
+        .expect_err("library-version of one should not be valid");
+    assert_eq!(err.to_string(), parse_err.to_string());
+
+    PK11URIBuilder::new()
+        .vendor("my attr", "value")
+        .build()
+        .expect_err("vendor attribute name with a space should not be valid");
+}
+
+/// Both `pin-source` and `pin-value` may not be set.
+#[test]
+fn pin_source_and_pin_value_are_not_valid_together() {
+    let err = PK11URIBuilder::new()
+        .object("my-key")
+        .pin_value("123456")
+        .pin_source("file:/etc/token_pin")
+        .build()
+        .expect_err("both pin-source and pin-value should not be valid");
+    assert_eq!(err.kind(), ViolationKind::ConflictingAttributes);
+    assert_eq!(err.error_span(), (52, 68));
+    assert_eq!(err.related_span(), Some((21, 51)));
+    assert!(err
+        .to_string()
+        .ends_with("help: Set only one of `pin_source` or `pin_value`."));
+
+    PK11URIBuilder::new()
+        .pin_value("123456")
+        .build()
+        .expect("valid PKCS#11 URI");
+}
@@ -0,0 +1,31 @@
+use pk11_uri_parser::{standard_component, Component};
+
+/// Every standard attribute name has a fixed component; anything else doesn't.
+#[test]
+fn standard_names_have_a_component() {
+    for name in [
+        "token",
+        "manufacturer",
+        "serial",
+        "model",
+        "library-manufacturer",
+        "library-version",
+        "library-description",
+        "object",
+        "type",
+        "id",
+        "slot-description",
+        "slot-manufacturer",
+        "slot-id",
+    ] {
+        assert_eq!(standard_component(name), Some(Component::Path), "{name}");
+    }
+
+    for name in ["pin-source", "pin-value", "module-name", "module-path"] {
+        assert_eq!(standard_component(name), Some(Component::Query), "{name}");
+    }
+
+    for name in ["", "vendor-attribute", "x-muppet", "Token", "pin_value"] {
+        assert_eq!(standard_component(name), None, "{name}");
+    }
+}
@@ -0,0 +1,174 @@
+use pk11_uri_parser::parse;
+use std::borrow::Cow;
+
+/// Only `%XX` triplets of `id` are decoded; literal characters are taken as-is.
+#[test]
+fn id_bytes_decodes_mixed_literal_and_encoded_values() {
+    let pk11_uri = "pkcs11:id=%69%95%3E%5C%F4%BD%EC%91";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.id_bytes(),
+        Ok(Some(vec![0x69, 0x95, 0x3E, 0x5C, 0xF4, 0xBD, 0xEC, 0x91]))
+    );
+
+    let pk11_uri = "pkcs11:id=ab%43cd";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.id_bytes(),
+        Ok(Some(vec![0x61, 0x62, 0x43, 0x63, 0x64]))
+    );
+
+    let pk11_uri = "pkcs11:id=%01%02%03%Ba%dd%Ca%fe%04%05%06";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.id_bytes(),
+        Ok(Some(vec![
+            0x01, 0x02, 0x03, 0xBA, 0xDD, 0xCA, 0xFE, 0x04, 0x05, 0x06
+        ]))
+    );
+
+    let pk11_uri = "pkcs11:id=abc";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.id_bytes(), Ok(Some(b"abc".to_vec())));
+
+    let pk11_uri = "pkcs11:id=";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.id_bytes(), Ok(Some(vec![])));
+
+    let pk11_uri = "pkcs11:object=no-id";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.id_bytes(), Ok(None));
+}
+
+/// A `%` not followed by two hexadecimal digits can't be decoded (validation refuses
+/// such a value outright).
+#[test]
+#[cfg(not(feature = "validation"))]
+fn id_bytes_rejects_malformed_percent_encoding() {
+    let pk11_uri = "pkcs11:id=%01%G0";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let err = mapping.id_bytes().expect_err("`%G0` should not decode");
+    assert_eq!(err.offset(), 3);
+
+    let pk11_uri = "pkcs11:id=ab%4";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let err = mapping
+        .id_bytes()
+        .expect_err("truncated `%4` should not decode");
+    assert_eq!(err.offset(), 2);
+
+    let pk11_uri = "pkcs11:id=%+1";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    mapping.id_bytes().expect_err("`%+1` should not decode");
+}
+
+/// The `object` label is decoded for display, never failing.
+#[test]
+fn object_lossy_decodes_for_display() {
+    let pk11_uri = "pkcs11:object=my-certificate";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert!(matches!(
+        mapping.object_lossy(),
+        Some(std::borrow::Cow::Borrowed("my-certificate"))
+    ));
+
+    let pk11_uri = "pkcs11:object=A%20name%20with%20a%20substring%20%25%3B";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.object_lossy().as_deref(),
+        Some("A name with a substring %;")
+    );
+
+    let pk11_uri = "pkcs11:object=%FFlabel";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.object_lossy().as_deref(), Some("\u{FFFD}label"));
+
+    // (validation refuses malformed percent-encoding outright)
+    #[cfg(not(feature = "validation"))]
+    {
+        let pk11_uri = "pkcs11:object=%FFlabel%G0%4";
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(
+            mapping.object_lossy().as_deref(),
+            Some("\u{FFFD}label%G0%4")
+        );
+    }
+
+    let pk11_uri = "pkcs11:token=my-token";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.object_lossy(), None);
+}
+
+/// An encoded ';' doesn't split the path, is stored as parsed, and decodes to ';'.
+#[test]
+fn encoded_semicolons_survive_parsing_and_decode() {
+    let pk11_uri = "pkcs11:object=my%3Bobject;type=data;id=%3b%3B?module-name=p11%3Bkit";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("my%3Bobject"));
+    assert_eq!(mapping.r#type(), Some("data"));
+    assert_eq!(mapping.module_name(), Some("p11%3Bkit"));
+    assert_eq!(mapping.object_lossy().as_deref(), Some("my;object"));
+    assert_eq!(mapping.id_bytes(), Ok(Some(b";;".to_vec())));
+
+    // serializing (and normalizing) retains the encoding:
+    assert_eq!(
+        mapping.to_string(),
+        "pkcs11:object=my%3Bobject;type=data;id=%3b%3B?module-name=p11%3Bkit"
+    );
+    assert_eq!(
+        mapping.normalize(),
+        "pkcs11:object=my%3Bobject;type=data;id=%3B%3B?module-name=p11%3Bkit"
+    );
+}
+
+/// Text attributes are decoded, borrowing whenever there's nothing to decode.
+#[test]
+fn decoded_accessors_decode_text_attributes() {
+    let pk11_uri = "pkcs11:token=The%20Software%20PKCS%2311%20Softtoken;object=my-key\
+        ?module-path=/usr/lib/caf%C3%A9.so";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.token_decoded(),
+        Ok(Some(Cow::Owned(String::from(
+            "The Software PKCS#11 Softtoken"
+        ))))
+    );
+    assert!(matches!(
+        mapping.object_decoded(),
+        Ok(Some(Cow::Borrowed("my-key")))
+    ));
+    assert_eq!(
+        mapping
+            .module_path_decoded()
+            .expect("valid encoding")
+            .as_deref(),
+        Some("/usr/lib/café.so")
+    );
+    assert_eq!(mapping.serial_decoded(), Ok(None));
+}
+
+/// Malformed encodings and decoded octets which aren't UTF-8 can't be decoded to text.
+#[test]
+fn decoded_accessors_reject_undecodable_text() {
+    // (validation refuses malformed percent-encoding outright)
+    #[cfg(not(feature = "validation"))]
+    {
+        let pk11_uri = "pkcs11:token=100%";
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        let err = mapping
+            .token_decoded()
+            .expect_err("`%` alone should not decode");
+        assert_eq!(err.offset(), 3);
+    }
+
+    let pk11_uri = "pkcs11:object=caf%C3%A9%FF";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let err = mapping
+        .object_decoded()
+        .expect_err("`%FF` is not valid UTF-8");
+    assert_eq!(err.offset(), 9);
+    assert!(
+        err.to_string().starts_with("invalid UTF-8 at offset 9"),
+        "{err}"
+    );
+}
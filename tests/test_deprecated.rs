@@ -0,0 +1,19 @@
+use pk11_uri_parser::parse;
+
+/// The "x-" vendor-specific prefix is identified as deprecated.
+#[test]
+fn x_prefixed_vendor_attributes_are_deprecated() {
+    let pk11_uri = "pkcs11:x-muppet=cookie-monster;object=my-key?x-puppet=kermit&x-muppet=elmo";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert!(mapping.uses_deprecated());
+    assert_eq!(mapping.deprecated_items(), ["x-muppet", "x-puppet"]);
+}
+
+/// Standard and other vendor-specific attributes aren't deprecated.
+#[test]
+fn current_constructs_are_not_deprecated() {
+    let pk11_uri = "pkcs11:object=my-key;muppet-x=cookie-monster?module-name=x-module";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert!(!mapping.uses_deprecated());
+    assert!(mapping.deprecated_items().is_empty());
+}
@@ -0,0 +1,69 @@
+use pk11_uri_parser::{parse, AttrDiff};
+
+/// Equal mappings have no differences, regardless of attribute order.
+#[test]
+fn equal_mappings_have_no_differences() {
+    let mapping = parse("pkcs11:type=cert;object=my-key;v-attr=a?v-attr=b&module-name=p11-kit")
+        .expect("mapping should be valid");
+    let other = parse("pkcs11:object=my-key;type=cert?module-name=p11-kit&v-attr=a&v-attr=b")
+        .expect("mapping should be valid");
+    assert_eq!(mapping, other);
+    assert!(mapping.diff(&other).is_empty());
+}
+
+/// Standard and vendor-specific attributes are reported as added, removed, or changed.
+#[test]
+fn differences_are_reported_in_order() {
+    let mapping =
+        parse("pkcs11:object=my-key;v-attr=a;v-gone=x?pin-source=file:/etc/token_pin&v-attr=b")
+            .expect("mapping should be valid");
+    let other =
+        parse("pkcs11:object=my%2Dkey;slot-id=3;v-new=y?module-name=p11-kit&v-attr=b&v-attr=a")
+            .expect("mapping should be valid");
+    assert_eq!(
+        mapping.diff(&other),
+        [
+            AttrDiff::Changed {
+                name: "object",
+                old: vec!["my-key"],
+                new: vec!["my%2Dkey"]
+            },
+            AttrDiff::Added {
+                name: "slot-id",
+                values: vec!["3"]
+            },
+            AttrDiff::Removed {
+                name: "pin-source",
+                values: vec!["file:/etc/token_pin"]
+            },
+            AttrDiff::Added {
+                name: "module-name",
+                values: vec!["p11-kit"]
+            },
+            AttrDiff::Changed {
+                name: "v-attr",
+                old: vec!["a", "b"],
+                new: vec!["b", "a"]
+            },
+            AttrDiff::Removed {
+                name: "v-gone",
+                values: vec!["x"]
+            },
+            AttrDiff::Added {
+                name: "v-new",
+                values: vec!["y"]
+            },
+        ]
+    );
+
+    // the reverse diff swaps additions and removals:
+    let reverse = other.diff(&mapping);
+    assert_eq!(reverse.len(), 7);
+    assert_eq!(
+        reverse[1],
+        AttrDiff::Removed {
+            name: "slot-id",
+            values: vec!["3"]
+        }
+    );
+}
@@ -0,0 +1,77 @@
+use pk11_uri_parser::parse;
+
+fn fix(attribute: &str, original: &str, fixed: &str) -> (String, String, String) {
+    (
+        attribute.to_string(),
+        original.to_string(),
+        fixed.to_string(),
+    )
+}
+
+/// Values needing no encoding have no fixes.
+#[test]
+fn well_encoded_values_have_no_fixes() {
+    let pk11_uri = "pkcs11:token=My%20Token;object=my-key;id=%0a%FF;library-version=1.2;v-attr=a&b?pin-source=file:/etc/token_pin|x&module-path=/usr/lib/p11.so";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert!(mapping.encoding_fixes().is_empty());
+}
+
+/// Each component's fix encodes what that component SHOULD have encoded.
+#[test]
+fn fixes_encode_per_component() {
+    let pk11_uri = "pkcs11:object=a<b>%25;id=%0aB;v-path=x^y?pin-value=p|n\"s&v-query=1&v-query=2`";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.encoding_fixes(),
+        [
+            fix("object", "a<b>%25", "a%3Cb%3E%25"),
+            fix("id", "%0aB", "%0a%42"),
+            fix("pin-value", "p|n\"s", "p|n%22s"),
+            fix("v-path", "x^y", "x%5Ey"),
+            fix("v-query", "2`", "2%60"),
+        ]
+    );
+}
+
+/// Applying every fix results in a URI having nothing left to fix.
+#[test]
+fn applied_fixes_leave_nothing_to_fix() {
+    let pk11_uri = "pkcs11:token={token};serial=a^b?module-name=p11<kit>";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let fixed = mapping.encoding_fixes().into_iter().fold(
+        pk11_uri.to_string(),
+        |pk11_uri, (attribute, original, fixed)| {
+            pk11_uri.replace(
+                &format!("{attribute}={original}"),
+                &format!("{attribute}={fixed}"),
+            )
+        },
+    );
+    assert_eq!(
+        fixed,
+        "pkcs11:token=%7Btoken%7D;serial=a%5Eb?module-name=p11%3Ckit%3E"
+    );
+    let mapping = parse(&fixed).expect("mapping should be valid");
+    assert!(mapping.encoding_fixes().is_empty());
+}
+
+/// Encoded values parse as-is (without violation or fix) and decode back to the original.
+#[test]
+fn encoded_values_need_no_fixes() {
+    use pk11_uri_parser::{percent_encode_value, Component};
+
+    let value = "my key#1/2;a&b=c?d|e%f<g>ü";
+    let path_value = percent_encode_value(value, Component::Path);
+    let query_value = percent_encode_value(value, Component::Query);
+    assert_eq!(
+        path_value,
+        "my%20key%231%2F2%3Ba%26b=c%3Fd%7Ce%25f%3Cg%3E%C3%BC"
+    );
+    assert_eq!(query_value, "my%20key%231/2%3Ba%26b=c?d|e%25f%3Cg%3E%C3%BC");
+
+    let pk11_uri = format!("pkcs11:object={path_value}?pin-value={query_value}");
+    let mapping = parse(&pk11_uri).expect("mapping should be valid");
+    assert!(mapping.encoding_fixes().is_empty());
+    assert_eq!(mapping.object_decoded().unwrap().as_deref(), Some(value));
+    assert_eq!(mapping.pin_value_decoded().unwrap().as_deref(), Some(value));
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "ffi")]
+
+use pk11_uri_parser::ffi::*;
+use std::ffi::{c_char, CStr, CString};
+
+/// The NUL-terminated string at `value`, if not null.
+fn string(value: *const c_char) -> Option<String> {
+    (!value.is_null()).then(|| {
+        unsafe { CStr::from_ptr(value) }
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+/// Parsed values are retrieved through the handle until it's freed.
+#[test]
+fn values_are_retrieved_through_handle() {
+    let pk11_uri = CString::new(
+        "pkcs11:object=my-key;type=private;v-attr=a?pin-source=file:/etc/token_pin&v-attr=b",
+    )
+    .expect("no interior NUL");
+    unsafe {
+        let handle = pk11_uri_parse(pk11_uri.as_ptr());
+        assert!(!handle.is_null());
+        assert_eq!(string(pk11_uri_last_error(handle)), None);
+        assert_eq!(string(pk11_uri_object(handle)).as_deref(), Some("my-key"));
+        assert_eq!(string(pk11_uri_type(handle)).as_deref(), Some("private"));
+        assert_eq!(
+            string(pk11_uri_pin_source(handle)).as_deref(),
+            Some("file:/etc/token_pin")
+        );
+        assert_eq!(string(pk11_uri_token(handle)), None);
+
+        let v_attr = CString::new("v-attr").expect("no interior NUL");
+        assert_eq!(
+            string(pk11_uri_attribute(handle, v_attr.as_ptr())).as_deref(),
+            Some("a")
+        );
+        let object = CString::new("object").expect("no interior NUL");
+        assert_eq!(
+            string(pk11_uri_attribute(handle, object.as_ptr())).as_deref(),
+            Some("my-key")
+        );
+        assert_eq!(string(pk11_uri_attribute(handle, std::ptr::null())), None);
+        pk11_uri_free(handle);
+    }
+}
+
+/// A PKCS#11 URI which isn't valid results in a handle describing the violation, while
+/// null pointers are tolerated throughout.
+#[test]
+fn errors_are_retrieved_through_handle() {
+    let pk11_uri = CString::new("pkcs11:object").expect("no interior NUL");
+    unsafe {
+        let handle = pk11_uri_parse(pk11_uri.as_ptr());
+        let error = string(pk11_uri_last_error(handle)).expect("bare attribute name");
+        assert!(error.contains("Malformed component: `object` is missing its '=' and value."));
+        assert_eq!(string(pk11_uri_object(handle)), None);
+        pk11_uri_free(handle);
+
+        assert!(pk11_uri_parse(std::ptr::null()).is_null());
+        assert_eq!(string(pk11_uri_last_error(std::ptr::null())), None);
+        assert_eq!(string(pk11_uri_object(std::ptr::null())), None);
+        pk11_uri_free(std::ptr::null_mut());
+    }
+}
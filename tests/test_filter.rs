@@ -0,0 +1,60 @@
+use pk11_uri_parser::{parse, CandidateAttributes};
+
+const CKO_CERTIFICATE: u64 = 1;
+const CKO_PRIVATE_KEY: u64 = 3;
+
+/// Each specified attribute must match the candidate's, decoded.
+#[test]
+fn filters_match_decoded_attributes() {
+    let pk11_uri = "pkcs11:token=my-token;object=my%20key;type=private;id=%69%95ab";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let filter = mapping.as_filter();
+
+    let candidate = CandidateAttributes {
+        class: CKO_PRIVATE_KEY,
+        id: &[0x69, 0x95, b'a', b'b'],
+        label: b"my key",
+    };
+    assert!(filter.matches(&candidate));
+    assert!(!filter.matches(&CandidateAttributes {
+        class: CKO_CERTIFICATE,
+        ..candidate
+    }));
+    assert!(!filter.matches(&CandidateAttributes {
+        id: &[0x69, 0x95],
+        ..candidate
+    }));
+    assert!(!filter.matches(&CandidateAttributes {
+        label: b"my%20key",
+        ..candidate
+    }));
+}
+
+/// Attributes the mapping didn't specify match any candidate.
+#[test]
+fn unspecified_attributes_match_anything() {
+    let mapping = parse("pkcs11:type=cert").expect("mapping should be valid");
+    let filter = mapping.as_filter();
+    assert!(filter.matches(&CandidateAttributes {
+        class: CKO_CERTIFICATE,
+        id: b"any",
+        label: b"thing",
+    }));
+
+    let mapping = parse("pkcs11:token=my-token").expect("mapping should be valid");
+    assert!(mapping.as_filter().matches(&CandidateAttributes::default()));
+}
+
+/// A `type` RFC7512 doesn't define matches nothing.
+#[test]
+#[cfg(not(feature = "validation"))]
+fn undefined_types_match_nothing() {
+    let mapping = parse("pkcs11:type=muppet").expect("mapping should be parsed");
+    let filter = mapping.as_filter();
+    assert!(
+        (0..=u64::from(u8::MAX)).all(|class| !filter.matches(&CandidateAttributes {
+            class,
+            ..CandidateAttributes::default()
+        }))
+    );
+}
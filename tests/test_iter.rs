@@ -0,0 +1,98 @@
+use pk11_uri_parser::parse;
+
+/// Every parsed standard attribute is visited in RFC7512 order, path before query.
+#[test]
+fn iter_visits_standard_attributes_in_rfc7512_order() {
+    let pk11_uri = "pkcs11:slot-id=3;token=my-token;serial=;v-attr=a\
+        ?module-path=/usr/lib/p11.so&pin-value=1234&v-attr=b";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let attributes: Vec<_> = mapping.iter().collect();
+    assert_eq!(
+        attributes,
+        [
+            ("token", "my-token"),
+            ("serial", ""),
+            ("slot-id", "3"),
+            ("pin-value", "1234"),
+            ("module-path", "/usr/lib/p11.so"),
+        ]
+    );
+
+    let mapping = parse("pkcs11:").expect("mapping should be valid");
+    assert_eq!(mapping.iter().count(), 0);
+    assert_eq!(mapping.vendor_iter().count(), 0);
+}
+
+/// Vendor-specific attributes are visited sorted by name.
+#[test]
+fn vendor_iter_visits_vendor_attributes_by_name() {
+    let pk11_uri = "pkcs11:z-attr=1;object=my-key;a-attr=2?m-attr=3&m-attr=4";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let vendor: Vec<_> = mapping.vendor_iter().collect();
+    assert_eq!(
+        vendor,
+        [
+            ("a-attr", &["2"][..]),
+            ("m-attr", &["3", "4"][..]),
+            ("z-attr", &["1"][..]),
+        ]
+    );
+}
+
+/// Vendor-specific attribute names are discoverable, sorted by name.
+#[test]
+fn vendor_names_lists_vendor_attributes() {
+    let pk11_uri = "pkcs11:z-attr=1;object=my-key;a-attr=2?m-attr=3&m-attr=4";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let vendor_names: Vec<_> = mapping.vendor_names().collect();
+    assert_eq!(vendor_names, ["a-attr", "m-attr", "z-attr"]);
+    for name in vendor_names {
+        assert!(mapping.vendor(name).is_some());
+    }
+
+    let mapping = parse("pkcs11:object=my-key").expect("mapping should be valid");
+    assert_eq!(mapping.vendor_names().count(), 0);
+}
+
+/// Every attribute's span is located within the tidied uri, in the order they appear.
+#[test]
+fn attribute_spans_locate_name_and_value() {
+    let pk11_uri = "pkcs11:slot-id=3;serial=;
+\ttoken=my-token;v-attr=a
+\t?module-path=/usr/lib/p11.so&v-attr=b";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let spans = mapping.attribute_spans(pk11_uri);
+    assert_eq!(
+        spans,
+        [
+            ("slot-id", 7..16),
+            ("serial", 17..24),
+            ("token", 25..39),
+            ("v-attr", 40..48),
+            ("module-path", 49..76),
+            ("v-attr", 77..85),
+        ]
+    );
+    let tidied = pk11_uri.replace(['\n', '\t'], "");
+    assert_eq!(&tidied[spans[4].1.clone()], "module-path=/usr/lib/p11.so");
+
+    // values of another uri have no span:
+    let copy = pk11_uri.to_string();
+    assert!(mapping.attribute_spans(&copy).is_empty());
+}
+
+/// Every standard attribute and vendor-specific value counts towards the length.
+#[test]
+fn len_counts_attributes_and_vendor_values() {
+    let pk11_uri = "pkcs11:slot-id=3;serial=;v-attr=a?pin-value=1234&v-attr=b&v-other=c";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.len(), 6);
+    assert!(!mapping.is_empty());
+
+    let mapping = parse("pkcs11:").expect("mapping should be valid");
+    assert_eq!(mapping.len(), 0);
+    assert!(mapping.is_empty());
+
+    let mapping = parse("pkcs11:?").expect("mapping should be valid");
+    assert!(mapping.is_empty());
+}
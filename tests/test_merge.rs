@@ -0,0 +1,65 @@
+use pk11_uri_parser::{parse, parse_preserving_order, VendorMergePolicy};
+
+/// Standard attributes of the other mapping win, those it lacks are kept.
+#[test]
+fn merged_standard_attributes_override() {
+    let mut mapping = parse("pkcs11:token=base;type=private;serial=1?module-name=mypkcs11")
+        .expect("mapping should be valid");
+    let overrides = parse("pkcs11:type=cert;object=my-certificate?pin-source=file:/etc/token_pin")
+        .expect("mapping should be valid");
+    mapping.merge(&overrides, VendorMergePolicy::Replace);
+    assert_eq!(mapping.token(), Some("base"));
+    assert_eq!(mapping.serial(), Some("1"));
+    assert_eq!(mapping.r#type(), Some("cert"));
+    assert_eq!(mapping.object(), Some("my-certificate"));
+    assert_eq!(mapping.module_name(), Some("mypkcs11"));
+    assert_eq!(mapping.pin_source(), Some("file:/etc/token_pin"));
+    assert_eq!(
+        mapping.attribute_order(),
+        [
+            "token",
+            "type",
+            "serial",
+            "module-name",
+            "object",
+            "pin-source"
+        ]
+    );
+
+    let empty = parse("pkcs11:").expect("mapping should be valid");
+    let merged = mapping.clone();
+    mapping.merge(&empty, VendorMergePolicy::Replace);
+    assert_eq!(mapping, merged);
+}
+
+/// Vendor-specific values are replaced or appended as per the policy.
+#[test]
+fn merged_vendor_attributes_follow_policy() {
+    let base = parse("pkcs11:v-attr=a;v-base=x?v-attr=b").expect("mapping should be valid");
+    let overrides = parse("pkcs11:v-attr=c;v-other=y").expect("mapping should be valid");
+
+    let mut replaced = base.clone();
+    replaced.merge(&overrides, VendorMergePolicy::Replace);
+    assert_eq!(replaced.vendor("v-attr"), Some(&vec!["c"]));
+    assert_eq!(replaced.vendor("v-base"), Some(&vec!["x"]));
+    assert_eq!(replaced.vendor("v-other"), Some(&vec!["y"]));
+
+    let mut appended = base.clone();
+    appended.merge(&overrides, VendorMergePolicy::Append);
+    assert_eq!(appended.vendor("v-attr"), Some(&vec!["a", "b", "c"]));
+    assert_eq!(appended.vendor("v-base"), Some(&vec!["x"]));
+    assert_eq!(appended.vendor("v-other"), Some(&vec!["y"]));
+}
+
+/// A preserved source order no longer applies to a merged mapping.
+#[test]
+fn merging_discards_preserved_order() {
+    let mut mapping =
+        parse_preserving_order("pkcs11:type=cert;object=a").expect("mapping should be valid");
+    let overrides = parse("pkcs11:object=b").expect("mapping should be valid");
+    mapping.merge(&overrides, VendorMergePolicy::default());
+    assert_eq!(
+        mapping.to_string_preserving_order(),
+        "pkcs11:object=b;type=cert"
+    );
+}
@@ -0,0 +1,144 @@
+use pk11_uri_parser::{fingerprint, parse};
+
+/// Attribute order, formatting, and percent-encoding (case) don't affect the normalized form.
+#[test]
+fn equivalent_uris_normalize_identically() {
+    let pk11_uri = "pkcs11:token=my%2dtoken;object=my-key;type=private?pin-source=file:/etc/token_pin&v-attr=%aB";
+    let expected = "pkcs11:token=my-token;object=my-key;type=private;v-attr=%AB?pin-source=file:/etc/token_pin";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.normalize(), expected);
+
+    let pk11_uri = "pkcs11:type=private;
+            object=my-key;
+            token=my%2Dtoken
+            ?v-attr=%Ab&pin-source=file:/etc/token_pin";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.normalize(), expected);
+}
+
+/// Only the two hexadecimal digits of a percent-encoded octet are uppercased.
+#[test]
+fn normalize_leaves_other_characters_alone() {
+    let pk11_uri = "pkcs11:object=abc%3bdef";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.normalize(), "pkcs11:object=abc%3Bdef");
+}
+
+/// Equivalent URIs share a fingerprint; differing ones don't.
+#[test]
+fn fingerprints_identify_equivalent_uris() {
+    let fingerprint_of = |pk11_uri| fingerprint(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        fingerprint_of("pkcs11:object=my-key;id=%0a"),
+        fingerprint_of("pkcs11:id=%0A;object=my-key")
+    );
+    assert_ne!(
+        fingerprint_of("pkcs11:object=my-key;id=%0A"),
+        fingerprint_of("pkcs11:object=my-key;id=%0B")
+    );
+}
+
+/// The fingerprint is a stable 64-bit FNV-1a hash of the normalized URI.
+#[test]
+fn fingerprint_is_stable() {
+    assert_eq!(
+        fingerprint("pkcs11:").expect("mapping should be valid"),
+        0xf328_f062_4a8b_2bbe
+    );
+}
+
+/// Fingerprinting an invalid URI is an error.
+#[test]
+#[cfg(feature = "validation")]
+fn invalid_uri_has_no_fingerprint() {
+    fingerprint("pkcs11:type=muppet").expect_err("invalid type should not be valid");
+}
+
+/// Normalizing into a buffer replaces its content with the normalized form.
+#[test]
+fn normalize_into_reuses_buffer() {
+    let mut buf = String::from("leftover content that's longer than the normalized form");
+    for pk11_uri in [
+        "pkcs11:type=cert;id=%3e%5c;object=my-certificate",
+        "pkcs11:object=%ab%c3",
+        "pkcs11:",
+    ] {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        mapping.normalize_into(&mut buf);
+        assert_eq!(buf, mapping.normalize());
+    }
+    assert_eq!(buf, "pkcs11:");
+
+    let mapping = parse("pkcs11:object=%ab%c3").expect("mapping should be valid");
+    assert_eq!(mapping.normalize(), "pkcs11:object=%AB%C3");
+}
+
+/// Needlessly percent-encoded unreserved characters are decoded.
+#[test]
+fn normalize_decodes_unreserved_characters() {
+    let mapping = parse("pkcs11:token=%41%42C").expect("mapping should be valid");
+    assert_eq!(mapping.normalize(), "pkcs11:token=ABC");
+
+    let pk11_uri = "pkcs11:object=%2d%2E%5f%7e%7a%30%20%2f%25?module-name=p11%2Dkit";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.normalize(),
+        "pkcs11:object=-._~z0%20%2F%25?module-name=p11-kit"
+    );
+}
+
+/// The `id` value keeps every percent-encoding, whatever precedes it.
+#[test]
+fn normalize_keeps_id_encoded() {
+    let pk11_uri = "pkcs11:id=%41%2d%0a;token=%41%41%41;object=%6b%65%79;v-attr=%41";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.normalize(),
+        "pkcs11:token=AAA;object=key;id=%41%2D%0A;v-attr=A"
+    );
+    assert_eq!(
+        fingerprint("pkcs11:object=%6b%65%79;id=%41").expect("mapping should be valid"),
+        fingerprint("pkcs11:object=key;id=%41").expect("mapping should be valid")
+    );
+}
+
+/// Every value's percent-encodings are uppercased (and nothing decoded).
+#[test]
+fn normalized_uppercases_percent_encodings() {
+    let pk11_uri = "pkcs11:id=%Ba%dd%Ca%fe;object=%c3%a1%2d;v-attr=%3b?pin-value=%7c&v-attr=x%eb";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let normalized = mapping.normalized();
+    assert_eq!(normalized.id(), Some("%BA%DD%CA%FE"));
+    assert_eq!(normalized.object(), Some("%C3%A1%2D"));
+    assert_eq!(normalized.pin_value(), Some("%7C"));
+    assert_eq!(
+        normalized.vendor("v-attr"),
+        Some(&vec![String::from("%3B"), String::from("x%EB")])
+    );
+    assert_eq!(
+        normalized,
+        parse("pkcs11:id=%BA%DD%CA%FE;object=%C3%A1%2D;v-attr=%3B?pin-value=%7C&v-attr=x%EB")
+            .expect("mapping should be valid")
+            .to_owned_mapping()
+    );
+}
+
+/// Deduplicating keeps the first occurrence of each vendor-specific value, in order,
+/// leaving standard attributes and distinct values alone.
+#[test]
+fn vendor_values_are_deduplicated_in_order() {
+    let pk11_uri = "pkcs11:object=my-key;v-path=x;v-attr=b?v-attr=a&v-attr=b&v-attr=a&v-path=y";
+    let mut mapping = parse(pk11_uri).expect("mapping should be valid");
+    mapping.dedup_vendor_values();
+    assert_eq!(mapping.vendor("v-attr"), Some(&vec!["b", "a"]));
+    assert_eq!(mapping.vendor("v-path"), Some(&vec!["x", "y"]));
+    assert_eq!(mapping.object(), Some("my-key"));
+    assert_eq!(
+        mapping.to_string(),
+        "pkcs11:object=my-key?v-attr=b&v-attr=a&v-path=x&v-path=y"
+    );
+
+    let once = mapping.clone();
+    mapping.dedup_vendor_values();
+    assert_eq!(mapping, once);
+}
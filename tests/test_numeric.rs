@@ -0,0 +1,52 @@
+use pk11_uri_parser::parse;
+
+/// The `library-version` is read as its major and optional minor numbers.
+#[test]
+fn library_version_parts_are_numeric() {
+    for (library_version, parts) in [
+        ("10", (10, None)),
+        ("1.01", (1, Some(1))),
+        ("2.40", (2, Some(40))),
+        ("0.0", (0, Some(0))),
+        ("007", (7, None)),
+        ("4294967295.1", (u32::MAX, Some(1))),
+    ] {
+        let pk11_uri = format!("pkcs11:library-version={library_version}");
+        let mapping = parse(&pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.library_version_parts(), Some(parts), "{pk11_uri}");
+    }
+
+    let mapping = parse("pkcs11:library-version=4294967296").expect("mapping should be valid");
+    assert_eq!(mapping.library_version_parts(), None);
+
+    let mapping = parse("pkcs11:object=my-key").expect("mapping should be valid");
+    assert_eq!(mapping.library_version_parts(), None);
+}
+
+/// Without validation, a malformed `library-version` has no parts.
+#[test]
+#[cfg(not(feature = "validation"))]
+fn malformed_library_versions_have_no_parts() {
+    for library_version in ["", "1.", ".1", "1.2.3", "+1", "1.-2", "v1"] {
+        let pk11_uri = format!("pkcs11:library-version={library_version}");
+        let mapping = parse(&pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.library_version_parts(), None, "{pk11_uri}");
+    }
+}
+
+/// The `slot-id` is read as a number, up to `u64::MAX`.
+#[test]
+fn slot_id_num_is_numeric() {
+    for (slot_id, num) in [("0", 0), ("0042", 42), ("18446744073709551615", u64::MAX)] {
+        let pk11_uri = format!("pkcs11:slot-id={slot_id}");
+        let mapping = parse(&pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.slot_id_num(), Some(num), "{pk11_uri}");
+    }
+
+    let mapping = parse("pkcs11:slot-id=18446744073709551616").expect("mapping should be valid");
+    assert_eq!(mapping.slot_id(), Some("18446744073709551616"));
+    assert_eq!(mapping.slot_id_num(), None);
+
+    let mapping = parse("pkcs11:object=my-key").expect("mapping should be valid");
+    assert_eq!(mapping.slot_id_num(), None);
+}
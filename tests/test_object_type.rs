@@ -0,0 +1,104 @@
+use pk11_uri_parser::{parse, ObjectType, ViolationKind};
+
+/// Object types and PKCS#11 object classes map onto one another.
+#[test]
+fn object_classes_map_both_ways() {
+    let object_types = [
+        (ObjectType::Data, 0),
+        (ObjectType::Cert, 1),
+        (ObjectType::Public, 2),
+        (ObjectType::Private, 3),
+        (ObjectType::SecretKey, 4),
+    ];
+    for (object_type, class) in object_types {
+        assert_eq!(object_type.to_object_class(), class);
+        assert_eq!(ObjectType::from_object_class(class), Some(object_type));
+    }
+
+    // CKO_HW_FEATURE, CKO_DOMAIN_PARAMETERS, CKO_MECHANISM, CKO_OTP_KEY, CKO_PROFILE:
+    for class in 5..=9 {
+        assert_eq!(ObjectType::from_object_class(class), None);
+    }
+    // CKO_VENDOR_DEFINED:
+    assert_eq!(ObjectType::from_object_class(0x8000_0000), None);
+}
+
+/// Object types display as their `type` attribute value.
+#[test]
+fn object_types_display_as_type_values() {
+    assert_eq!(ObjectType::Public.to_string(), "public");
+    assert_eq!(ObjectType::Private.to_string(), "private");
+    assert_eq!(ObjectType::Cert.to_string(), "cert");
+    assert_eq!(ObjectType::SecretKey.to_string(), "secret-key");
+    assert_eq!(ObjectType::Data.to_string(), "data");
+}
+
+/// Both `object` and `type` result in the pair.
+#[test]
+fn object_and_type_required_results_in_both() {
+    let mapping = parse("pkcs11:type=secret-key;token=my-token;object=my%20key")
+        .expect("mapping should be valid");
+    let (object, object_type) = mapping
+        .object_and_type_required()
+        .expect("object and type should be present");
+    assert_eq!(object, "my%20key");
+    assert_eq!(object_type, ObjectType::SecretKey);
+}
+
+/// Whichever of `object` and `type` is missing gets named.
+#[test]
+fn object_and_type_required_names_the_missing() {
+    let missing = [
+        (
+            "pkcs11:type=cert",
+            "Missing required `object` path attribute.",
+        ),
+        (
+            "pkcs11:object=my-key",
+            "Missing required `type` path attribute.",
+        ),
+        (
+            "pkcs11:token=my-token?pin-source=file:/etc/token_pin",
+            "Missing required `object` and `type` path attributes.",
+        ),
+    ];
+    for (pk11_uri, violation) in missing {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        let err = mapping
+            .object_and_type_required()
+            .expect_err("object or type should be missing");
+        assert_eq!(err.to_string().lines().next(), Some(pk11_uri));
+        assert!(err.to_string().contains(violation), "{err}");
+        let path_end = pk11_uri.find('?').unwrap_or(pk11_uri.len());
+        assert_eq!(err.error_span(), (path_end, path_end));
+        assert_eq!(err.kind(), ViolationKind::MissingRequiredAttr);
+    }
+}
+
+/// Without validation, a `type` RFC7512 doesn't define is reported.
+#[test]
+#[cfg(not(feature = "validation"))]
+fn object_and_type_required_rejects_undefined_types() {
+    let mapping = parse("pkcs11:object=atype=key;type=key").expect("mapping should be parsed");
+    let err = mapping
+        .object_and_type_required()
+        .expect_err("type should not be valid");
+    assert_eq!(err.error_span(), (24, 32));
+    assert!(err.to_string().contains("Replace `key` value"));
+}
+
+/// The `type` attribute maps onto its `ObjectType`, if present.
+#[test]
+fn object_type_maps_the_type_attribute() {
+    for (pk11_uri, object_type) in [
+        ("pkcs11:type=public", Some(ObjectType::Public)),
+        ("pkcs11:type=private", Some(ObjectType::Private)),
+        ("pkcs11:type=cert", Some(ObjectType::Cert)),
+        ("pkcs11:type=secret-key", Some(ObjectType::SecretKey)),
+        ("pkcs11:type=data", Some(ObjectType::Data)),
+        ("pkcs11:object=my-key", None),
+    ] {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.object_type(), object_type, "{pk11_uri}");
+    }
+}
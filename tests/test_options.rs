@@ -0,0 +1,272 @@
+use pk11_uri_parser::{parse, parse_with_options, ParseOptions};
+
+fn options() -> ParseOptions<'static> {
+    ParseOptions::default().extra_query_attributes(&["pin-id", "slot-label"])
+}
+
+/// Extra query attributes are retrieved through `vendor` with a single value.
+#[test]
+fn extra_query_attributes_are_single_valued() {
+    let pk11_uri = "pkcs11:object=my-key?pin-id=01&slot-label=primary&v-attr=a&v-attr=b";
+    let mapping = parse_with_options(pk11_uri, &options()).expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("my-key"));
+    assert_eq!(mapping.vendor("pin-id"), Some(&vec!["01"]));
+    assert_eq!(mapping.vendor("slot-label"), Some(&vec!["primary"]));
+    assert_eq!(mapping.vendor("v-attr"), Some(&vec!["a", "b"]));
+}
+
+/// Without the options, the same attributes are simply vendor-specific.
+#[test]
+fn unregistered_attributes_remain_vendor_specific() {
+    let pk11_uri = "pkcs11:pin-id=01?pin-id=02";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.vendor("pin-id"), Some(&vec!["01", "02"]));
+
+    let mapping =
+        parse_with_options(pk11_uri, &ParseOptions::default()).expect("mapping should be valid");
+    assert_eq!(mapping.vendor("pin-id"), Some(&vec!["01", "02"]));
+}
+
+/// Extra query attributes may not be duplicated.
+#[test]
+#[cfg(feature = "validation")]
+fn duplicate_extra_query_attributes_are_not_valid() {
+    let pk11_uri = "pkcs11:?pin-id=01&pin-id=02";
+    let err = parse_with_options(pk11_uri, &options())
+        .expect_err("duplicate pin-id attribute names should not be valid");
+    assert!(err
+        .to_string()
+        .contains(r#"Duplicate `pk11-qattr` standard name: "pin-id"."#));
+}
+
+/// Extra query attributes may not be placed in the path.
+#[test]
+#[cfg(feature = "validation")]
+fn extra_query_attributes_in_path_are_not_valid() {
+    let pk11_uri = "pkcs11:object=my-key;pin-id=01";
+    let err = parse_with_options(pk11_uri, &options())
+        .expect_err("pin-id in the path should not be valid");
+    assert!(err
+        .to_string()
+        .contains("help: Move `pin-id` and its value to the PKCS#11 URI query."));
+}
+
+/// Standard attribute names are unaffected by being registered.
+#[test]
+#[cfg(feature = "validation")]
+fn standard_names_are_unaffected() {
+    let options = ParseOptions::default().extra_query_attributes(&["pin-source", "object"]);
+    let pk11_uri = "pkcs11:object=my-key?pin-source=file:/etc/token_pin";
+    let mapping = parse_with_options(pk11_uri, &options).expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("my-key"));
+    assert_eq!(mapping.pin_source(), Some("file:/etc/token_pin"));
+}
+
+/// Duplicate extra query attributes relate their first occurrence.
+#[test]
+#[cfg(feature = "validation")]
+fn duplicate_extra_query_attributes_relate_first_occurrence() {
+    let pk11_uri = "pkcs11:?pin-id=01&v-attr=a&pin-id=02";
+    let err = parse_with_options(pk11_uri, &options())
+        .expect_err("duplicate pin-id attribute names should not be valid");
+    assert_eq!(err.error_span(), (27, 36));
+    assert_eq!(err.related_span(), Some((8, 17)));
+}
+
+/// Strict attribute names don't tolerate whitespace adjacent to the '='.
+#[test]
+#[cfg(feature = "validation")]
+fn strict_attribute_names_reject_whitespace_around_equals() {
+    let options = ParseOptions::default().strict_attribute_names(true);
+    let violations = [
+        ("pkcs11:token =foo", (12, 14), "`token=foo`"),
+        ("pkcs11:token= foo", (12, 14), "`token=foo`"),
+        (
+            "pkcs11:object=my-key?module-name \t= p11-kit",
+            (32, 35),
+            "`module-name=p11-kit`",
+        ),
+    ];
+    for (pk11_uri, (start, end), fixed) in violations {
+        parse(pk11_uri).expect("whitespace is trimmed by default");
+        let err = parse_with_options(pk11_uri, &options)
+            .expect_err("whitespace around '=' should not be valid")
+            .to_string();
+        let highlight = format!(
+            "{:start$}{:^^len$} Malformed component:",
+            "",
+            "^",
+            len = end - start
+        );
+        assert!(
+            err.lines()
+                .nth(1)
+                .expect("highlight line")
+                .starts_with(&highlight),
+            "{err}"
+        );
+        assert!(
+            err.contains(&format!("help: Remove the whitespace, ie, {fixed}.")),
+            "{err}"
+        );
+    }
+
+    // formatting between attributes remains acceptable:
+    let pk11_uri = "pkcs11:token=foo;
+                   serial=
+                   ?pin-source=file:/etc/token_pin";
+    let mapping = parse_with_options(pk11_uri, &options).expect("mapping should be valid");
+    assert_eq!(mapping.serial(), Some(""));
+}
+
+/// Percent-decoded text values must be valid UTF-8 when opted into, whereas the
+/// binary `id` and vendor-specific values are left alone.
+#[test]
+#[cfg(feature = "validation")]
+fn validate_utf8_rejects_corrupted_sequences() {
+    use pk11_uri_parser::ViolationKind;
+
+    let options = ParseOptions::default().validate_utf8(true);
+    let pk11_uri = "pkcs11:token=Name%20with%20%C3%A1;id=%C3%FF?v-attr=%FF";
+    parse_with_options(pk11_uri, &options).expect("mapping should be valid");
+
+    let violations = [
+        ("pkcs11:token=Name%20with%20%C3%C3", (27, 30)),
+        ("pkcs11:object=%E2%82;type=cert", (14, 17)),
+        ("pkcs11:object=a?module-name=p11%FFkit", (31, 34)),
+    ];
+    for (pk11_uri, error_span) in violations {
+        parse(pk11_uri).expect("UTF-8 isn't validated by default");
+        let err = parse_with_options(pk11_uri, &options).expect_err("invalid UTF-8");
+        assert_eq!(err.kind(), ViolationKind::InvalidUtf8, "{pk11_uri}");
+        assert_eq!(err.error_span(), error_span, "{pk11_uri}");
+    }
+
+    let err = parse_with_options("pkcs11:token=%C3%C3", &options).expect_err("invalid UTF-8");
+    assert!(
+        err.to_string().ends_with(
+            "help: Correct the octet `%C3` (at offset 0 of the value), \
+            percent-encoding text as its UTF-8 octets, ie, `%C3%A1` for `á`."
+        ),
+        "{err}"
+    );
+}
+
+/// A single trailing delimiter is dropped when tolerated, while any other misplaced
+/// delimiter remains a violation.
+#[test]
+fn trailing_delimiters_are_tolerated() {
+    use pk11_uri_parser::ViolationKind;
+
+    let options = ParseOptions::default().tolerate_trailing_delimiter(true);
+    let cases = [
+        ("pkcs11:object=my-key;", "pkcs11:object=my-key"),
+        (
+            "pkcs11:object=my-key;?module-name=mypkcs11&",
+            "pkcs11:object=my-key?module-name=mypkcs11",
+        ),
+        ("pkcs11:object=my-key;\n\t", "pkcs11:object=my-key"),
+        ("pkcs11:;", "pkcs11:"),
+    ];
+    for (pk11_uri, expected) in cases {
+        let err = parse(pk11_uri).expect_err("trailing delimiter should not be valid");
+        assert_eq!(err.kind(), ViolationKind::MisplacedDelimiter, "{pk11_uri}");
+        let mapping = parse_with_options(pk11_uri, &options).expect("mapping should be valid");
+        assert_eq!(mapping.to_string(), expected);
+    }
+
+    for pk11_uri in [
+        "pkcs11:object=my-key;;",
+        "pkcs11:;object=my-key",
+        "pkcs11:?&&",
+    ] {
+        let err = parse_with_options(pk11_uri, &options).expect_err("misplaced delimiter");
+        assert_eq!(err.kind(), ViolationKind::MisplacedDelimiter, "{pk11_uri}");
+    }
+}
+
+/// The `Default` options must be indistinguishable from `parse` across every
+/// PKCS#11 URI exercised by the test suite, be it valid or not.
+#[test]
+fn default_options_are_equivalent_to_parse() {
+    let corpus = [
+        include_str!("test_audit.rs"),
+        include_str!("test_batch.rs"),
+        include_str!("test_best_effort.rs"),
+        include_str!("test_component.rs"),
+        include_str!("test_decode.rs"),
+        include_str!("test_deprecated.rs"),
+        include_str!("test_normalize.rs"),
+        include_str!("test_object_type.rs"),
+        include_str!("test_options.rs"),
+        include_str!("test_parse.rs"),
+        include_str!("test_pin_source.rs"),
+        include_str!("test_serialize.rs"),
+        include_str!("test_warnings.rs"),
+    ];
+    let pk11_uris: Vec<&str> = corpus
+        .iter()
+        .flat_map(|source| source.split('"'))
+        .filter(|literal| literal.starts_with("pkcs11:"))
+        .collect();
+    assert!(pk11_uris.len() > 100, "{} uris", pk11_uris.len());
+
+    for pk11_uri in pk11_uris {
+        match (
+            parse(pk11_uri),
+            parse_with_options(pk11_uri, &ParseOptions::default()),
+        ) {
+            (Ok(mapping), Ok(with_options)) => {
+                assert_eq!(mapping.to_string(), with_options.to_string(), "{pk11_uri}");
+                assert_eq!(
+                    mapping.attribute_order(),
+                    with_options.attribute_order(),
+                    "{pk11_uri}"
+                );
+            }
+            (Err(err), Err(with_options)) => {
+                assert_eq!(err.to_string(), with_options.to_string(), "{pk11_uri}");
+                assert_eq!(err.error_span(), with_options.error_span(), "{pk11_uri}");
+                assert_eq!(
+                    err.related_span(),
+                    with_options.related_span(),
+                    "{pk11_uri}"
+                );
+            }
+            (parsed, with_options) => {
+                panic!("{pk11_uri}: `parse` {parsed:?} but `parse_with_options` {with_options:?}")
+            }
+        }
+    }
+}
+
+/// The builder-style setters result in the same options as a struct literal.
+#[test]
+fn setters_set_their_option() {
+    let options = ParseOptions::default()
+        .extra_query_attributes(&["pin-id", "slot-label"])
+        .validate_pin_source_url(true)
+        .validate_pin_source(true)
+        .validate_utf8(true)
+        .strict_attribute_names(true)
+        .preserve_order(true)
+        .vendor_allowed_chars(&['{', '}'])
+        .allow_x_vendor_prefix(true)
+        .tolerate_trailing_delimiter(true);
+    assert_eq!(options.extra_query_attributes, ["pin-id", "slot-label"]);
+    assert!(options.validate_pin_source_url);
+    assert!(options.validate_pin_source);
+    assert!(options.validate_utf8);
+    assert!(options.strict_attribute_names);
+    assert!(options.preserve_order);
+    assert_eq!(options.vendor_allowed_chars, ['{', '}']);
+    assert!(options.allow_x_vendor_prefix);
+    assert!(options.tolerate_trailing_delimiter);
+
+    let options = options.preserve_order(false);
+    assert!(!options.preserve_order);
+
+    let pk11_uri = "pkcs11:object=my-key?pin-id=01";
+    let mapping = parse_with_options(pk11_uri, &options).expect("mapping should be valid");
+    assert_eq!(mapping.vendor("pin-id"), Some(&vec!["01"]));
+}
@@ -0,0 +1,121 @@
+use pk11_uri_parser::{parse, parse_owned, OwnedPK11URIMapping};
+
+/// An owned mapping retains every standard and vendor attribute, outliving its uri.
+#[test]
+fn owned_mappings_outlive_their_uri() {
+    let mapping: OwnedPK11URIMapping = {
+        let pk11_uri = String::from(
+            "pkcs11:token=my-token;type=cert;v-path=a?pin-source=file:/etc/token_pin&v-query=b&v-query=c",
+        );
+        parse_owned(&pk11_uri).expect("mapping should be valid")
+    };
+    assert_eq!(mapping.token(), Some("my-token"));
+    assert_eq!(mapping.r#type(), Some("cert"));
+    assert_eq!(mapping.pin_source(), Some("file:/etc/token_pin"));
+    assert_eq!(mapping.object(), None);
+    assert_eq!(mapping.vendor("v-path"), Some(&vec![String::from("a")]));
+    assert_eq!(
+        mapping.vendor("v-query"),
+        Some(&vec![String::from("b"), String::from("c")])
+    );
+    assert_eq!(mapping.attribute_order(), ["token", "type", "pin-source"]);
+    assert_eq!(
+        mapping.to_string(),
+        "pkcs11:token=my-token;type=cert;v-path=a?pin-source=file:/etc/token_pin&v-query=b&v-query=c"
+    );
+}
+
+/// Converting to and from an owned mapping is lossless.
+#[test]
+fn owned_mappings_convert_both_ways() {
+    let pk11_uri = "pkcs11:object=my-key;id=%01%02?module-name=p11-kit&v-attr=a";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let owned = mapping.to_owned_mapping();
+    assert_eq!(owned, OwnedPK11URIMapping::from(mapping.clone()));
+
+    let borrowed = owned.as_mapping();
+    assert_eq!(borrowed.to_string(), mapping.to_string());
+    assert_eq!(borrowed.id_bytes(), Ok(Some(vec![0x01, 0x02])));
+    assert_eq!(borrowed.attribute_order(), mapping.attribute_order());
+}
+
+/// Violations are reported just as `parse` reports them.
+#[test]
+#[cfg(feature = "validation")]
+fn parse_owned_reports_violations() {
+    let pk11_uri = "pkcs11:object=my key";
+    let err = parse_owned(pk11_uri).expect_err("empty spaces in value violation");
+    let parse_err = parse(pk11_uri).expect_err("empty spaces in value violation");
+    assert_eq!(err.to_string(), parse_err.to_string());
+}
+
+/// `FromStr` parses into an owned mapping.
+#[test]
+fn owned_mappings_parse_from_str() {
+    let mapping: OwnedPK11URIMapping = "pkcs11:object=my-key;type=private"
+        .parse()
+        .expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("my-key"));
+    assert_eq!(mapping.r#type(), Some("private"));
+
+    "pkcs11:object"
+        .parse::<OwnedPK11URIMapping>()
+        .expect_err("bare attribute name should not be valid");
+}
+
+/// Equal mappings hash equally, borrowed or owned, so either may key a cache.
+#[test]
+fn equal_mappings_hash_equally() {
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, RandomState};
+
+    let state = RandomState::new();
+    let first =
+        parse("pkcs11:object=my-key;type=cert;v-b=1;v-a=2?v-a=3").expect("mapping should be valid");
+    let second =
+        parse("pkcs11:v-a=2;type=cert;object=my-key?v-a=3&v-b=1").expect("mapping should be valid");
+    assert_eq!(first, second);
+    assert_eq!(state.hash_one(&first), state.hash_one(&second));
+    assert_eq!(
+        state.hash_one(first.to_owned_mapping()),
+        state.hash_one(second.to_owned_mapping())
+    );
+
+    let mut cache = HashMap::new();
+    cache.insert(first.to_owned_mapping(), "cached");
+    let reordered =
+        parse("pkcs11:v-a=3;type=cert;object=my-key?v-a=2&v-b=1").expect("mapping should be valid");
+    assert_eq!(cache.get(&second.to_owned_mapping()), Some(&"cached"));
+    assert_eq!(cache.get(&reordered.to_owned_mapping()), None);
+}
+
+/// Owned copies of standard and vendor-specific values are retrieved by name.
+#[test]
+fn values_are_copied_by_name() {
+    let pk11_uri = "pkcs11:token=my-token;serial=;v-attr=a?pin-source=file:/etc/token_pin&v-attr=b";
+    let values: Vec<_> = {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        [
+            "token",
+            "serial",
+            "pin-source",
+            "v-attr",
+            "object",
+            "v-other",
+        ]
+        .into_iter()
+        .map(|attr| mapping.get_owned(attr))
+        .collect()
+    };
+    assert_eq!(
+        values,
+        [
+            Some(String::from("my-token")),
+            Some(String::new()),
+            Some(String::from("file:/etc/token_pin")),
+            Some(String::from("a")),
+            None,
+            None
+        ]
+    );
+}
@@ -1,4 +1,4 @@
-use pk11_uri_parser::parse;
+use pk11_uri_parser::{parse, ViolationKind};
 
 /// All example PKCS#11 URI samples from the
 /// RFC7512 specification should properly parse.
@@ -213,6 +213,57 @@ fn text_values_with_hash_char_are_not_valid() {
     parse(pk11_uri).expect_err("'#' in value should not be valid");
 }
 
+/// A '%' must be followed by two hexadecimal digits, the error pointing at the
+/// malformed octet.
+#[test]
+#[cfg(feature = "validation")]
+fn malformed_percent_encoding_is_not_valid() {
+    let cases = [
+        ("pkcs11:object=100%", (17, 18)),
+        ("pkcs11:object=%1", (14, 16)),
+        ("pkcs11:object=%G0", (14, 15)),
+        ("pkcs11:id=%01%0;object=my-key", (13, 15)),
+        ("pkcs11:?pin-source=file:/x%2", (26, 28)),
+    ];
+    for (pk11_uri, error_span) in cases {
+        let err = parse(pk11_uri).expect_err("malformed percent-encoding should not be valid");
+        assert_eq!(err.kind(), ViolationKind::InvalidPercentEncoding, "{pk11_uri}");
+        assert_eq!(err.error_span(), error_span, "{pk11_uri}");
+    }
+
+    let pk11_uri = "pkcs11:object=100%25;id=%0a%FF";
+    parse(pk11_uri).expect("well-formed percent-encoding should be valid");
+}
+
+/// A value of whitespace only is pointed out rather than silently trimmed away,
+/// whereas percent-encoded spaces and formatting are fine.
+#[test]
+#[cfg(feature = "validation")]
+fn whitespace_only_values_are_not_valid() {
+    let cases = [
+        ("pkcs11:token=   ;object=my-key", (13, 16)),
+        ("pkcs11:object=my-key?module-name= ", (33, 34)),
+        ("pkcs11:v-attr=  ", (14, 16)),
+    ];
+    for (pk11_uri, error_span) in cases {
+        let err = parse(pk11_uri).expect_err("whitespace only value should not be valid");
+        assert_eq!(err.kind(), ViolationKind::WhitespaceOnlyValue, "{pk11_uri}");
+        assert_eq!(err.error_span(), error_span, "{pk11_uri}");
+    }
+    let err = parse("pkcs11:token=   ").expect_err("whitespace only value should not be valid");
+    assert!(err.to_string().ends_with(
+        "help: Remove the whitespace for an empty value, ie, `token=`, \
+        or percent-encode it for a value of spaces, ie, `token=%20`."
+    ));
+
+    let pk11_uri = "pkcs11:token=%20%20%20;serial=;model=
+            ?module-name=";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.token(), Some("%20%20%20"));
+    assert_eq!(mapping.serial(), Some(""));
+    assert_eq!(mapping.model(), Some(""));
+}
+
 /// Path attributes whose value is text may not contain the '/' char.
 #[test]
 #[cfg(feature = "validation")]
@@ -453,6 +504,27 @@ fn duplicate_standard_query_attributes_are_not_allowed() {
     parse(pk11_uri).expect_err("duplicate module-path attribute names should be not valid");
 }
 
+/// A PKCS#11 URI containing both "pin-source" and "pin-value" SHOULD be
+/// refused as invalid; the error identifies whichever of the two came second.
+#[test]
+fn pin_source_and_pin_value_are_not_valid_together() {
+    let pk11_uri = "pkcs11:?pin-source=file:/x&pin-value=y";
+    #[cfg(feature = "validation")]
+    {
+        let err = parse(pk11_uri).expect_err("pin-source and pin-value should be not valid");
+        assert_eq!(err.kind(), ViolationKind::ConflictingAttributes);
+        assert_eq!(err.error_span(), (27, 38));
+        assert_eq!(err.related_span(), Some((8, 26)));
+
+        let pk11_uri = "pkcs11:?pin-value=y&module-name=p11-kit&pin-source=file:/x";
+        let err = parse(pk11_uri).expect_err("pin-source and pin-value should be not valid");
+        assert_eq!(err.error_span(), (40, 58));
+        assert_eq!(err.related_span(), Some((8, 19)));
+    }
+    #[cfg(not(feature = "validation"))]
+    parse(pk11_uri).expect("lenient parsing keeps both");
+}
+
 /// Vendor-specific attributes may have multiple values.
 /// Limited to a single path-component, but an arbitrary
 /// number of query component entries.
@@ -475,3 +547,435 @@ fn vendor_attributes_may_have_multiple_values() {
     let vendor_attribute_value = mapping.vendor("vendor-attribute").expect("valid vendor-attribute value");
     assert!(vendor_attribute_value.eq(&vec!["hello", "world", "foo", "bar"]));
 }
+
+/// The first value of a vendor-specific attribute is retrieved directly.
+#[test]
+fn vendor_first_retrieves_first_value() {
+    let pk11_uri = "pkcs11:vendor-attribute=hello?vendor-attribute=world&other-attribute=foo";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.vendor_first("vendor-attribute"), Some("hello"));
+    assert_eq!(mapping.vendor_first("other-attribute"), Some("foo"));
+    assert_eq!(mapping.vendor_first("missing-attribute"), None);
+    assert_eq!(mapping.to_owned_mapping().vendor_first("vendor-attribute"), Some("hello"));
+}
+
+/// Raw bytes parse like text, save for a non-ASCII byte which is pointed out.
+#[test]
+fn bytes_must_be_ascii() {
+    let pk11_uri = b"pkcs11:object=my-key;type=private?module-name=mypkcs11";
+    let mapping = pk11_uri_parser::parse_bytes(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("my-key"));
+    assert_eq!(mapping.module_name(), Some("mypkcs11"));
+
+    let pk11_uri = b"pkcs11:object=\n\tmy\xFFkey";
+    let err = pk11_uri_parser::parse_bytes(pk11_uri).expect_err("non-ASCII byte should not be valid");
+    assert_eq!(err.kind(), ViolationKind::NonAsciiByte);
+    assert_eq!(err.error_span(), (16, 19));
+    assert!(err.to_string().contains("`0xFF` (at offset 18)"), "{err}");
+}
+
+/// A URI missing the ':' after `pkcs11` gets a targeted suggestion.
+#[test]
+#[cfg(feature = "validation")]
+fn scheme_missing_colon_is_pointed_out() {
+    let pk11_uri = "pkcs11";
+    let err = parse(pk11_uri).expect_err("missing ':' should not be valid");
+    let err = err.to_string();
+    let mut lines = err.lines();
+    assert_eq!(lines.next(), Some("pkcs11"));
+    assert!(lines.next().expect("highlight line").starts_with("      ^ "));
+    assert!(err.ends_with("help: Add the missing ':' after `pkcs11`."));
+
+    let pk11_uri = "pkcs11object=my-key";
+    let err = parse(pk11_uri).expect_err("missing ':' should not be valid");
+    assert!(err.to_string().ends_with("help: Add the missing ':' after `pkcs11`."));
+
+    let pk11_uri = "pkcs12:object=my-key";
+    let err = parse(pk11_uri).expect_err("wrong scheme should not be valid");
+    assert_eq!(err.error_span(), (0, 7));
+    assert!(err.to_string().starts_with("pkcs12:object=my-key\n^^^^^^^ "));
+    assert!(err.to_string().ends_with("help: Found `pkcs12:`, expected `pkcs11:`."));
+
+    let pk11_uri = "object=my-key?pin-source=file:/etc/token_pin";
+    let err = parse(pk11_uri).expect_err("missing scheme should not be valid");
+    assert_eq!(err.error_span(), (0, 44));
+    assert!(err.to_string().ends_with("help: PKCS#11 URI must start with `pkcs11:`."));
+}
+
+/// The scheme is case-insensitive (RFC3986), though a mapping is always
+/// displayed using the lowercase scheme.
+#[test]
+fn scheme_is_case_insensitive() {
+    for pk11_uri in ["PKCS11:object=x", "Pkcs11:object=x"] {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.object(), Some("x"));
+        assert_eq!(mapping.to_string(), "pkcs11:object=x");
+    }
+
+    #[cfg(feature = "validation")]
+    {
+        let err = parse("pkcs12:object=x").expect_err("wrong scheme should not be valid");
+        assert_eq!(err.kind(), ViolationKind::MissingScheme);
+        let err = parse("PKCS11object=x").expect_err("missing ':' should not be valid");
+        assert!(err.to_string().ends_with("help: Add the missing ':' after `pkcs11`."));
+    }
+}
+
+/// Using the path delimiter in the query (or vice versa) is pointed out,
+/// highlighting the misused delimiter.
+#[test]
+#[cfg(feature = "validation")]
+fn misused_component_delimiters_are_not_valid() {
+    let pk11_uri = "pkcs11:?pin-source=x;module-name=y";
+    let err = parse(pk11_uri).expect_err("';' in query should not be valid");
+    let err = err.to_string();
+    assert!(err.lines().nth(1).expect("highlight line").starts_with(&format!("{:20}^ ", "")));
+    assert!(err.ends_with("help: Use '&' to separate query attributes, not ';'."));
+
+    let pk11_uri = "pkcs11:object=my-key&type=cert";
+    let err = parse(pk11_uri).expect_err("'&' separating path attributes should not be valid");
+    let err = err.to_string();
+    assert!(err.lines().nth(1).expect("highlight line").starts_with(&format!("{:20}^ ", "")));
+    assert!(err.ends_with("help: Use ';' to separate path attributes, not '&'."));
+
+    // the caret accounts for multi-line formatting:
+    let pk11_uri = "pkcs11:object=my-key;
+            type=cert
+            ?pin-source=file:/etc/token_pin;module-name=mypkcs11";
+    let err = parse(pk11_uri).expect_err("';' in query should not be valid");
+    let err = err.to_string();
+    let offset = err.lines().next().expect("uri line").find(";module").expect("';' offset");
+    assert!(err.lines().nth(1).expect("highlight line").starts_with(&format!("{:offset$}^ ", "")));
+
+    // whereas a lone ';' within a query value is tolerated:
+    let pk11_uri = "pkcs11:?pin-value=12;34&v-attr=a;b";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.pin_value(), Some("12;34"));
+    assert_eq!(mapping.vendor("v-attr"), Some(&vec!["a;b"]));
+
+    // but '&' is perfectly fine in a path value:
+    let pk11_uri = "pkcs11:token=Snake&Oil;manufacturer=A&B=C";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.token(), Some("Snake&Oil"));
+    assert_eq!(mapping.manufacturer(), Some("A&B=C"));
+}
+
+/// Windows module paths may contain spaces, but only when percent-encoded.
+#[test]
+fn percent_encoded_spaces_in_module_path_are_valid() {
+    let pk11_uri = r"pkcs11:?module-path=C:\Program%20Files\x.dll";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.module_path(), Some(r"C:\Program%20Files\x.dll"));
+}
+
+/// A literal space in a Windows module path is still not valid.
+#[test]
+#[cfg(feature = "validation")]
+fn literal_spaces_in_module_path_are_not_valid() {
+    let pk11_uri = r"pkcs11:?module-path=C:\Program Files\x.dll";
+    let err = parse(pk11_uri).expect_err("empty space(s) in value should not be valid");
+    assert!(err.to_string().contains(r"C:\Program%20Files\x.dll"));
+}
+
+/// The first space of a value is pointed out within the highlighted attribute.
+#[test]
+#[cfg(feature = "validation")]
+fn first_spaces_are_focused() {
+    let pk11_uri = "pkcs11:token=my-token;object=Private key for Card Authentication";
+    let err = parse(pk11_uri).expect_err("empty space(s) in value should not be valid");
+    assert_eq!(err.error_span(), (22, 64));
+    assert_eq!(err.focus_span(), Some((36, 37)));
+    assert_eq!(
+        err.to_string().lines().nth(2),
+        Some("                                    ^ starting here")
+    );
+
+    // (newline formatting preceding the space is tidied away)
+    let pk11_uri = "pkcs11:token=my-token?pin-source=file:/etc/\nmy pin";
+    let err = parse(pk11_uri).expect_err("empty space(s) in value should not be valid");
+    assert_eq!(err.error_span(), (22, 49));
+    assert_eq!(err.focus_span(), Some((45, 46)));
+
+    let err = parse("pkcs11:object=my#key").expect_err("'#' in value should not be valid");
+    assert_eq!(err.focus_span(), None);
+    assert_eq!(err.to_string().lines().nth(2), Some(""));
+}
+
+/// Percent-encoded '?' and '&' delimiters in query values survive intact.
+#[test]
+fn percent_encoded_query_delimiters_are_not_split() {
+    let pk11_uri = "pkcs11:?pin-source=http://h/%3Fa%26b";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.pin_source(), Some("http://h/%3Fa%26b"));
+
+    let pk11_uri = "pkcs11:?v-attr=a%26b%3Fc&v-attr=d";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.vendor("v-attr"), Some(&vec!["a%26b%3Fc", "d"]));
+
+    let pk11_uri = "pkcs11:id=%26%3F";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.id_bytes(), Ok(Some(b"&?".to_vec())));
+}
+
+/// A percent-encoded ';' never splits a path value, whereas an unencoded one truncates it
+/// (the remainder being a bare attribute name), which is pointed out as such.
+#[test]
+fn percent_encoded_path_delimiters_are_not_split() {
+    let pk11_uri = "pkcs11:token=My%3BToken;object=a%3bb;type=cert";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.token(), Some("My%3BToken"));
+    assert_eq!(mapping.object(), Some("a%3bb"));
+    assert_eq!(mapping.r#type(), Some("cert"));
+
+    let pk11_uri = "pkcs11:token=My;Token;object=x";
+    let err = parse(pk11_uri).expect_err("truncated value should not be valid");
+    assert_eq!(
+        err.to_string(),
+        "pkcs11:token=My;Token;object=x\n                ^^^^^ Malformed component: `Token` is missing its '=' and value.\n\n\
+        help: If `Token` is part of the `token` value, percent-encode the ';' as `%3B`, \
+        ie, `token=My%3BToken`; otherwise, attributes take the form `name=value`, ie, `Token=value`."
+    );
+
+    let pk11_uri = "pkcs11:object=my-key?module-name=a&b";
+    let err = parse(pk11_uri).expect_err("truncated value should not be valid");
+    assert!(
+        err.to_string().ends_with(
+            "help: If `b` is part of the `module-name` value, percent-encode the '&' as `%26`, \
+            ie, `module-name=a%26b`; otherwise, attributes take the form `name=value`, ie, `b=value`."
+        ),
+        "{err}"
+    );
+}
+
+/// A standard attribute name never becomes a vendor-specific attribute, wherever it's placed.
+#[test]
+fn misplaced_standard_attributes_never_become_vendor_attributes() {
+    let pk11_uri = "pkcs11:?type=cert";
+    #[cfg(feature = "validation")]
+    {
+        let err = parse(pk11_uri).expect_err("type in the query should not be valid");
+        assert!(err
+            .to_string()
+            .contains("Naming collision with standard path component."));
+    }
+    #[cfg(not(feature = "validation"))]
+    {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.r#type(), Some("cert"));
+        assert_eq!(mapping.vendor("type"), None);
+    }
+
+    let pk11_uri = "pkcs11:pin-value=123456";
+    #[cfg(feature = "validation")]
+    parse(pk11_uri).expect_err("pin-value in the path should not be valid");
+    #[cfg(not(feature = "validation"))]
+    {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.pin_value(), Some("123456"));
+        assert_eq!(mapping.vendor("pin-value"), None);
+    }
+
+    #[cfg(feature = "validation")]
+    parse("pkcs11:type=cert;type=private").expect_err("duplicate type attribute names should not be valid");
+}
+
+/// Reusing a scratch buffer doesn't affect the outcome of parsing.
+#[test]
+#[cfg(feature = "validation")]
+fn parse_with_buf_matches_parse() {
+    let mut buf = String::from("leftover content");
+    for pk11_uri in [
+        "pkcs11:object=my-key;type=private?pin-source=file:/etc/token_pin",
+        "pkcs11:object=my key",
+        "pkcs11:token=a;
+            token=b",
+        "pkcs11:object=my-key;",
+        "pkcs11:object=my-key?
+            pin-source=file:/etc/token_pin&",
+        "pkcs11:?module-name=my module",
+    ] {
+        match (
+            parse(pk11_uri),
+            pk11_uri_parser::parse_with_buf(pk11_uri, &mut buf),
+        ) {
+            (Ok(mapping), Ok(buf_mapping)) => {
+                assert_eq!(mapping.to_string(), buf_mapping.to_string())
+            }
+            (Err(err), Err(buf_err)) => assert_eq!(err.to_string(), buf_err.to_string()),
+            (result, buf_result) => panic!("{result:?} != {buf_result:?}"),
+        }
+    }
+}
+
+/// A bare attribute name (lacking its '=' and value) is pointed out, with or without validation.
+#[test]
+fn bare_attribute_names_are_not_valid() {
+    let pk11_uri = "pkcs11:object";
+    let err = parse(pk11_uri).expect_err("bare attribute name should not be valid");
+    assert_eq!(
+        err.to_string(),
+        "pkcs11:object\n       ^^^^^^ Malformed component: `object` is missing its '=' and value.\n\n\
+        help: Attributes take the form `name=value`, ie, `object=value`."
+    );
+
+    let pk11_uri = "pkcs11:token=my-token;
+            object;type=cert";
+    let err = parse(pk11_uri).expect_err("bare attribute name should not be valid");
+    assert!(err.to_string().starts_with(
+        "pkcs11:token=my-token;            object;type=cert\n                                  ^^^^^^ "
+    ));
+
+    let pk11_uri = "pkcs11:object=my-key?pin-source=file:/etc/token_pin&module-name";
+    let err = parse(pk11_uri).expect_err("bare attribute name should not be valid");
+    assert!(err.to_string().starts_with(
+        "pkcs11:object=my-key?pin-source=file:/etc/token_pin&module-name\n                                                    ^^^^^^^^^^^ "
+    ));
+}
+
+/// A component beginning with its '=' is pointed out at the '=' as lacking its name.
+#[test]
+#[cfg(feature = "validation")]
+fn missing_attribute_names_are_not_valid() {
+    let pk11_uri = "pkcs11:=foo";
+    let err = parse(pk11_uri).expect_err("missing attribute name should not be valid");
+    assert_eq!(err.kind(), ViolationKind::MalformedComponent);
+    assert_eq!(
+        err.to_string(),
+        "pkcs11:=foo\n       ^ Invalid component: Missing attribute name.\n\n\
+        help: The attribute name is missing before `=`, ie, `name=foo`."
+    );
+
+    let cases = [
+        ("pkcs11:a=b;=c", (11, 12)),
+        ("pkcs11:a=b;\n\t =c", (12, 13)),
+        ("pkcs11:object=my-key?=c", (21, 22)),
+        ("pkcs11:object=my-key?module-name=mypkcs11&=", (42, 43)),
+    ];
+    for (pk11_uri, error_span) in cases {
+        let err = parse(pk11_uri).expect_err("missing attribute name should not be valid");
+        assert_eq!(err.kind(), ViolationKind::MalformedComponent, "{pk11_uri}");
+        assert_eq!(err.error_span(), error_span, "{pk11_uri}");
+        assert!(err.to_string().contains("help: The attribute name is missing before `=`"), "{err}");
+    }
+}
+
+/// A '?' within a path value must be percent-encoded, rather than being taken for
+/// the beginning of the query.
+#[test]
+#[cfg(feature = "validation")]
+fn unencoded_question_mark_in_path_value_is_pointed_out() {
+    let pk11_uri = "pkcs11:object=a?b";
+    let err = parse(pk11_uri).expect_err("'?' in path value should not be valid");
+    assert_eq!(err.kind(), ViolationKind::MisplacedDelimiter);
+    assert_eq!(
+        err.to_string(),
+        "pkcs11:object=a?b\n               ^ Invalid component value: a '?' begins the `pk11-query`, \
+        so must be percent-encoded within a path value.\n\n\
+        help: Percent-encode the '?' of the `object` value as `%3F`: `object=a%3Fb`."
+    );
+
+    let pk11_uri = "pkcs11:token=my-token;
+            object=what?not;type=cert";
+    let err = parse(pk11_uri).expect_err("'?' in path value should not be valid");
+    assert_eq!(err.error_span(), (45, 46));
+    assert!(err.to_string().ends_with("`object=what%3Fnot`."), "{err}");
+
+    // a bare attribute name is reported as such:
+    let pk11_uri = "pkcs11:object=a?module-name";
+    let err = parse(pk11_uri).expect_err("bare attribute name should not be valid");
+    assert_eq!(err.kind(), ViolationKind::MalformedComponent);
+
+    let pk11_uri = "pkcs11:object=a%3Fb?module-name=mypkcs11";
+    parse(pk11_uri).expect("percent-encoded '?' should be valid");
+}
+
+/// Superfluous delimiters are reported gracefully, with or without validation.
+#[test]
+fn misplaced_delimiters_are_not_valid() {
+    let pk11_uri = "pkcs11:object=my-key;";
+    let err = parse(pk11_uri).expect_err("trailing ';' should not be valid");
+    assert!(err.to_string().contains("Misplaced path delimiter."));
+
+    let pk11_uri = "pkcs11:?module-name=mypkcs11&";
+    let err = parse(pk11_uri).expect_err("trailing '&' should not be valid");
+    assert!(err.to_string().contains("Misplaced query delimiter."));
+}
+
+/// Duplicate attribute errors span the duplicate and relate its first occurrence.
+#[test]
+#[cfg(feature = "validation")]
+fn duplicate_attributes_report_both_occurrences() {
+    let duplicates = [
+        ("pkcs11:token=a;object=my-key;token=b", (29, 36), (7, 14)),
+        ("pkcs11:v-attr=1;v-attr=2", (16, 24), (7, 15)),
+        ("pkcs11:object=my-key?module-name=a&pin-value=1234&module-name=b", (50, 63), (21, 34)),
+        ("pkcs11:object=x;
+                 type=cert;
+                 object=y", (43, 68), (7, 15)),
+    ];
+    for (pk11_uri, error_span, related_span) in duplicates {
+        let err = parse(pk11_uri).expect_err("duplicate attribute names should not be valid");
+        assert_eq!(err.error_span(), error_span, "{pk11_uri}");
+        assert_eq!(err.related_span(), Some(related_span), "{pk11_uri}");
+    }
+
+    let err = parse("pkcs11:object=my key").expect_err("empty spaces in value should not be valid");
+    assert_eq!(err.related_span(), None);
+}
+
+/// An attribute duplicated verbatim is highlighted at its second occurrence.
+#[test]
+#[cfg(feature = "validation")]
+fn identical_duplicate_attributes_highlight_second_occurrence() {
+    let pk11_uri = "pkcs11:token=foo;token=foo";
+    let err = parse(pk11_uri).expect_err("duplicate attribute names should not be valid");
+    assert_eq!(err.error_span(), (17, 26));
+    assert_eq!(err.related_span(), Some((7, 16)));
+    assert!(err
+        .to_string()
+        .starts_with("pkcs11:token=foo;token=foo\n                 ^^^^^^^^^ "));
+
+    let pk11_uri = "pkcs11:?module-name=a&\n\tmodule-name=a";
+    let err = parse(pk11_uri).expect_err("duplicate attribute names should not be valid");
+    assert_eq!(err.error_span(), (22, 35));
+    assert_eq!(err.related_span(), Some((8, 21)));
+}
+
+/// Each violation is categorized by its `ViolationKind`.
+#[test]
+fn violations_are_categorized_by_kind() {
+    let cases = [
+        ("pkcs11:object", ViolationKind::MalformedComponent),
+        ("pkcs11:object=my-key;", ViolationKind::MisplacedDelimiter),
+        ("pkcs11:?module-name=mypkcs11&", ViolationKind::MisplacedDelimiter),
+    ]
+    .into_iter();
+    #[cfg(feature = "validation")]
+    let cases = cases.chain([
+        ("pkcs11object=my-key", ViolationKind::MissingScheme),
+        ("pkcs11:object=my key", ViolationKind::EmptySpaceInValue),
+        ("pkcs11:object=my#key", ViolationKind::HashInValue),
+        ("pkcs11:object=100%", ViolationKind::InvalidPercentEncoding),
+        ("pkcs11:object= ", ViolationKind::WhitespaceOnlyValue),
+        ("pkcs11:object=my/key", ViolationKind::SlashInPathValue),
+        ("pkcs11:object=a;object=b", ViolationKind::DuplicatePathAttr),
+        ("pkcs11:v-attr=a;v-attr=b", ViolationKind::DuplicatePathAttr),
+        ("pkcs11:?pin-value=1&pin-value=2", ViolationKind::DuplicateQueryAttr),
+        (
+            "pkcs11:?pin-source=file:/x&pin-value=1",
+            ViolationKind::ConflictingAttributes,
+        ),
+        ("pkcs11:?object=my-key", ViolationKind::PathQueryNamingCollision),
+        ("pkcs11:pin-value=1234", ViolationKind::PathQueryNamingCollision),
+        ("pkcs11:library-version=1.x", ViolationKind::InvalidLibraryVersion),
+        ("pkcs11:slot-id=one", ViolationKind::NonNumericSlotId),
+        ("pkcs11:type=private-key", ViolationKind::InvalidType),
+        ("pkcs11:object=my-key&type=cert", ViolationKind::MisplacedDelimiter),
+        ("pkcs11:v.attr=a", ViolationKind::InvalidVendorName),
+        ("pkcs11:=my-key", ViolationKind::MalformedComponent),
+    ]);
+    for (pk11_uri, kind) in cases {
+        let err = parse(pk11_uri).expect_err("uri should not be valid");
+        assert_eq!(err.kind(), kind, "{pk11_uri}");
+    }
+}
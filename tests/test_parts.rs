@@ -0,0 +1,69 @@
+use pk11_uri_parser::{parse, PK11URIMapping, ViolationKind};
+
+/// A mapping assembled from its parts equals that of the PKCS#11 URI they make up.
+#[test]
+fn mappings_are_assembled_from_parts() {
+    let mapping = PK11URIMapping::from_parts(
+        &[("object", "my-key"), ("type", "private"), ("v-attr", "a")],
+        &[("pin-source", "file:/etc/token_pin")],
+        &[("v-attr", "b"), ("x-slot", "2")],
+    )
+    .expect("mapping should be valid");
+    let pk11_uri = "pkcs11:object=my-key;type=private;v-attr=a?pin-source=file:/etc/token_pin&v-attr=b&x-slot=2";
+    assert_eq!(mapping, parse(pk11_uri).expect("mapping should be valid"));
+    assert_eq!(mapping.attribute_order(), ["object", "type", "pin-source"]);
+    assert_eq!(mapping.vendor("v-attr"), Some(&vec!["a", "b"]));
+
+    let empty = PK11URIMapping::from_parts(&[], &[], &[]).expect("mapping should be valid");
+    assert!(empty.is_empty());
+}
+
+/// Attributes are validated (and placed) as parsing them would be.
+#[cfg(feature = "validation")]
+#[test]
+fn parts_are_validated() {
+    let err = PK11URIMapping::from_parts(&[("object", "my-key")], &[("type", "cert")], &[])
+        .expect_err("type is a path attribute");
+    assert_eq!(
+        err.to_string().lines().next(),
+        Some("pkcs11:object=my-key?type=cert")
+    );
+    assert_eq!(err.error_span(), (21, 30));
+
+    PK11URIMapping::from_parts(&[("object", "my key")], &[], &[])
+        .expect_err("spaces should be percent-encoded");
+    PK11URIMapping::from_parts(&[], &[], &[("token", "my-token")])
+        .expect_err("token is a path attribute");
+    PK11URIMapping::from_parts(&[("object", "a"), ("object", "b")], &[], &[])
+        .expect_err("duplicate object attribute");
+}
+
+/// Delimiters within a name or value can't smuggle in further attributes.
+#[test]
+fn delimiters_are_not_injected() {
+    let err = PK11URIMapping::from_parts(&[("object", "a;type=cert")], &[], &[])
+        .expect_err("';' should be percent-encoded");
+    assert_eq!(err.kind(), ViolationKind::MisplacedDelimiter);
+    assert_eq!(err.error_span(), (7, 25));
+    assert!(err.to_string().contains("as `%3B`"), "{err}");
+
+    let err = PK11URIMapping::from_parts(&[("token", "a?pin-value=1234")], &[], &[])
+        .expect_err("'?' should be percent-encoded");
+    assert_eq!(err.kind(), ViolationKind::MisplacedDelimiter);
+    assert_eq!(err.error_span(), (7, 29));
+    assert!(err.to_string().contains("as `%3F`"), "{err}");
+
+    let err = PK11URIMapping::from_parts(&[], &[("module-name", "a&module-path=/lib")], &[])
+        .expect_err("'&' should be percent-encoded");
+    assert_eq!(err.kind(), ViolationKind::MisplacedDelimiter);
+    assert_eq!(err.error_span(), (8, 38));
+    assert!(err.to_string().contains("as `%26`"), "{err}");
+
+    PK11URIMapping::from_parts(&[], &[], &[("x-a=b", "c")])
+        .expect_err("'=' ends the attribute name");
+
+    let mapping = PK11URIMapping::from_parts(&[("object", "a%3Btype=cert")], &[], &[])
+        .expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("a%3Btype=cert"));
+    assert_eq!(mapping.r#type(), None);
+}
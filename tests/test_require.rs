@@ -0,0 +1,29 @@
+use pk11_uri_parser::parse;
+
+/// Present attributes (even empty ones) are retrieved, missing ones are named.
+#[test]
+fn required_attributes_are_retrieved_or_named() {
+    let pk11_uri = "pkcs11:object=my-key;serial=;v-attr=a?module-name=mypkcs11&v-attr=b";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.require("object"), Ok("my-key"));
+    assert_eq!(mapping.require("serial"), Ok(""));
+    assert_eq!(mapping.require("module-name"), Ok("mypkcs11"));
+    assert_eq!(mapping.require("v-attr"), Ok("a"));
+
+    let cases = [
+        ("type", "missing required `type` path attribute"),
+        (
+            "pin-source",
+            "missing required `pin-source` query attribute",
+        ),
+        (
+            "x-muppet",
+            "missing required `x-muppet` vendor-specific attribute",
+        ),
+    ];
+    for (attr, message) in cases {
+        let err = mapping.require(attr).expect_err("missing attribute");
+        assert_eq!(err.attribute(), attr);
+        assert_eq!(err.to_string(), message);
+    }
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "serde")]
+
+use pk11_uri_parser::{parse, PK11URIMapping};
+
+/// Serializing to JSON and back reproduces every standard and vendor attribute.
+#[test]
+fn mappings_round_trip_through_json() {
+    let pk11_uri = "pkcs11:token=The%20Software%20PKCS%2311%20Softtoken;
+            manufacturer=Snake%20Oil,%20Inc.;
+            model=1.0;
+            library-version=2.40;
+            object=my-certificate;
+            type=cert;
+            id=%69%95%3E%5C%F4%BD%EC%91;
+            slot-id=3;
+            serial=;
+            v-path=a
+            ?pin-source=file:/etc/token_pin&module-name=mypkcs11&v-query=b&v-query=c";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+
+    let json = serde_json::to_string(&mapping).expect("serializable mapping");
+    assert_eq!(
+        json,
+        r#"{"token":"The%20Software%20PKCS%2311%20Softtoken","manufacturer":"Snake%20Oil,%20Inc.","serial":"","model":"1.0","library-version":"2.40","object":"my-certificate","type":"cert","id":"%69%95%3E%5C%F4%BD%EC%91","slot-id":"3","pin-source":"file:/etc/token_pin","module-name":"mypkcs11","vendor":{"v-path":["a"],"v-query":["b","c"]}}"#
+    );
+
+    let deserialized: PK11URIMapping = serde_json::from_str(&json).expect("deserializable mapping");
+    assert_eq!(deserialized.to_string(), mapping.to_string());
+    assert_eq!(deserialized.vendor("v-query"), Some(&vec!["b", "c"]));
+
+    let mapping = parse("pkcs11:").expect("mapping should be valid");
+    let json = serde_json::to_string(&mapping).expect("serializable mapping");
+    assert_eq!(json, "{}");
+    let deserialized: PK11URIMapping = serde_json::from_str(&json).expect("deserializable mapping");
+    assert_eq!(deserialized.to_string(), "pkcs11:");
+}
+
+/// Unknown standard attributes, duplicates and unborrowable strings are rejected.
+#[test]
+fn malformed_json_is_not_deserialized() {
+    for json in [
+        r#"{"v-attr":"a"}"#,
+        r#"{"object":"a","object":"b"}"#,
+        r#"{"object":"my\"key"}"#,
+        r#"{"object":null}"#,
+    ] {
+        serde_json::from_str::<PK11URIMapping>(json).expect_err(json);
+    }
+}
+
+/// Errors serialize as diagnostics, the related span included only when there is one.
+#[test]
+#[cfg(feature = "validation")]
+fn errors_serialize_as_diagnostics() {
+    let err = parse("pkcs11:token=a;object=my-key;token=b").expect_err("duplicate token attribute");
+    let json = serde_json::to_string(&err).expect("serializable error");
+    assert!(
+        json.starts_with(
+            r#"{"uri":"pkcs11:token=a;object=my-key;token=b","span":[29,36],"kind":"DuplicatePathAttr","violation":"#
+        ),
+        "{json}"
+    );
+    assert!(json.ends_with(r#","related_span":[7,14]}"#), "{json}");
+
+    let err = parse("pkcs11:object=my\n\tkey;type=x").expect_err("invalid type");
+    let json: serde_json::Value = serde_json::to_value(&err).expect("serializable error");
+    assert_eq!(json["uri"], "pkcs11:object=mykey;type=x");
+    assert_eq!(json["kind"], "InvalidType");
+    assert_eq!(
+        json["help"],
+        err.to_string().rsplit("help: ").next().unwrap()
+    );
+    assert!(json.get("related_span").is_none());
+}
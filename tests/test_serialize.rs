@@ -0,0 +1,188 @@
+use pk11_uri_parser::parse;
+
+/// A parsed mapping reconstructs its canonical URI, directly or via `TryInto`.
+#[test]
+fn parsed_mappings_convert_to_uri_strings() {
+    let pk11_uri = "pkcs11:token=The%20Software%20PKCS%2311%20Softtoken;
+            manufacturer=Snake%20Oil,%20Inc.;
+            model=1.0;
+            object=my-certificate;
+            type=cert;
+            id=%69%95%3E%5C%F4%BD%EC%91;
+            serial=
+            ?pin-source=file:/etc/token_pin";
+    let canonical = "pkcs11:token=The%20Software%20PKCS%2311%20Softtoken;\
+            manufacturer=Snake%20Oil,%20Inc.;serial=;model=1.0;object=my-certificate;\
+            type=cert;id=%69%95%3E%5C%F4%BD%EC%91?pin-source=file:/etc/token_pin";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.to_uri().expect("valid uri"), canonical);
+
+    let converted: String = (&mapping).try_into().expect("valid uri");
+    assert_eq!(converted, canonical);
+
+    let pk11_uri = "pkcs11:";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.to_uri().expect("valid uri"), "pkcs11:");
+}
+
+/// Standard attributes are recorded in the order they were parsed.
+#[test]
+fn attribute_order_follows_the_parsed_uri() {
+    let pk11_uri = "pkcs11:slot-id=2;object=my-key;v-attr=a;token=my-token?module-name=p11-kit&v-attr=b&pin-value=1234";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.attribute_order(),
+        ["slot-id", "object", "token", "module-name", "pin-value"]
+    );
+
+    let mapping = parse("pkcs11:").expect("mapping should be valid");
+    assert!(mapping.attribute_order().is_empty());
+}
+
+/// The recorded order is bookkeeping, left out of the `Debug` output.
+#[test]
+fn attribute_order_is_not_debugged() {
+    let mapping = parse("pkcs11:object=my-key;type=cert").expect("mapping should be valid");
+    let debugged = format!("{mapping:?}");
+    assert!(
+        debugged.starts_with("PK11URIMapping { token: None,"),
+        "{debugged}"
+    );
+    assert!(debugged.ends_with("type: Some(\"cert\"), id: None, slot_description: None, slot_manufacturer: None, slot_id: None, pin_source: None, pin_value: None, module_name: None, module_path: None, vendor: {} }"), "{debugged}");
+}
+
+/// Without validation, a duplicate keeps the first occurrence's place in the order.
+#[test]
+#[cfg(not(feature = "validation"))]
+fn attribute_order_without_validation() {
+    let mapping = parse("pkcs11:object=a;pin-source=file:/pin;type=cert;object=b")
+        .expect("mapping should be parsed");
+    assert_eq!(mapping.attribute_order(), ["object", "pin-source", "type"]);
+    assert_eq!(mapping.object(), Some("b"));
+}
+
+/// Preserving the order reproduces a PKCS#11 URI (lacking formatting whitespace)
+/// byte for byte, vendor-specific attributes and their components included.
+#[test]
+fn preserved_order_reproduces_the_parsed_uri() {
+    use pk11_uri_parser::parse_preserving_order;
+
+    for pk11_uri in [
+        "pkcs11:",
+        "pkcs11:v-attr=a;type=cert;serial=;object=my-key",
+        "pkcs11:slot-id=2;x-muppet=cookie?v-attr=b&module-name=p11-kit&v-attr=a&pin-value=1234",
+        "pkcs11:?x-muppet=cookie&pin-source=file:/etc/token_pin",
+    ] {
+        let mapping = parse_preserving_order(pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.to_string_preserving_order(), pk11_uri);
+        // the owned mapping preserves it too:
+        assert_eq!(
+            mapping
+                .to_owned_mapping()
+                .as_mapping()
+                .to_string_preserving_order(),
+            pk11_uri
+        );
+    }
+
+    // formatting whitespace isn't retained:
+    let mapping = parse_preserving_order("pkcs11:type=cert;\n    object=my-key")
+        .expect("mapping should be valid");
+    assert_eq!(
+        mapping.to_string_preserving_order(),
+        "pkcs11:type=cert;object=my-key"
+    );
+
+    // nor are dropped vendor-specific attributes:
+    let mapping = parse_preserving_order("pkcs11:v-attr=a;type=cert?v-attr=b&module-name=p11-kit")
+        .expect("mapping should be valid");
+    assert_eq!(
+        mapping.standard_only().to_string_preserving_order(),
+        "pkcs11:type=cert?module-name=p11-kit"
+    );
+}
+
+/// Without a preserved order, the canonical PKCS#11 URI results.
+#[test]
+fn unpreserved_order_is_canonical() {
+    let mapping =
+        parse("pkcs11:v-attr=a;type=cert;object=my-key").expect("mapping should be valid");
+    assert_eq!(
+        mapping.to_string_preserving_order(),
+        "pkcs11:object=my-key;type=cert;v-attr=a"
+    );
+}
+
+/// Only the standard attributes (and their parse order) are retained.
+#[test]
+fn standard_only_drops_vendor_attributes() {
+    let pk11_uri = "pkcs11:v-attr=a;token=my-token;type=cert?v-attr=b&pin-source=file:/etc/token_pin&x-other=c";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    let standard = mapping.standard_only();
+    assert_eq!(
+        standard.to_string(),
+        "pkcs11:token=my-token;type=cert?pin-source=file:/etc/token_pin"
+    );
+    assert_eq!(standard.vendor("v-attr"), None);
+    assert_eq!(standard.attribute_order(), mapping.attribute_order());
+    // the original is left as-is:
+    assert_eq!(mapping.vendor("v-attr"), Some(&vec!["a", "b"]));
+}
+
+/// `Display` is the inverse of `parse`: the canonical URI parses back into an
+/// equivalent mapping, including empty values, vendor attributes and the lone scheme.
+#[test]
+fn display_round_trips_through_parse() {
+    for (pk11_uri, canonical) in [
+        ("pkcs11:", "pkcs11:"),
+        ("pkcs11:serial=", "pkcs11:serial="),
+        ("pkcs11:?pin-value=", "pkcs11:?pin-value="),
+        ("pkcs11:?v-attr=a;b", "pkcs11:?v-attr=a;b"),
+        (
+            "pkcs11:type=cert;object=my-certificate?module-name=mypkcs11",
+            "pkcs11:object=my-certificate;type=cert?module-name=mypkcs11",
+        ),
+        (
+            "pkcs11:v-path=a;token=foo?v-query=b&v-query=c&pin-source=file:/etc/token_pin",
+            "pkcs11:token=foo;v-path=a?pin-source=file:/etc/token_pin&v-query=b&v-query=c",
+        ),
+    ] {
+        let mapping = parse(pk11_uri).expect("mapping should be valid");
+        assert_eq!(mapping.to_string(), canonical);
+
+        let reparsed = parse(canonical).expect("canonical uri should be valid");
+        assert_eq!(reparsed.to_string(), canonical);
+    }
+}
+
+/// Mappings compare equal regardless of attribute order, save the order of a
+/// vendor-specific attribute's values.
+#[test]
+fn mappings_compare_semantically() {
+    let mapping =
+        parse("pkcs11:object=my-key;type=cert;v-path=a?pin-value=1234&v-query=b&v-query=c")
+            .expect("mapping should be valid");
+    let reordered =
+        parse("pkcs11:v-path=a;type=cert;object=my-key?v-query=b&pin-value=1234&v-query=c")
+            .expect("mapping should be valid");
+    assert_ne!(mapping.attribute_order(), reordered.attribute_order());
+    assert_eq!(mapping, reordered);
+    assert_eq!(
+        mapping,
+        parse(&mapping.to_string()).expect("mapping should be valid")
+    );
+
+    for different in [
+        "pkcs11:object=my-key;type=cert;v-path=a?pin-value=1234&v-query=c&v-query=b",
+        "pkcs11:object=my-key;type=cert;v-path=a?pin-value=1234&v-query=b",
+        "pkcs11:object=my%2Dkey;type=cert;v-path=a?pin-value=1234&v-query=b&v-query=c",
+        "pkcs11:object=my-key;type=cert?pin-value=1234&v-query=b&v-query=c",
+        "pkcs11:object=my-key;type=cert;v-path=a?v-query=b&v-query=c",
+    ] {
+        assert_ne!(
+            mapping,
+            parse(different).expect("mapping should be valid"),
+            "{different}"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use pk11_uri_parser::{parse, shell_quote};
+use std::process::Command;
+
+/// The normalized URI is single-quoted for the shell.
+#[test]
+fn mapping_quotes_as_a_single_shell_argument() {
+    let pk11_uri = "pkcs11:token=my%2dtoken;object=my-key;type=private
+            ?pin-source=|/usr/lib/pinomatic&v-attr=a&v-attr=b%7e";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.to_shell_arg(),
+        "'pkcs11:token=my-token;object=my-key;type=private?pin-source=|/usr/lib/pinomatic&v-attr=a&v-attr=b~'"
+    );
+}
+
+/// A POSIX shell receives the quoted URI verbatim, embedded quotes included.
+#[test]
+#[cfg(unix)]
+fn shell_receives_quoted_uri_verbatim() {
+    for pk11_uri in [
+        "pkcs11:object=my-key;type=private?pin-source=|/usr/lib/pinomatic&module-name=p11",
+        "pkcs11:object=Joe's%20key;token='quoted'",
+        "pkcs11:object=$HOME;token=`id`",
+    ] {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(format!("printf %s {}", shell_quote(pk11_uri)))
+            .output()
+            .expect("sh should run");
+        assert_eq!(String::from_utf8_lossy(&output.stdout), pk11_uri);
+    }
+}
+
+/// Attributes are named after the prefix and their uppercased name, omitting `pin-value`.
+#[test]
+fn mapping_exports_environment_variables() {
+    let pk11_uri = "pkcs11:token=my%20token;object=my-key;type=private;v-attr=a
+            ?pin-value=123456&module-path=/usr/lib/mypkcs11.so&v-attr=b";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.to_env("PKCS11_"),
+        [
+            ("PKCS11_TOKEN", "my%20token"),
+            ("PKCS11_OBJECT", "my-key"),
+            ("PKCS11_TYPE", "private"),
+            ("PKCS11_MODULE_PATH", "/usr/lib/mypkcs11.so"),
+            ("PKCS11_V_ATTR", "a b"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+    );
+
+    let pk11_uri = "pkcs11:?pin-value=123456";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert!(mapping.to_env("PKCS11_").is_empty());
+
+    let pk11_uri = "pkcs11:slot-id=1";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+    assert_eq!(
+        mapping.to_env(""),
+        [(String::from("SLOT_ID"), String::from("1"))]
+    );
+}
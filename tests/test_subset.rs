@@ -0,0 +1,50 @@
+use pk11_uri_parser::parse;
+
+/// A mapping is a subset of itself and of any more specific mapping.
+#[test]
+fn less_specific_mappings_are_subsets() {
+    let pk11_uri = "pkcs11:token=my-token;type=cert";
+    let general = parse(pk11_uri).expect("mapping should be valid");
+
+    let pk11_uri =
+        "pkcs11:token=my-token;object=my-certificate;type=cert?pin-source=file:/etc/token_pin";
+    let specific = parse(pk11_uri).expect("mapping should be valid");
+
+    assert!(general.is_subset_of(&general));
+    assert!(general.is_subset_of(&specific));
+    assert!(!specific.is_subset_of(&general));
+
+    let empty = parse("pkcs11:").expect("mapping should be valid");
+    assert!(empty.is_subset_of(&general));
+    assert!(!general.is_subset_of(&empty));
+}
+
+/// Differing values, even for the same attributes, are not subsets.
+#[test]
+fn differing_values_are_not_subsets() {
+    let pk11_uri = "pkcs11:token=my-token;type=cert";
+    let cert = parse(pk11_uri).expect("mapping should be valid");
+
+    let pk11_uri = "pkcs11:token=my-token;type=private";
+    let private = parse(pk11_uri).expect("mapping should be valid");
+
+    assert!(!cert.is_subset_of(&private));
+    assert!(!private.is_subset_of(&cert));
+}
+
+/// Vendor-specific values must be among the other mapping's values for the attribute.
+#[test]
+fn vendor_values_are_compared_as_sets() {
+    let pk11_uri = "pkcs11:?v-attr=b&v-attr=a";
+    let two_values = parse(pk11_uri).expect("mapping should be valid");
+
+    let pk11_uri = "pkcs11:v-attr=a?v-attr=b&v-attr=c";
+    let three_values = parse(pk11_uri).expect("mapping should be valid");
+
+    assert!(two_values.is_subset_of(&three_values));
+    assert!(!three_values.is_subset_of(&two_values));
+
+    let pk11_uri = "pkcs11:x-attr=a";
+    let other_name = parse(pk11_uri).expect("mapping should be valid");
+    assert!(!other_name.is_subset_of(&three_values));
+}
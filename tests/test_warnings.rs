@@ -0,0 +1,346 @@
+#![cfg(feature = "warnings")]
+
+use pk11_uri_parser::{parse_with_warnings, WarningKind};
+
+/// The number of warnings (advisories that fired) parsing the valid `pk11_uri` results in.
+fn warning_count(pk11_uri: &str) -> usize {
+    parse_with_warnings(pk11_uri)
+        .map(|(_mapping, warnings)| warnings.len())
+        .expect("mapping should be valid")
+}
+
+/// A URI complying with every "SHOULD" guideline has no warnings.
+#[test]
+fn compliant_uri_has_no_warnings() {
+    let pk11_uri = "pkcs11:token=my-token;object=my-key;id=%01%02?module-name=mypkcs11";
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+/// Each advisory that fires is counted, however many concern the same attribute.
+#[test]
+fn warnings_are_counted_exactly() {
+    let cases = [
+        ("pkcs11:object=my-key", 0),
+        ("pkcs11:object=my-key;id=abc", 1),
+        ("pkcs11:x-muppet=cookie<monster", 2),
+        ("pkcs11:token=a<b>;x-muppet=c?module-name=libp11.so", 4),
+    ];
+    for (pk11_uri, count) in cases {
+        assert_eq!(warning_count(pk11_uri), count, "{pk11_uri}");
+    }
+}
+
+/// Warnings identify their attribute and offset within the given (untidied) URI.
+#[test]
+fn warnings_locate_offending_attribute() {
+    let pk11_uri = "pkcs11:object=my-key;
+            id=abc";
+    let (mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.id(), Some("abc"));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].attribute(), "id");
+    assert_eq!(&pk11_uri[warnings[0].offset()..], "abc");
+    assert_eq!(
+        warnings[0].to_string(),
+        "pkcs11 warning: the whole value of the `id` attribute SHOULD be percent-encoded: id=abc."
+    );
+}
+
+/// Characters that SHOULD be percent-encoded are each warned about.
+#[test]
+fn unencoded_characters_are_warned_about() {
+    let pk11_uri = "pkcs11:token=a<b>?pin-source=file:/x%25";
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    let offsets: Vec<_> = warnings
+        .iter()
+        .map(|warning| {
+            (
+                warning.attribute(),
+                &pk11_uri[warning.offset()..warning.offset() + 1],
+            )
+        })
+        .collect();
+    assert_eq!(offsets, [("token", "<"), ("token", ">")]);
+
+    // (validation refuses malformed percent-encoding outright)
+    #[cfg(not(feature = "validation"))]
+    {
+        let pk11_uri = "pkcs11:?pin-source=file:/x%2";
+        let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].attribute(), "pin-source");
+        assert!(warnings[0].message().contains("malformed percent-encoding"));
+    }
+}
+
+/// Combinations of attributes which SHOULD be avoided are warned about.
+#[test]
+fn conflicting_query_attributes_are_warned_about() {
+    let pk11_uri = "pkcs11:?module-name=mypkcs11&module-path=/usr/lib/mypkcs11.so";
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].attribute(), "module-path");
+
+    // (validation refuses the combination outright)
+    #[cfg(not(feature = "validation"))]
+    {
+        let pk11_uri = "pkcs11:?pin-source=file:/etc/token_pin&pin-value=123456";
+        let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].attribute(), "pin-value");
+        assert_eq!(&pk11_uri[warnings[0].offset()..], "123456");
+    }
+}
+
+/// Errors still take precedence over warnings.
+#[test]
+#[cfg(feature = "validation")]
+fn invalid_uri_is_an_error() {
+    let pk11_uri = "pkcs11:x-muppet=cookie;type=muppet";
+    parse_with_warnings(pk11_uri).expect_err("invalid type should not be valid");
+}
+
+/// A `slot-id` exceeding a 64-bit `CK_SLOT_ID` is warned about.
+#[test]
+fn overflowing_slot_ids_are_warned_about() {
+    assert_eq!(warning_count("pkcs11:slot-id=18446744073709551615"), 0);
+
+    let pk11_uri = "pkcs11:object=my-key;slot-id=18446744073709551616";
+    let (mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.slot_id_num(), None);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].attribute(), "slot-id");
+    assert_eq!(warnings[0].offset(), 29);
+    assert_eq!(
+        warnings[0].to_string(),
+        "pkcs11 warning: the `slot-id` value exceeds the range of a 64-bit `CK_SLOT_ID`: slot-id=18446744073709551616."
+    );
+}
+
+/// Warnings identify the component the offending characters are in, including
+/// wherever a vendor-specific attribute was given.
+#[test]
+fn warnings_identify_offending_component() {
+    use pk11_uri_parser::Component;
+
+    let pk11_uri = "pkcs11:object=my<key>;x-path=a?x-query=b&module-name=p11|kit";
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    let components: Vec<_> = warnings
+        .iter()
+        .map(|warning| (warning.attribute(), warning.component()))
+        .collect();
+    assert_eq!(
+        components,
+        [
+            ("object", Component::Path),
+            ("object", Component::Path),
+            ("x-path", Component::Path),
+            ("x-query", Component::Query),
+        ]
+    );
+}
+
+/// A sink receives the same warnings `parse_with_warnings` returns, as well as
+/// those issued before a violation.
+#[test]
+fn warnings_are_handed_to_sink() {
+    use pk11_uri_parser::parse_with_sink;
+
+    let pk11_uri = "pkcs11:x-muppet=cookie<monster";
+    let mut sunk = vec![];
+    let mapping = parse_with_sink(pk11_uri, &mut |warning| sunk.push(warning))
+        .expect("mapping should be valid");
+    assert_eq!(mapping.vendor("x-muppet"), Some(&vec!["cookie<monster"]));
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(sunk, warnings);
+
+    #[cfg(feature = "validation")]
+    {
+        let mut sunk = vec![];
+        parse_with_sink("pkcs11:x-muppet=cookie<monster;id=my key", &mut |warning| {
+            sunk.push(warning)
+        })
+        .expect_err("empty space in value violation");
+        assert_eq!(sunk.len(), 2);
+    }
+}
+
+/// A `module-path` which isn't absolute is warned about.
+#[test]
+fn relative_module_path_is_warned_about() {
+    for pk11_uri in [
+        "pkcs11:?module-path=/usr/lib/mypkcs11.so",
+        "pkcs11:?module-path=C:%5CWindows%5Cmypkcs11.dll",
+        "pkcs11:?module-path=c:/mypkcs11.dll",
+    ] {
+        let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+        assert!(warnings.is_empty(), "{pk11_uri}: {warnings:?}");
+    }
+
+    let pk11_uri = "pkcs11:?module-path=lib/mypkcs11.so";
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].attribute(), "module-path");
+    assert_eq!(warnings[0].offset(), 20);
+    assert!(warnings[0].message().contains("SHOULD be an absolute path"));
+}
+
+/// Characters allowed for vendor-specific attributes aren't warned about, those of
+/// standard attributes still are.
+#[test]
+fn vendor_allowed_chars_are_not_warned_about() {
+    use pk11_uri_parser::{parse_with_warnings_and_options, ParseOptions};
+
+    let options = ParseOptions {
+        vendor_allowed_chars: &['{', '}'],
+        ..ParseOptions::default()
+    };
+    let pk11_uri = "pkcs11:token={t};v-path={a}<b>?v-query={c}&module-name={m}";
+    let (_mapping, warnings) =
+        parse_with_warnings_and_options(pk11_uri, &options).expect("mapping should be valid");
+    let offending: Vec<_> = warnings
+        .iter()
+        .map(|warning| {
+            (
+                warning.attribute(),
+                &pk11_uri[warning.offset()..warning.offset() + 1],
+            )
+        })
+        .collect();
+    assert_eq!(
+        offending,
+        [
+            ("token", "{"),
+            ("token", "}"),
+            ("v-path", "<"),
+            ("v-path", ">"),
+            ("module-name", "{"),
+            ("module-name", "}"),
+        ]
+    );
+
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(warnings.len(), 10);
+}
+
+/// A knowingly used `x-` prefix isn't warned about, the vendor-specific value still is.
+#[test]
+fn allowed_x_vendor_prefix_is_not_warned_about() {
+    use pk11_uri_parser::{parse_with_warnings_and_options, ParseOptions};
+
+    let pk11_uri = "pkcs11:x-muppet=cookie<monster?x-query=b";
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(warnings.len(), 3);
+
+    let options = ParseOptions::default().allow_x_vendor_prefix(true);
+    let (_mapping, warnings) =
+        parse_with_warnings_and_options(pk11_uri, &options).expect("mapping should be valid");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].attribute(), "x-muppet");
+    assert_eq!(warnings[0].offset(), 22);
+}
+
+/// An unencoded '=' in a path value is warned about, as it's likely a mistyped attribute.
+#[test]
+fn unencoded_equals_in_path_value_is_warned_about() {
+    let pk11_uri = "pkcs11:object=a=b;token=my-token;id=%01?module-name=a=b";
+    let (mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(mapping.object(), Some("a=b"));
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].attribute(), "object");
+    assert_eq!(warnings[0].offset(), 14);
+    assert!(warnings[0]
+        .message()
+        .contains("SHOULD be percent-encoded as `%3D`"));
+
+    let (_mapping, warnings) =
+        parse_with_warnings("pkcs11:object=a%3Db").expect("mapping should be valid");
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+/// A `type` given without an `object` is warned about, the combination being advisory.
+#[test]
+fn type_without_object_is_warned_about() {
+    let pk11_uri = "pkcs11:token=my-token;type=cert";
+    let (_mapping, warnings) = parse_with_warnings(pk11_uri).expect("mapping should be valid");
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].attribute(), "type");
+    assert_eq!(warnings[0].kind(), WarningKind::TypeWithoutObject);
+    assert_eq!(warnings[0].offset(), 27);
+    assert_eq!(
+        warnings[0].message(),
+        "the `type=cert` attribute is given without an `object` attribute, \
+        so identifies every such object rather than a particular one."
+    );
+
+    assert_eq!(warning_count("pkcs11:object=my-key;type=cert"), 0);
+    assert_eq!(warning_count("pkcs11:object=my-key"), 0);
+}
+
+/// Strict parsing refuses whatever merits a warning, identifying the first
+/// offender within the tidied URI.
+#[test]
+fn strict_parsing_promotes_warnings() {
+    use pk11_uri_parser::{parse_strict, ViolationKind};
+
+    let compliant = "pkcs11:token=my-token;object=my-key;id=%01%02?module-name=mypkcs11";
+    assert!(parse_strict(compliant).is_ok());
+
+    let cases = [
+        ("pkcs11:object=my-key;\n\tid=abc", (24, 27)),
+        ("pkcs11:object=my-key;x-muppet=cookie", (21, 29)),
+        ("pkcs11:object=a|b", (15, 16)),
+        ("pkcs11:?module-path=mypkcs11.so", (20, 31)),
+        (
+            "pkcs11:?module-name=mypkcs11&module-path=/usr/lib/mypkcs11.so",
+            (41, 61),
+        ),
+    ];
+    for (pk11_uri, error_span) in cases {
+        let err = parse_strict(pk11_uri).expect_err("strict advisory");
+        assert_eq!(err.kind(), ViolationKind::StrictAdvisory, "{pk11_uri}");
+        assert_eq!(err.error_span(), error_span, "{pk11_uri}");
+    }
+
+    // a path (or filename) given as the `module-name` is suggested as the `module-path`:
+    let err = parse_strict("pkcs11:object=my-key?module-name=/usr/lib/libmypkcs11.so")
+        .expect_err("strict advisory");
+    assert_eq!(err.error_span(), (33, 56));
+    assert!(
+        err.to_string().ends_with(
+            "help: Move the value to the `module-path` attribute, ie, \
+            `module-path=/usr/lib/libmypkcs11.so`, as `module-name` is meant to be \
+            a system-independent module name (such as `mypkcs11`)."
+        ),
+        "{err}"
+    );
+
+    // an unencoded '=' in a path value gets its encoded form suggested:
+    let err = parse_strict("pkcs11:object=a=b;type=cert").expect_err("strict advisory");
+    assert_eq!(err.error_span(), (14, 17));
+    assert!(
+        err.to_string()
+            .ends_with("help: Replace `a=b` with `a%3Db`."),
+        "{err}"
+    );
+
+    // a `type` without an `object` gets the object's label suggested:
+    let err = parse_strict("pkcs11:type=private").expect_err("strict advisory");
+    assert_eq!(err.error_span(), (12, 19));
+    assert!(
+        err.to_string().ends_with(
+            "help: Add the object's label alongside its type, ie, `object=my-key;type=private`."
+        ),
+        "{err}"
+    );
+
+    // an `id` that isn't wholly percent-encoded gets its encoded form suggested:
+    let err = parse_strict("pkcs11:object=my-key;id=ab%01c").expect_err("strict advisory");
+    assert_eq!(err.error_span(), (24, 30));
+    assert!(
+        err.to_string()
+            .ends_with("help: Replace `ab%01c` with `%61%62%01%63`."),
+        "{err}"
+    );
+}
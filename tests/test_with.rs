@@ -0,0 +1,51 @@
+use pk11_uri_parser::{parse, parse_preserving_order};
+
+/// Deriving a mapping leaves the original intact, sharing its other values.
+#[test]
+fn with_changes_a_single_attribute() {
+    let pk11_uri = "pkcs11:token=my-token;object=my-key;v-attr=a?module-name=mypkcs11";
+    let mapping = parse(pk11_uri).expect("mapping should be valid");
+
+    let derived = mapping.with_object("other-key").with_pin_value("1234");
+    assert_eq!(derived.object(), Some("other-key"));
+    assert_eq!(derived.pin_value(), Some("1234"));
+    assert_eq!(derived.token(), mapping.token());
+    assert_eq!(derived.vendor("v-attr"), mapping.vendor("v-attr"));
+    assert_eq!(
+        derived.attribute_order(),
+        ["token", "object", "module-name", "pin-value"]
+    );
+    assert_eq!(
+        derived.to_string(),
+        "pkcs11:token=my-token;object=other-key;v-attr=a?pin-value=1234&module-name=mypkcs11"
+    );
+    assert_eq!(mapping.object(), Some("my-key"));
+    assert_eq!(mapping.pin_value(), None);
+}
+
+/// A newly given attribute joins the preserved order within its own component.
+#[test]
+fn with_keeps_preserved_order_coherent() {
+    let pk11_uri = "pkcs11:object=my-key;token=my-token?module-name=mypkcs11";
+    let mapping = parse_preserving_order(pk11_uri).expect("mapping should be valid");
+
+    assert_eq!(
+        mapping.with_token("other").to_string_preserving_order(),
+        "pkcs11:object=my-key;token=other?module-name=mypkcs11"
+    );
+    assert_eq!(
+        mapping
+            .with_type("private")
+            .with_pin_source("file:/etc/token_pin")
+            .to_string_preserving_order(),
+        "pkcs11:object=my-key;token=my-token;type=private?module-name=mypkcs11&pin-source=file:/etc/token_pin"
+    );
+    let query_only =
+        parse_preserving_order("pkcs11:?module-name=mypkcs11").expect("mapping should be valid");
+    assert_eq!(
+        query_only
+            .with_object("my-key")
+            .to_string_preserving_order(),
+        "pkcs11:object=my-key?module-name=mypkcs11"
+    );
+}